@@ -26,6 +26,12 @@ pub struct PluginSettings {
     pub ability_log: bool,
     pub social: bool,
     pub quest: bool,
+    // send movement offenders back to their last valid position instead of only logging them
+    #[serde(default)]
+    pub movement_rubber_band: bool,
+    // watch assets/lua and reload changed group, block and gadget scripts, for development
+    #[serde(default)]
+    pub lua_hot_reload: bool,
 }
 
 impl TomlConfig for GameServerConfig {
@@ -1,6 +1,7 @@
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use common::time_util;
+use nod_krai_gi_combat::stamina::persisted_stamina;
 use nod_krai_gi_data::excel;
 use nod_krai_gi_data::excel::{FetterDataConfig, FetterDataConfigKeyed};
 use nod_krai_gi_entity::common::create_fight_props_with_equip;
@@ -35,20 +36,23 @@ pub fn sync_player_data(players: Res<Players>, message_output: Res<MessageOutput
         let Some(ref player_basic_bin) = player_info.basic_bin else {
             continue;
         };
+        let (max_stamina, cur_persist_stamina, cur_temporary_stamina) =
+            persisted_stamina(player_basic_bin);
         message_output.send(
             *uid,
             "PlayerDataNotify",
             PlayerDataNotify {
                 nick_name: player_basic_bin.nickname.clone(),
                 prop_map: int_prop_map! {
-                    PROP_PLAYER_WORLD_LEVEL: 9;
+                    PROP_PLAYER_WORLD_LEVEL: player_basic_bin.world_level;
                     PROP_IS_SPRING_AUTO_USE: 1;
                     PROP_SPRING_AUTO_USE_PERCENT: 50;
                     PROP_IS_FLYABLE: 1;
                     PROP_IS_GAME_TIME_LOCKED: player_basic_bin.is_game_time_locked as i64;
                     PROP_IS_TRANSFERABLE: 1;
-                    PROP_MAX_STAMINA: 24000;
-                    PROP_CUR_PERSIST_STAMINA: 24000;
+                    PROP_MAX_STAMINA: max_stamina;
+                    PROP_CUR_PERSIST_STAMINA: cur_persist_stamina;
+                    PROP_CUR_TEMPORARY_STAMINA: cur_temporary_stamina;
                     PROP_PLAYER_LEVEL: player_basic_bin.level;
                     PROP_PLAYER_EXP: player_basic_bin.exp;
                     PROP_PLAYER_MP_SETTING_TYPE :1;
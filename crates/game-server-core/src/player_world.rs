@@ -11,7 +11,7 @@ use nod_krai_gi_command::CommandPlugin;
 use nod_krai_gi_data::{GAME_SERVER_CONFIG, REGION_LIST};
 use nod_krai_gi_entity::EntityPlugin;
 use nod_krai_gi_environment::EnvironmentPlugin;
-use nod_krai_gi_event::scene::{WorldOwnerUID, WorldVersionConfig};
+use nod_krai_gi_event::scene::{WorldLevel, WorldOwnerUID, WorldVersionConfig};
 use nod_krai_gi_event::EventRegistryPlugin;
 use nod_krai_gi_inventory::InventoryPlugin;
 use nod_krai_gi_luashell::{LuaShellPlugin, LuaShellSettings};
@@ -19,6 +19,7 @@ use nod_krai_gi_map::MapPlugin;
 use nod_krai_gi_message::{
     event::ClientMessageEvent,
     get_player_version,
+    invocation::InvocationRouterPlugin,
     output::{ClientOutput, MessageOutput},
 };
 use nod_krai_gi_pathfinding::PathfindingPlugin;
@@ -35,9 +36,22 @@ use nod_krai_gi_misc::MiscPlugin;
 
 pub struct PlayerWorld(App);
 
+// what players start at, and what saves from before world_level was stored migrate to
+const DEFAULT_WORLD_LEVEL: u32 = 9;
+
 impl PlayerWorld {
-    pub fn new(player_information: PlayerDataBin, output: ClientOutput) -> Self {
+    pub fn new(mut player_information: PlayerDataBin, output: ClientOutput) -> Self {
         let uid = player_information.uid;
+        let world_level = match player_information.basic_bin.as_mut() {
+            Some(basic_bin) => {
+                // the field decodes as 0 on older saves
+                if basic_bin.world_level == 0 {
+                    basic_bin.world_level = DEFAULT_WORLD_LEVEL;
+                }
+                basic_bin.world_level
+            }
+            None => DEFAULT_WORLD_LEVEL,
+        };
 
         let message_out = MessageOutput::new(HashMap::from([(uid, output.clone())]));
         let players = Players::from(HashMap::from([(uid, player_information)]));
@@ -47,13 +61,16 @@ impl PlayerWorld {
             .insert_resource(players)
             .add_message::<ClientMessageEvent>();
 
-        app.add_plugins(EventRegistryPlugin);
+        app.add_plugins(EventRegistryPlugin)
+            .add_plugins(InvocationRouterPlugin);
 
         app.world_mut()
             .get_resource_mut::<WorldOwnerUID>()
             .unwrap()
             .0 = uid;
 
+        app.world_mut().get_resource_mut::<WorldLevel>().unwrap().0 = world_level;
+
         let version = get_player_version!(&uid);
 
         app.world_mut()
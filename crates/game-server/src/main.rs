@@ -9,7 +9,7 @@ use game_server_core::LogicSimulator;
 use net::UdpServer;
 use nod_krai_gi_data::ability::load_ability_configs_from_bin;
 use nod_krai_gi_data::config::load_avatar_talent_configs_from_bin;
-use nod_krai_gi_data::custom::{CombinedDrop, GadgetMapping};
+use nod_krai_gi_data::custom::{CombinedDrop, GadgetMapping, MonsterPool};
 use nod_krai_gi_data::quest::quest_config::load_quest_configs_from_bin;
 use nod_krai_gi_data::scene::scene_point_config::load_scene_point_configs_from_bin;
 use nod_krai_gi_data::scene::script_cache::{
@@ -73,6 +73,10 @@ async fn main() -> Result<()> {
         tracing::info!("load_lua_vm end");
     });
 
+    if GAME_SERVER_CONFIG.plugin.lua_hot_reload {
+        std::thread::spawn(nod_krai_gi_script::script_hot_reload::init);
+    }
+
     if GAME_SERVER_CONFIG.plugin.ability {
         tokio::spawn(async {
             load_ability_configs_from_bin("assets/BinOutput").unwrap();
@@ -109,6 +113,7 @@ async fn main() -> Result<()> {
     custom::load_all("assets/custom")?;
     CombinedDrop::load("assets/custom");
     GadgetMapping::load("assets/custom");
+    MonsterPool::load("assets/custom");
 
     loop {
         if MULTI_VERSION_PROTOCOL.get().unwrap().is_empty() {
@@ -12,6 +12,7 @@ use nod_krai_gi_proto::server_only::*;
 pub fn create_default_player_information(uid: u32, nick_name: String) -> PlayerDataBin {
     const DEFAULT_TEAM: [u32; 1] = [10000046];
     const DEFAULT_LEVEL: u32 = 60;
+    const DEFAULT_WORLD_LEVEL: u32 = 9;
 
     let avatar_excel_config_collection_clone =
         std::sync::Arc::clone(avatar_excel_config_collection::get());
@@ -38,6 +39,7 @@ pub fn create_default_player_information(uid: u32, nick_name: String) -> PlayerD
         guid_counter: 0,
         basic_bin: Some(PlayerBasicCompBin {
             level: DEFAULT_LEVEL,
+            world_level: DEFAULT_WORLD_LEVEL,
             exp: 0,
             nickname: nick_name,
             is_game_time_locked: false,
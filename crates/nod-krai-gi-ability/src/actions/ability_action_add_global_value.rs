@@ -4,6 +4,8 @@ use nod_krai_gi_event::ability::ExecuteActionEvent;
 
 use crate::util::eval_option;
 
+pub(crate) const TYPE_NAME: &str = "AddGlobalValue";
+
 pub fn ability_action_add_global_value_event(
     mut events: MessageReader<ExecuteActionEvent>,
     abilities_query: Query<&InstancedAbilities>,
@@ -12,7 +14,7 @@ pub fn ability_action_add_global_value_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "AddGlobalValue" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
         let ability = match abilities_query.get(*ability_entity) {
@@ -0,0 +1,108 @@
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_entity::common::{FightProperties, InstancedAbilities, InstancedModifiers};
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+
+use crate::handler::modifier_change::apply_server_modifier;
+
+pub(crate) const TYPE_NAME: &str = "ApplyModifier";
+
+pub fn ability_action_apply_modifier_event(
+    mut events: ParamSet<(
+        MessageReader<ExecuteActionEvent>,
+        MessageWriter<ExecuteActionEvent>,
+    )>,
+    abilities_query: Query<&InstancedAbilities>,
+    mut modifiers_query: Query<&mut InstancedModifiers>,
+    mut fight_props_query: Query<&mut FightProperties>,
+) {
+    let mut on_added_events = Vec::new();
+
+    for ExecuteActionEvent(ability_index, ability_entity, action, ability_data, target_entity) in
+        events.p0().read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        let target_entity = target_entity.unwrap_or(*ability_entity);
+
+        apply_modifier_action(
+            *ability_index,
+            *ability_entity,
+            action,
+            ability_data,
+            target_entity,
+            &abilities_query,
+            &mut modifiers_query,
+            &mut fight_props_query,
+            &mut on_added_events,
+        );
+    }
+
+    events.p1().write_batch(on_added_events);
+}
+
+// shared by ApplyModifier and AttachModifier, the modifier's on_added actions are queued
+// into on_added_events since the caller still holds the action reader
+pub(crate) fn apply_modifier_action(
+    ability_index: u32,
+    ability_entity: Entity,
+    action: &nod_krai_gi_data::ability::AbilityModifierAction,
+    ability_data: &[u8],
+    target_entity: Entity,
+    abilities_query: &Query<&InstancedAbilities>,
+    modifiers_query: &mut Query<&mut InstancedModifiers>,
+    fight_props_query: &mut Query<&mut FightProperties>,
+    on_added_events: &mut Vec<ExecuteActionEvent>,
+) {
+    let ability = match abilities_query.get(ability_entity) {
+        Ok(abilities) => abilities.list.get(ability_index as usize),
+        Err(_) => None,
+    };
+    let Some(ability) = ability else {
+        tracing::debug!(target: "ability",
+            "[apply_modifier_action] Ability not found for index: {} entity: {}",
+            ability_index,
+            ability_entity
+        );
+        return;
+    };
+
+    let Ok(mut modifiers) = modifiers_query.get_mut(target_entity) else {
+        tracing::debug!(target: "ability",
+            "[apply_modifier_action] target_entity {} has no modifiers",
+            target_entity
+        );
+        return;
+    };
+
+    let source_entity = (ability_entity != target_entity).then_some(ability_entity);
+    let Some(modifier_data) = apply_server_modifier(
+        &mut modifiers,
+        ability_index,
+        ability,
+        source_entity,
+        &action.modifier_name,
+        fight_props_query.get_mut(target_entity).ok().as_deref_mut(),
+    ) else {
+        return;
+    };
+
+    tracing::debug!(target: "ability",
+        "[apply_modifier_action] {} modifier {} applied on {}",
+        action.type_name,
+        action.modifier_name,
+        target_entity
+    );
+
+    for on_added_action in modifier_data.on_added.iter() {
+        on_added_events.push(ExecuteActionEvent(
+            ability_index,
+            ability_entity,
+            on_added_action.clone(),
+            ability_data.to_vec(),
+            Some(target_entity),
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_entity::common::{FightProperties, InstancedAbilities, InstancedModifiers};
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+
+use crate::actions::ability_action_apply_modifier::apply_modifier_action;
+
+pub(crate) const TYPE_NAME: &str = "AttachModifier";
+
+// an attached modifier always lives on the entity owning the ability, whatever the target
+pub fn ability_action_attach_modifier_event(
+    mut events: ParamSet<(
+        MessageReader<ExecuteActionEvent>,
+        MessageWriter<ExecuteActionEvent>,
+    )>,
+    abilities_query: Query<&InstancedAbilities>,
+    mut modifiers_query: Query<&mut InstancedModifiers>,
+    mut fight_props_query: Query<&mut FightProperties>,
+) {
+    let mut on_added_events = Vec::new();
+
+    for ExecuteActionEvent(ability_index, ability_entity, action, ability_data, _target_entity) in
+        events.p0().read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        apply_modifier_action(
+            *ability_index,
+            *ability_entity,
+            action,
+            ability_data,
+            *ability_entity,
+            &abilities_query,
+            &mut modifiers_query,
+            &mut fight_props_query,
+            &mut on_added_events,
+        );
+    }
+
+    events.p1().write_batch(on_added_events);
+}
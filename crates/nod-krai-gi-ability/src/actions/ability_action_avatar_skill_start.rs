@@ -0,0 +1,56 @@
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_entity::avatar::SkillDepot;
+use nod_krai_gi_entity::common::{Guid, OwnerPlayerUID};
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_persistence::Players;
+
+use crate::actions::ability_action_modify_avatar_skill_cd::update_avatar_skill_map;
+
+pub(crate) const TYPE_NAME: &str = "AvatarSkillStart";
+
+// a forced skill cast starts that skill's cooldown over
+pub fn ability_action_avatar_skill_start_event(
+    mut events: MessageReader<ExecuteActionEvent>,
+    avatar_query: Query<(&Guid, &OwnerPlayerUID), With<SkillDepot>>,
+    mut players: ResMut<Players>,
+    message_output: Res<MessageOutput>,
+) {
+    for ExecuteActionEvent(_ability_index, ability_entity, action, _ability_data, target_entity) in
+        events.read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        if action.skill_id == 0 {
+            continue;
+        }
+
+        let target_entity = target_entity.unwrap_or(*ability_entity);
+
+        let Ok((guid, owner_player_uid)) = avatar_query.get(target_entity) else {
+            tracing::debug!(target: "ability",
+                "[ability_action_avatar_skill_start_event] target_entity {} is not an avatar",
+                target_entity
+            );
+            continue;
+        };
+
+        tracing::debug!(target: "ability",
+            "[ability_action_avatar_skill_start_event] avatar {} start skill {}",
+            guid.0,
+            action.skill_id
+        );
+
+        update_avatar_skill_map(
+            &mut players,
+            &message_output,
+            owner_player_uid.0,
+            guid.0,
+            &[action.skill_id],
+            |skill_bin| skill_bin.pass_cd_time = 0,
+        );
+    }
+}
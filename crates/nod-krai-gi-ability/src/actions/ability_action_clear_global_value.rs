@@ -3,6 +3,8 @@ use bevy_ecs::prelude::*;
 use nod_krai_gi_entity::common::{GlobalAbilityValues, InstancedAbilities};
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 
+pub(crate) const TYPE_NAME: &str = "ClearGlobalValue";
+
 pub fn ability_action_clear_global_value_event(
     mut events: MessageReader<ExecuteActionEvent>,
     abilities_query: Query<&InstancedAbilities>,
@@ -11,7 +13,7 @@ pub fn ability_action_clear_global_value_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "ClearGlobalValue" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
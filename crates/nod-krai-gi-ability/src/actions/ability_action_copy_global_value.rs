@@ -7,6 +7,8 @@ use nod_krai_gi_entity::common::{
 use nod_krai_gi_entity::team::TeamEntityMarker;
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 
+pub(crate) const TYPE_NAME: &str = "CopyGlobalValue";
+
 pub fn ability_action_copy_global_value_event(
     mut events: MessageReader<ExecuteActionEvent>,
     abilities_query: Query<&InstancedAbilities>,
@@ -23,7 +25,7 @@ pub fn ability_action_copy_global_value_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "CopyGlobalValue" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
@@ -8,6 +8,8 @@ use nod_krai_gi_entity::common::{
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 use nod_krai_gi_event::lua::OnClientExecuteReqEvent;
 
+pub(crate) const TYPE_NAME: &str = "ExecuteGadgetLua";
+
 pub fn ability_action_execute_gadget_lua_event(
     mut events: MessageReader<ExecuteActionEvent>,
     entities_query: Query<(
@@ -21,7 +23,7 @@ pub fn ability_action_execute_gadget_lua_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "ExecuteGadgetLua" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
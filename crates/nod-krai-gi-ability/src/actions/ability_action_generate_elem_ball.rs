@@ -0,0 +1,88 @@
+use crate::util::eval_option;
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_entity::avatar::SkillDepot;
+use nod_krai_gi_entity::common::{FightProperties, InstancedAbilities, OwnerPlayerUID};
+use nod_krai_gi_entity::transform::Transform;
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+use nod_krai_gi_event::combat::ElemBallGenerateEvent;
+use nod_krai_gi_message::get_player_version;
+use nod_krai_gi_proto::normal::AbilityActionGenerateElemBall;
+
+pub(crate) const TYPE_NAME: &str = "GenerateElemBall";
+
+// particles from avatar skills, the element is resolved from the caster's skill depot
+pub fn ability_action_generate_elem_ball_event(
+    mut events: MessageReader<ExecuteActionEvent>,
+    avatar_query: Query<
+        (
+            &InstancedAbilities,
+            &FightProperties,
+            &Transform,
+            &OwnerPlayerUID,
+        ),
+        With<SkillDepot>,
+    >,
+    mut elem_ball_generate_events: MessageWriter<ElemBallGenerateEvent>,
+) {
+    for ExecuteActionEvent(ability_index, ability_entity, action, ability_data, _target_entity) in
+        events.read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        let Ok((abilities, fight_props, transform, owner_player_uid)) =
+            avatar_query.get(*ability_entity)
+        else {
+            tracing::debug!(target: "ability",
+                "[ability_action_generate_elem_ball_event] ability_entity {} is not an avatar",
+                ability_entity
+            );
+            continue;
+        };
+        let Some(ability) = abilities.list.get(*ability_index as usize) else {
+            tracing::debug!(target: "ability",
+                "[ability_action_generate_elem_ball_event] Ability not found for index: {} entity: {}",
+                ability_index,
+                ability_entity
+            );
+            continue;
+        };
+
+        let base_energy = eval_option(ability, Some(fight_props), &action.base_energy, 1.0);
+        let ratio = eval_option(ability, Some(fight_props), &action.ratio, 1.0);
+        let count = (base_energy * ratio).round().max(1.0) as u32;
+
+        // the client reports where the ball pops out, fall back to the caster
+        let version = get_player_version!(&owner_player_uid.0);
+        let pos = nod_krai_gi_proto::dy_parser::decode_from_vec_by_name_version::<
+            AbilityActionGenerateElemBall,
+        >(
+            version.as_str(),
+            "AbilityActionGenerateElemBall",
+            ability_data,
+        )
+        .and_then(|generate_elem_ball| generate_elem_ball.pos)
+        .map(|pos| (pos.x, pos.y, pos.z))
+        .unwrap_or((
+            transform.position.x,
+            transform.position.y + 0.5,
+            transform.position.z,
+        ));
+
+        tracing::debug!(target: "ability",
+            "[ability_action_generate_elem_ball_event] entity {} config_id {} count {}",
+            ability_entity,
+            action.config_id,
+            count
+        );
+
+        elem_ball_generate_events.write(ElemBallGenerateEvent(
+            *ability_entity,
+            action.config_id,
+            count,
+            pos,
+        ));
+    }
+}
@@ -3,6 +3,8 @@ use nod_krai_gi_data::prop_type::FightPropType;
 
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 
+pub(crate) const TYPE_NAME: &str = "GetHPPaidDebts";
+
 pub fn ability_action_get_hp_paid_debts_event(
     mut events: MessageReader<ExecuteActionEvent>,
     mut fight_props_query: Query<&mut nod_krai_gi_entity::common::FightProperties>,
@@ -11,7 +13,7 @@ pub fn ability_action_get_hp_paid_debts_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "GetHPPaidDebts" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
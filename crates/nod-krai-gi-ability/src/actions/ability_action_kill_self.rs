@@ -3,6 +3,8 @@ use bevy_ecs::prelude::*;
 use nod_krai_gi_entity::common::FightProperties;
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 
+pub(crate) const TYPE_NAME: &str = "KillSelf";
+
 pub fn ability_action_kill_self_event(
     mut events: MessageReader<ExecuteActionEvent>,
     mut fight_props_query: Query<&mut FightProperties>,
@@ -10,7 +12,7 @@ pub fn ability_action_kill_self_event(
     for ExecuteActionEvent(_ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "KillSelf" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
@@ -7,6 +7,8 @@ use nod_krai_gi_entity::common::{
 };
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 
+pub(crate) const TYPE_NAME: &str = "LoseHP";
+
 pub fn ability_action_lose_hp_event(
     index: Res<EntityById>,
     mut events: MessageReader<ExecuteActionEvent>,
@@ -20,7 +22,7 @@ pub fn ability_action_lose_hp_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "LoseHP" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
         let Ok((owner_protocol_entity_id, abilities, _)) = entities_query.get(*ability_entity)
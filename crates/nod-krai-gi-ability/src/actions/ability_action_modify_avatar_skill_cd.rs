@@ -0,0 +1,159 @@
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_data::excel::avatar_skill_depot_excel_config_collection;
+use nod_krai_gi_entity::avatar::SkillDepot;
+use nod_krai_gi_entity::common::{FightProperties, Guid, InstancedAbilities, OwnerPlayerUID};
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::{AvatarSkillInfo, AvatarSkillInfoNotify};
+use nod_krai_gi_proto::server_only::AvatarSkillBin;
+
+use crate::util::eval_option;
+
+pub(crate) const TYPE_NAME: &str = "ModifyAvatarSkillCD";
+
+pub fn ability_action_modify_avatar_skill_cd_event(
+    mut events: MessageReader<ExecuteActionEvent>,
+    abilities_query: Query<&InstancedAbilities>,
+    avatar_query: Query<(
+        &Guid,
+        &OwnerPlayerUID,
+        &SkillDepot,
+        Option<&FightProperties>,
+    )>,
+    mut players: ResMut<Players>,
+    message_output: Res<MessageOutput>,
+) {
+    for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
+        events.read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        let target_entity = target_entity.unwrap_or(*ability_entity);
+
+        let ability = match abilities_query.get(*ability_entity) {
+            Ok(abilities) => abilities.list.get(*ability_index as usize).cloned(),
+            Err(_) => None,
+        };
+        let Some(ability) = ability else {
+            tracing::debug!(target: "ability",
+                "[ability_action_modify_avatar_skill_cd_event] Ability not found for index: {} entity: {}",
+                ability_index,
+                ability_entity
+            );
+            continue;
+        };
+
+        let Ok((guid, owner_player_uid, skill_depot, fight_props)) =
+            avatar_query.get(target_entity)
+        else {
+            tracing::debug!(target: "ability",
+                "[ability_action_modify_avatar_skill_cd_event] target_entity {} is not an avatar",
+                target_entity
+            );
+            continue;
+        };
+
+        // seconds, a negative delta shortens the remaining cd
+        let cd_delta = eval_option(&ability, fight_props, &action.cd_delta, 0.0);
+        let cd_ratio = eval_option(&ability, fight_props, &action.cd_ratio, 0.0);
+
+        let skill_id_list = get_skill_id_list_by_slot(skill_depot.0, &action.skill_slot);
+
+        tracing::debug!(target: "ability",
+            "[ability_action_modify_avatar_skill_cd_event] skills {:?} cd_delta: {} cd_ratio: {}",
+            skill_id_list,
+            cd_delta,
+            cd_ratio
+        );
+
+        update_avatar_skill_map(
+            &mut players,
+            &message_output,
+            owner_player_uid.0,
+            guid.0,
+            &skill_id_list,
+            |skill_bin| {
+                let full_cd_time = skill_bin.full_cd_time_list.first().copied().unwrap_or(0);
+                let passed = skill_bin.pass_cd_time as f32
+                    - cd_delta * 1000.0
+                    - cd_ratio * full_cd_time as f32;
+                skill_bin.pass_cd_time = if full_cd_time > 0 {
+                    passed.clamp(0.0, full_cd_time as f32) as u32
+                } else {
+                    passed.max(0.0) as u32
+                };
+            },
+        );
+    }
+}
+
+// skill slots follow the depot layout, 0 is the normal attack, 1 the elemental skill and
+// 2 the elemental burst
+pub(crate) fn get_skill_id_list_by_slot(skill_depot_id: u32, skill_slot: &[u32]) -> Vec<u32> {
+    let Some(skill_depot_config) =
+        avatar_skill_depot_excel_config_collection::get().get(&skill_depot_id)
+    else {
+        return vec![];
+    };
+
+    skill_slot
+        .iter()
+        .filter_map(|slot| match slot {
+            2 => Some(skill_depot_config.energy_skill),
+            _ => skill_depot_config.skills.get(*slot as usize).copied(),
+        })
+        .filter(|skill_id| *skill_id != 0)
+        .collect()
+}
+
+pub(crate) fn update_avatar_skill_map(
+    players: &mut Players,
+    message_output: &MessageOutput,
+    uid: u32,
+    guid: u64,
+    skill_id_list: &[u32],
+    update: impl Fn(&mut AvatarSkillBin),
+) {
+    if skill_id_list.is_empty() {
+        return;
+    }
+
+    let Some(avatar_bin) = players
+        .get_mut(uid)
+        .and_then(|player_info| player_info.avatar_bin.as_mut())
+        .and_then(|player_avatar_bin| player_avatar_bin.avatar_map.get_mut(&guid))
+    else {
+        tracing::debug!(target: "ability", "[update_avatar_skill_map] avatar {} not found", guid);
+        return;
+    };
+
+    for skill_id in skill_id_list {
+        update(avatar_bin.skill_map.entry(*skill_id).or_default());
+    }
+
+    message_output.send(
+        uid,
+        "AvatarSkillInfoNotify",
+        AvatarSkillInfoNotify {
+            guid,
+            skill_map: avatar_bin
+                .skill_map
+                .iter()
+                .map(|(skill_id, skill_bin)| {
+                    (
+                        *skill_id,
+                        AvatarSkillInfo {
+                            pass_cd_time: skill_bin.pass_cd_time,
+                            full_cd_time_list: skill_bin.full_cd_time_list.clone(),
+                            max_charge_count: skill_bin.max_charge_count,
+                        },
+                    )
+                })
+                .collect(),
+        },
+    );
+}
@@ -7,6 +7,8 @@ use nod_krai_gi_event::ability::ExecuteActionEvent;
 use nod_krai_gi_event::entity::{ChangeReason, EntityFightPropChangeReasonNotifyEvent};
 use nod_krai_gi_proto::normal::{ChangeHpDebtsReason, PropChangeReason};
 
+pub(crate) const TYPE_NAME: &str = "ReduceHPDebts";
+
 pub fn ability_action_reduce_hp_debts_event(
     mut events: MessageReader<ExecuteActionEvent>,
     mut fight_props_query: Query<(
@@ -19,7 +21,7 @@ pub fn ability_action_reduce_hp_debts_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "ReduceHPDebts" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
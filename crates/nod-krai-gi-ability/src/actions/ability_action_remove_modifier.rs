@@ -0,0 +1,115 @@
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_entity::common::{
+    FightProperties, InstancedAbilities, InstancedModifier, InstancedModifiers,
+};
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+
+use crate::handler::modifier_change::remove_modifier_properties;
+
+pub(crate) const TYPE_NAME: &str = "RemoveModifier";
+
+pub fn ability_action_remove_modifier_event(
+    mut events: ParamSet<(
+        MessageReader<ExecuteActionEvent>,
+        MessageWriter<ExecuteActionEvent>,
+    )>,
+    abilities_query: Query<&InstancedAbilities>,
+    mut modifiers_query: Query<&mut InstancedModifiers>,
+    mut fight_props_query: Query<&mut FightProperties>,
+) {
+    let mut on_removed_events = Vec::new();
+
+    for ExecuteActionEvent(ability_index, ability_entity, action, ability_data, target_entity) in
+        events.p0().read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        let target_entity = target_entity.unwrap_or(*ability_entity);
+        let source_entity = (*ability_entity != target_entity).then_some(*ability_entity);
+
+        // only the instances this ability put on the target
+        remove_modifiers(
+            target_entity,
+            |modifier| {
+                modifier.name == action.modifier_name
+                    && modifier.ability_index == Some(*ability_index)
+                    && modifier.target_entity == source_entity
+            },
+            ability_data,
+            &abilities_query,
+            &mut modifiers_query,
+            &mut fight_props_query,
+            &mut on_removed_events,
+        );
+    }
+
+    events.p1().write_batch(on_removed_events);
+}
+
+// shared by RemoveModifier and RemoveUniqueModifier, the on_removed actions of every dropped
+// modifier are queued into on_removed_events since the caller still holds the action reader
+pub(crate) fn remove_modifiers(
+    entity: Entity,
+    filter: impl Fn(&InstancedModifier) -> bool,
+    ability_data: &[u8],
+    abilities_query: &Query<&InstancedAbilities>,
+    modifiers_query: &mut Query<&mut InstancedModifiers>,
+    fight_props_query: &mut Query<&mut FightProperties>,
+    on_removed_events: &mut Vec<ExecuteActionEvent>,
+) {
+    let Ok(mut modifiers) = modifiers_query.get_mut(entity) else {
+        tracing::debug!(target: "ability", "[remove_modifiers] entity {} has no modifiers", entity);
+        return;
+    };
+
+    let removed_modifier_ids: Vec<u32> = modifiers
+        .modifiers
+        .iter()
+        .filter(|(_, modifier)| filter(modifier))
+        .map(|(instanced_modifier_id, _)| *instanced_modifier_id)
+        .collect();
+
+    for instanced_modifier_id in removed_modifier_ids {
+        let Some(modifier) = modifiers.modifiers.remove(&instanced_modifier_id) else {
+            continue;
+        };
+        let (Some(modifier_data), Some(ability_index)) =
+            (modifier.modifier_data, modifier.ability_index)
+        else {
+            continue;
+        };
+
+        tracing::debug!(target: "ability",
+            "[remove_modifiers] entity {} instanced_modifier_id: {} modifier {} removed",
+            entity,
+            instanced_modifier_id,
+            modifier.name
+        );
+
+        let ability_entity = modifier.target_entity.unwrap_or(entity);
+        if let Some(ability) = abilities_query
+            .get(ability_entity)
+            .ok()
+            .and_then(|abilities| abilities.list.get(ability_index as usize))
+        {
+            remove_modifier_properties(
+                modifier_data,
+                ability,
+                fight_props_query.get_mut(entity).ok().as_deref_mut(),
+            );
+        }
+
+        for on_removed_action in modifier_data.on_removed.iter() {
+            on_removed_events.push(ExecuteActionEvent(
+                ability_index,
+                ability_entity,
+                on_removed_action.clone(),
+                ability_data.to_vec(),
+                Some(entity),
+            ));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_entity::common::{FightProperties, InstancedAbilities, InstancedModifiers};
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+
+use crate::actions::ability_action_remove_modifier::remove_modifiers;
+
+pub(crate) const TYPE_NAME: &str = "RemoveUniqueModifier";
+
+// unique modifiers are keyed by name alone, whichever ability applied them. only the ones
+// the server added are dropped here, the client removes its own
+pub fn ability_action_remove_unique_modifier_event(
+    mut events: ParamSet<(
+        MessageReader<ExecuteActionEvent>,
+        MessageWriter<ExecuteActionEvent>,
+    )>,
+    abilities_query: Query<&InstancedAbilities>,
+    mut modifiers_query: Query<&mut InstancedModifiers>,
+    mut fight_props_query: Query<&mut FightProperties>,
+) {
+    let mut on_removed_events = Vec::new();
+
+    for ExecuteActionEvent(_ability_index, ability_entity, action, ability_data, target_entity) in
+        events.p0().read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        let target_entity = target_entity.unwrap_or(*ability_entity);
+
+        remove_modifiers(
+            target_entity,
+            |modifier| modifier.is_server_added() && modifier.name == action.modifier_name,
+            ability_data,
+            &abilities_query,
+            &mut modifiers_query,
+            &mut fight_props_query,
+            &mut on_removed_events,
+        );
+    }
+
+    events.p1().write_batch(on_removed_events);
+}
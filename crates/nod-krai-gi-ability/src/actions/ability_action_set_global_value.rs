@@ -5,6 +5,8 @@ use nod_krai_gi_event::ability::ExecuteActionEvent;
 
 use crate::util::eval_option;
 
+pub(crate) const TYPE_NAME: &str = "SetGlobalValue";
+
 pub fn ability_action_set_global_value_event(
     mut events: MessageReader<ExecuteActionEvent>,
     abilities_query: Query<&InstancedAbilities>,
@@ -13,7 +15,7 @@ pub fn ability_action_set_global_value_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "SetGlobalValue" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
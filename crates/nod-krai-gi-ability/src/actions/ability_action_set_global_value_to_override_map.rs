@@ -7,6 +7,8 @@ use nod_krai_gi_entity::common::{
 use nod_krai_gi_entity::team::TeamEntityMarker;
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 
+pub(crate) const TYPE_NAME: &str = "SetGlobalValueToOverrideMap";
+
 pub fn ability_action_set_global_value_to_override_map_event(
     mut events: MessageReader<ExecuteActionEvent>,
     mut abilities_query: Query<&mut InstancedAbilities>,
@@ -23,7 +25,7 @@ pub fn ability_action_set_global_value_to_override_map_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, target_entity) in
         events.read()
     {
-        if action.type_name != "SetGlobalValueToOverrideMap" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
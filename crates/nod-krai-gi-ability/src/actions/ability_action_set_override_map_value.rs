@@ -4,6 +4,8 @@ use bevy_ecs::prelude::*;
 use nod_krai_gi_entity::common::{FightProperties, InstancedAbilities};
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 
+pub(crate) const TYPE_NAME: &str = "SetOverrideMapValue";
+
 pub fn ability_action_set_override_map_value_event(
     mut events: MessageReader<ExecuteActionEvent>,
     fight_props_query: Query<&FightProperties>,
@@ -12,7 +14,7 @@ pub fn ability_action_set_override_map_value_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, _target_entity) in
         events.read()
     {
-        if action.type_name != "SetOverrideMapValue" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
@@ -4,6 +4,8 @@ use nod_krai_gi_entity::common::InstancedAbilities;
 use nod_krai_gi_event::ability::ExecuteActionEvent;
 use rand::Rng;
 
+pub(crate) const TYPE_NAME: &str = "SetRandomOverrideMapValue";
+
 pub fn ability_action_set_random_override_map_value_event(
     mut events: MessageReader<ExecuteActionEvent>,
     mut abilities_query: Query<&mut InstancedAbilities>,
@@ -11,7 +13,7 @@ pub fn ability_action_set_random_override_map_value_event(
     for ExecuteActionEvent(ability_index, ability_entity, action, _ability_data, _target_entity) in
         events.read()
     {
-        if action.type_name != "SetRandomOverrideMapValue" {
+        if action.type_name != TYPE_NAME {
             continue;
         }
 
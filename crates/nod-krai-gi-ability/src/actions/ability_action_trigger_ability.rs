@@ -0,0 +1,63 @@
+use bevy_ecs::prelude::*;
+
+use nod_krai_gi_entity::common::InstancedAbilities;
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+
+pub(crate) const TYPE_NAME: &str = "TriggerAbility";
+
+// the triggered ability's on_ability_start goes back through ExecuteActionEvent, so nested
+// TriggerAbility actions keep recursing one hop per read
+pub fn ability_action_trigger_ability_event(
+    mut events: ParamSet<(
+        MessageReader<ExecuteActionEvent>,
+        MessageWriter<ExecuteActionEvent>,
+    )>,
+    abilities_query: Query<&InstancedAbilities>,
+) {
+    let mut on_ability_start_events = Vec::new();
+
+    for ExecuteActionEvent(_ability_index, ability_entity, action, ability_data, target_entity) in
+        events.p0().read()
+    {
+        if action.type_name != TYPE_NAME {
+            continue;
+        }
+
+        let target_entity = target_entity.unwrap_or(*ability_entity);
+
+        // the ability usually sits on the target, falling back to the caster's own list
+        let found = [target_entity, *ability_entity]
+            .into_iter()
+            .find_map(|entity| {
+                let abilities = abilities_query.get(entity).ok()?;
+                let (index, ability) = abilities.find_by_ability_name(&action.ability_name)?;
+                Some((entity, index, ability.ability_data?))
+            });
+        let Some((triggered_entity, triggered_index, triggered_ability_data)) = found else {
+            tracing::debug!(target: "ability",
+                "[ability_action_trigger_ability_event] Ability {} not found on {}",
+                action.ability_name,
+                target_entity
+            );
+            continue;
+        };
+
+        tracing::debug!(target: "ability",
+            "[ability_action_trigger_ability_event] Trigger ability {} on {}",
+            action.ability_name,
+            triggered_entity
+        );
+
+        for start_action in triggered_ability_data.on_ability_start.iter() {
+            on_ability_start_events.push(ExecuteActionEvent(
+                triggered_index,
+                triggered_entity,
+                start_action.clone(),
+                ability_data.clone(),
+                Some(target_entity),
+            ));
+        }
+    }
+
+    events.p1().write_batch(on_ability_start_events);
+}
@@ -1,14 +1,34 @@
-pub(crate) mod ability_action_add_global_value;
-pub(crate) mod ability_action_add_hp_debts;
-pub(crate) mod ability_action_clear_global_value;
-pub(crate) mod ability_action_copy_global_value;
-pub(crate) mod ability_action_execute_gadget_lua;
-pub(crate) mod ability_action_get_hp_paid_debts;
-pub(crate) mod ability_action_heal_hp;
-pub(crate) mod ability_action_kill_self;
-pub(crate) mod ability_action_lose_hp;
-pub(crate) mod ability_action_reduce_hp_debts;
-pub(crate) mod ability_action_set_global_value;
-pub(crate) mod ability_action_set_global_value_to_override_map;
-pub(crate) mod ability_action_set_override_map_value;
-pub(crate) mod ability_action_set_random_override_map_value;
+// declares the action handler modules, each names the action $type its system reacts to in
+// TYPE_NAME, and collects those names so the coverage report counts what is really dispatched
+macro_rules! action_handlers {
+    ($($module:ident),* $(,)?) => {
+        $(pub(crate) mod $module;)*
+
+        pub(crate) const HANDLED_ACTION_TYPES: &[&str] = &[$($module::TYPE_NAME),*];
+    };
+}
+
+action_handlers!(
+    ability_action_add_global_value,
+    ability_action_add_hp_debts,
+    ability_action_apply_modifier,
+    ability_action_attach_modifier,
+    ability_action_avatar_skill_start,
+    ability_action_clear_global_value,
+    ability_action_copy_global_value,
+    ability_action_execute_gadget_lua,
+    ability_action_generate_elem_ball,
+    ability_action_get_hp_paid_debts,
+    ability_action_heal_hp,
+    ability_action_kill_self,
+    ability_action_lose_hp,
+    ability_action_modify_avatar_skill_cd,
+    ability_action_reduce_hp_debts,
+    ability_action_remove_modifier,
+    ability_action_remove_unique_modifier,
+    ability_action_set_global_value,
+    ability_action_set_global_value_to_override_map,
+    ability_action_set_override_map_value,
+    ability_action_set_random_override_map_value,
+    ability_action_trigger_ability,
+);
@@ -0,0 +1,70 @@
+//! Reports how much of the ability configs the server actually handles.
+//!
+//! Walks every loaded ability, its modifiers, mixins and nested actions, and lists the
+//! action and mixin types with and without a handler, the override map keys that are read
+//! but never set, and the share of action and mixin uses that reach a handler.
+//!
+//! usage: ability_coverage [--bin-root assets/BinOutput] [--min-coverage 0] [--strict]
+//!
+//! exits with 1 when the coverage is below --min-coverage, or with --strict when anything
+//! at all is unhandled or unset.
+
+use nod_krai_gi_ability::coverage;
+use nod_krai_gi_data::ability::{iter_ability_data_map, load_ability_configs_from_bin};
+
+struct CoverageOptions {
+    bin_root: String,
+    min_coverage: f32,
+    strict: bool,
+}
+
+fn parse_args() -> Option<CoverageOptions> {
+    let mut options = CoverageOptions {
+        bin_root: "assets/BinOutput".to_string(),
+        min_coverage: 0.0,
+        strict: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bin-root" => options.bin_root = args.next()?,
+            "--min-coverage" => options.min_coverage = args.next()?.parse().ok()?,
+            "--strict" => options.strict = true,
+            _ => return None,
+        }
+    }
+
+    Some(options)
+}
+
+fn main() {
+    let Some(options) = parse_args() else {
+        println!("usage: ability_coverage [--bin-root dir] [--min-coverage percent] [--strict]");
+        std::process::exit(2);
+    };
+
+    if let Err(err) = load_ability_configs_from_bin(&options.bin_root) {
+        println!(
+            "failed to load abilities from {}: {}",
+            options.bin_root, err
+        );
+        std::process::exit(2);
+    }
+
+    let report = coverage::analyze(iter_ability_data_map().map(|(_, ability_data)| ability_data));
+    print!("{}", report);
+
+    let coverage_percent = report.coverage_percent();
+    if coverage_percent < options.min_coverage {
+        println!(
+            "coverage {:.2}% is below the required {:.2}%",
+            coverage_percent, options.min_coverage
+        );
+        std::process::exit(1);
+    }
+    if options.strict && !report.is_strict_clean() {
+        println!("strict mode: unhandled types or unset override map keys left");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,343 @@
+use crate::actions::HANDLED_ACTION_TYPES;
+use crate::mixins::MixinKind;
+use nod_krai_gi_data::ability::{
+    AbilityData, AbilityMixinData, AbilityModifier, AbilityModifierAction,
+};
+use nod_krai_gi_data::dynamic_float::NumberOrInternString;
+use nod_krai_gi_data::DynamicFloat;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+#[derive(Default)]
+pub struct TypeUsage {
+    pub count: usize,
+    pub handled: bool,
+}
+
+#[derive(Default)]
+pub struct CoverageReport {
+    pub ability_count: usize,
+    pub action_types: BTreeMap<String, TypeUsage>,
+    pub mixin_types: BTreeMap<String, TypeUsage>,
+    // ability name -> override map keys a DynamicFloat reads but nothing sets
+    pub unset_override_keys: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl CoverageReport {
+    // share of action and mixin uses that reach a handler, each use counts once
+    pub fn coverage_percent(&self) -> f32 {
+        let (handled, total) = self
+            .action_types
+            .values()
+            .chain(self.mixin_types.values())
+            .fold((0, 0), |(handled, total), usage| {
+                (
+                    handled + if usage.handled { usage.count } else { 0 },
+                    total + usage.count,
+                )
+            });
+        if total == 0 {
+            return 100.0;
+        }
+        handled as f32 * 100.0 / total as f32
+    }
+
+    pub fn unhandled_action_types(&self) -> impl Iterator<Item = (&String, &TypeUsage)> {
+        self.action_types.iter().filter(|(_, usage)| !usage.handled)
+    }
+
+    pub fn unhandled_mixin_types(&self) -> impl Iterator<Item = (&String, &TypeUsage)> {
+        self.mixin_types.iter().filter(|(_, usage)| !usage.handled)
+    }
+
+    // strict mode passes only when every type has a handler and every key is set somewhere
+    pub fn is_strict_clean(&self) -> bool {
+        self.unhandled_action_types().next().is_none()
+            && self.unhandled_mixin_types().next().is_none()
+            && self.unset_override_keys.is_empty()
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "abilities: {}", self.ability_count)?;
+
+        for (title, types) in [
+            ("action types", &self.action_types),
+            ("mixin types", &self.mixin_types),
+        ] {
+            let handled_count = types.values().filter(|usage| usage.handled).count();
+            writeln!(
+                f,
+                "\n{} ({}/{} handled):",
+                title,
+                handled_count,
+                types.len()
+            )?;
+            // the most used types first, that is where a handler pays off most
+            let mut sorted: Vec<_> = types.iter().collect();
+            sorted.sort_by(|a, b| b.1.count.cmp(&a.1.count).then(a.0.cmp(b.0)));
+            for (type_name, usage) in sorted {
+                writeln!(
+                    f,
+                    "  [{}] {} x{}",
+                    if usage.handled { "x" } else { " " },
+                    type_name,
+                    usage.count
+                )?;
+            }
+        }
+
+        let unset_key_count: usize = self.unset_override_keys.values().map(BTreeSet::len).sum();
+        writeln!(
+            f,
+            "\noverride map keys referenced but never set ({} in {} abilities):",
+            unset_key_count,
+            self.unset_override_keys.len()
+        )?;
+        for (ability_name, keys) in self.unset_override_keys.iter() {
+            let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+            writeln!(f, "  {}: {}", ability_name, keys.join(", "))?;
+        }
+
+        writeln!(f, "\ncoverage: {:.2}%", self.coverage_percent())
+    }
+}
+
+pub fn analyze<'a>(abilities: impl IntoIterator<Item = &'a AbilityData>) -> CoverageReport {
+    let mut report = CoverageReport::default();
+
+    for ability_data in abilities {
+        report.ability_count += 1;
+
+        let mut walker = AbilityWalker {
+            report: &mut report,
+            referenced_keys: BTreeSet::new(),
+            set_keys: ability_data
+                .ability_specials
+                .keys()
+                .map(|key| key.as_str().to_string())
+                .collect(),
+        };
+        walker.walk_ability(ability_data);
+
+        let AbilityWalker {
+            referenced_keys,
+            set_keys,
+            ..
+        } = walker;
+        let unset_keys: BTreeSet<String> = referenced_keys.difference(&set_keys).cloned().collect();
+        if !unset_keys.is_empty() {
+            report
+                .unset_override_keys
+                .insert(ability_data.ability_name.as_str().to_string(), unset_keys);
+        }
+    }
+
+    report
+}
+
+struct AbilityWalker<'a> {
+    report: &'a mut CoverageReport,
+    referenced_keys: BTreeSet<String>,
+    set_keys: BTreeSet<String>,
+}
+
+impl AbilityWalker<'_> {
+    fn walk_ability(&mut self, ability_data: &AbilityData) {
+        for actions in [
+            &ability_data.on_added,
+            &ability_data.on_removed,
+            &ability_data.on_ability_start,
+            &ability_data.on_kill,
+            &ability_data.on_field_enter,
+            &ability_data.on_field_exit,
+            &ability_data.on_attach,
+            &ability_data.on_detach,
+            &ability_data.on_avatar_in,
+            &ability_data.on_avatar_out,
+            &ability_data.on_trigger_avatar_ray,
+            &ability_data.on_vehicle_in,
+            &ability_data.on_vehicle_out,
+        ] {
+            self.walk_actions(actions);
+        }
+
+        for mixin in ability_data.ability_mixins.iter() {
+            self.walk_mixin(mixin);
+        }
+
+        for modifier in ability_data.modifiers.values() {
+            self.walk_modifier(modifier);
+        }
+    }
+
+    fn walk_modifier(&mut self, modifier: &AbilityModifier) {
+        for actions in [
+            &modifier.on_added,
+            &modifier.on_removed,
+            &modifier.on_being_hit,
+            &modifier.on_attack_landed,
+            &modifier.on_hitting_other,
+            &modifier.on_think_interval,
+            &modifier.on_kill,
+            &modifier.on_crash,
+            &modifier.on_avatar_in,
+            &modifier.on_avatar_out,
+            &modifier.on_reconnect,
+            &modifier.on_change_authority,
+            &modifier.on_vehicle_in,
+            &modifier.on_vehicle_out,
+            &modifier.on_zone_enter,
+            &modifier.on_zone_exit,
+            &modifier.on_heal,
+            &modifier.on_being_healed,
+        ] {
+            self.walk_actions(actions);
+        }
+
+        for mixin in modifier.modifier_mixins.iter() {
+            self.walk_mixin(mixin);
+        }
+
+        if let Some(properties) = &modifier.properties {
+            for value in [
+                &properties.actor_hp_threshold_ratio,
+                &properties.actor_max_hp_ratio,
+                &properties.actor_attack_s_ratio,
+                &properties.actor_healed_add_delta,
+            ] {
+                self.walk_value(value);
+            }
+        }
+
+        for value in [
+            &modifier.duration,
+            &modifier.think_interval,
+            &modifier.element_durability,
+        ] {
+            self.walk_value(value);
+        }
+    }
+
+    fn walk_mixin(&mut self, mixin: &AbilityMixinData) {
+        let usage = self
+            .report
+            .mixin_types
+            .entry(mixin.type_name.as_str().to_string())
+            .or_default();
+        usage.count += 1;
+        usage.handled = MixinKind::from_type_name(mixin.type_name.as_str()).is_some();
+
+        for value in [
+            &mixin.speed,
+            &mixin.cost_stamina_delta,
+            &mixin.ratio,
+            &mixin.default_global_value_on_create,
+            &mixin.damage_percentage,
+            &mixin.interval,
+        ] {
+            self.walk_value(value);
+        }
+        for value_step in mixin.value_steps.iter() {
+            self.walk_dynamic_float(value_step);
+        }
+
+        self.walk_actions(&mixin.actions);
+    }
+
+    fn walk_actions(&mut self, actions: &[AbilityModifierAction]) {
+        for action in actions {
+            self.walk_action(action);
+        }
+    }
+
+    fn walk_action(&mut self, action: &AbilityModifierAction) {
+        let usage = self
+            .report
+            .action_types
+            .entry(action.type_name.as_str().to_string())
+            .or_default();
+        usage.count += 1;
+        usage.handled = HANDLED_ACTION_TYPES.contains(&action.type_name.as_str());
+
+        // the override map writers, whatever they store counts as set for the whole ability
+        if matches!(
+            action.type_name.as_str(),
+            "SetOverrideMapValue" | "SetRandomOverrideMapValue" | "SetGlobalValueToOverrideMap"
+        ) && !action.override_map_key.is_empty()
+        {
+            self.set_keys
+                .insert(action.override_map_key.as_str().to_string());
+        }
+
+        for value in [
+            &action.amount,
+            &action.amount_by_caster_attack_ratio,
+            &action.amount_by_caster_current_hp_ratio,
+            &action.amount_by_caster_max_hp_ratio,
+            &action.amount_by_get_damage,
+            &action.amount_by_target_current_hp_ratio,
+            &action.amount_by_target_max_hp_ratio,
+            &action.limbo_by_target_max_hp_ratio,
+            &action.heal_ratio,
+            &action.speed,
+            &action.min_value,
+            &action.max_value,
+            &action.target_value,
+            &action.cost_stamina_ratio,
+            &action.base_energy,
+            &action.ratio,
+            &action.param1,
+            &action.param2,
+            &action.param3,
+            &action.value,
+            &action.heal_limited_by_caster_max_hp_ratio,
+            &action.cd_delta,
+            &action.cd_ratio,
+            &action.duration,
+        ] {
+            self.walk_value(value);
+        }
+
+        self.walk_actions(&action.actions);
+        self.walk_actions(&action.success_actions);
+        self.walk_actions(&action.fail_actions);
+        if let Some(other_targets) = &action.other_targets {
+            self.walk_action(other_targets);
+        }
+    }
+
+    fn walk_value(&mut self, value: &Option<DynamicFloat>) {
+        if let Some(value) = value {
+            self.walk_dynamic_float(value);
+        }
+    }
+
+    fn walk_dynamic_float(&mut self, value: &DynamicFloat) {
+        match value {
+            DynamicFloat::Number(_) => {}
+            DynamicFloat::InternString(key) => self.reference_key(key.as_str()),
+            DynamicFloat::Array(items) => {
+                for item in items {
+                    if let NumberOrInternString::InternString(key) = item {
+                        self.reference_key(key.as_str());
+                    }
+                }
+            }
+        }
+    }
+
+    // mirrors what util::eval resolves on its own before falling back to the override map
+    fn reference_key(&mut self, key: &str) {
+        let key = key.strip_prefix('%').unwrap_or(key);
+        if key.is_empty()
+            || key.parse::<f32>().is_ok()
+            || key.starts_with("FIGHT_PROP_")
+            || matches!(key.to_uppercase().as_str(), "ADD" | "SUB" | "MUL" | "DIV")
+        {
+            return;
+        }
+        self.referenced_keys.insert(key.to_string());
+    }
+}
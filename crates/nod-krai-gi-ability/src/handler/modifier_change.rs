@@ -1,15 +1,21 @@
 use bevy_ecs::prelude::*;
+use common::string_util::InternString;
 use nod_krai_gi_data::ability::AbilityModifier;
 use nod_krai_gi_data::prop_type::FightPropType;
 use nod_krai_gi_entity::common::{
     EntityById, FightProperties, InstancedAbilities, InstancedAbility, InstancedModifier,
-    InstancedModifiers,
+    InstancedModifiers, MAX_CLIENT_MODIFIER_ID,
 };
 use nod_krai_gi_proto::normal::{AbilityMetaModifierChange, ModifierAction};
 
+use crate::modifier_timer::new_modifier_timer;
 use crate::util::{eval, get_ability_name};
 use nod_krai_gi_event::ability::*;
 
+// the most instances a Multiple stacking modifier keeps from one source, past it the oldest
+// is restarted instead
+const MAX_MULTIPLE_STACKS: usize = 10;
+
 pub fn handle_modifier_change(
     index: Res<EntityById>,
     mut events: MessageReader<ModifierChangeEvent>,
@@ -33,7 +39,9 @@ pub fn handle_modifier_change(
                 tracing::debug!(target: "ability", "[ModifierChange] AbilityInvokeEntry head is missing");
             }
             Some(head) => {
-                if head.instanced_modifier_id == 0 || head.instanced_modifier_id > 2000 {
+                if head.instanced_modifier_id == 0
+                    || head.instanced_modifier_id > MAX_CLIENT_MODIFIER_ID
+                {
                     continue;
                 }
 
@@ -235,12 +243,34 @@ pub fn handle_modifier_change(
                                         continue;
                                     }
                                     Some(ability_index) => {
-                                        let modifier_controller = InstancedModifier::new(
+                                        let mut modifier_controller = InstancedModifier::new(
                                             instanced_modifier_id,
                                             modifier_data.modifier_name,
                                             ability_index,
                                             target_entity_ref,
                                         );
+                                        modifier_controller.modifier_data = Some(modifier_data);
+                                        modifier_controller.modifier_local_id =
+                                            mod_change.modifier_local_id;
+
+                                        // the ability index points into the target's list when the ability came from there
+                                        let timer_ability = if target_entity_ref.is_none() {
+                                            this_instanced_abilities
+                                                .list
+                                                .get(ability_index as usize)
+                                                .cloned()
+                                        } else {
+                                            None
+                                        }
+                                        .unwrap_or_else(|| {
+                                            InstancedAbility::new(None, Some(ability_data))
+                                        });
+                                        modifier_controller.timer = new_modifier_timer(
+                                            modifier_data,
+                                            &timer_ability,
+                                            fight_properties.as_deref(),
+                                            Some(version),
+                                        );
 
                                         this_instanced_modifiers
                                             .insert(instanced_modifier_id, modifier_controller);
@@ -306,6 +336,73 @@ pub fn handle_modifier_change(
     }
 }
 
+// applies a modifier decided on by the server following its stacking rule, returns the
+// config when a new instance was added so the caller can run its on_added
+pub(crate) fn apply_server_modifier(
+    modifiers: &mut InstancedModifiers,
+    ability_index: u32,
+    ability: &InstancedAbility,
+    source_entity: Option<Entity>,
+    modifier_name: &InternString,
+    fight_properties: Option<&mut FightProperties>,
+) -> Option<&'static AbilityModifier> {
+    let ability_data = ability.ability_data?;
+    let Some((modifier_local_id, _, modifier_data)) =
+        ability_data.modifiers.get_full(modifier_name)
+    else {
+        tracing::debug!(target: "ability",
+            "[apply_server_modifier] Modifier {} not found in ability {}",
+            modifier_name,
+            ability_data.ability_name
+        );
+        return None;
+    };
+
+    let mut existing_modifier_ids: Vec<u32> = modifiers
+        .modifiers
+        .values()
+        .filter(|modifier| {
+            modifier.ability_index == Some(ability_index)
+                && modifier.target_entity == source_entity
+                && modifier.name == *modifier_name
+        })
+        .map(|modifier| modifier.instanced_modifier_id)
+        .collect();
+    existing_modifier_ids.sort_unstable();
+    let existing_modifier_id = existing_modifier_ids.first().copied();
+
+    match (modifier_data.stacking.as_str(), existing_modifier_id) {
+        ("Multiple" | "MultipleRefreshNoRemove", _)
+            if existing_modifier_ids.len() < MAX_MULTIPLE_STACKS => {}
+        (_, None) => {}
+        ("Unique" | "GlobalUnique", Some(_)) => return None,
+        // the refresh stackings, and a full Multiple stack, restart the oldest running
+        // instance instead of adding one
+        (_, Some(instanced_modifier_id)) => {
+            if let Some(modifier) = modifiers.get_by_id_mut(instanced_modifier_id) {
+                modifier.timer =
+                    new_modifier_timer(modifier_data, ability, fight_properties.as_deref(), None);
+            }
+            return None;
+        }
+    }
+
+    let instanced_modifier_id = modifiers.next_server_modifier_id();
+    let mut modifier = InstancedModifier::new(
+        instanced_modifier_id,
+        modifier_data.modifier_name,
+        ability_index,
+        source_entity,
+    );
+    modifier.modifier_data = Some(modifier_data);
+    modifier.modifier_local_id = modifier_local_id as i32;
+    modifier.timer = new_modifier_timer(modifier_data, ability, fight_properties.as_deref(), None);
+    modifiers.insert(instanced_modifier_id, modifier);
+
+    apply_modifier_properties(modifier_data, ability, fight_properties);
+    Some(modifier_data)
+}
+
 fn apply_modifier_properties(
     modifier: &AbilityModifier,
     ability: &InstancedAbility,
@@ -336,7 +433,7 @@ fn apply_modifier_properties(
     }
 }
 
-fn remove_modifier_properties(
+pub(crate) fn remove_modifier_properties(
     modifier: &AbilityModifier,
     ability: &InstancedAbility,
     fight_properties: Option<&mut FightProperties>,
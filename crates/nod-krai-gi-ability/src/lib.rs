@@ -3,30 +3,46 @@ use crate::server_invoke::server_invoke;
 
 use crate::actions::ability_action_add_global_value::ability_action_add_global_value_event;
 use crate::actions::ability_action_add_hp_debts::ability_action_add_hp_debts_event;
+use crate::actions::ability_action_apply_modifier::ability_action_apply_modifier_event;
+use crate::actions::ability_action_attach_modifier::ability_action_attach_modifier_event;
+use crate::actions::ability_action_avatar_skill_start::ability_action_avatar_skill_start_event;
 use crate::actions::ability_action_clear_global_value::ability_action_clear_global_value_event;
 use crate::actions::ability_action_copy_global_value::ability_action_copy_global_value_event;
 use crate::actions::ability_action_execute_gadget_lua::ability_action_execute_gadget_lua_event;
+use crate::actions::ability_action_generate_elem_ball::ability_action_generate_elem_ball_event;
 use crate::actions::ability_action_get_hp_paid_debts::ability_action_get_hp_paid_debts_event;
 use crate::actions::ability_action_heal_hp::ability_action_heal_hp_event;
 use crate::actions::ability_action_kill_self::ability_action_kill_self_event;
 use crate::actions::ability_action_lose_hp::ability_action_lose_hp_event;
+use crate::actions::ability_action_modify_avatar_skill_cd::ability_action_modify_avatar_skill_cd_event;
 use crate::actions::ability_action_reduce_hp_debts::ability_action_reduce_hp_debts_event;
+use crate::actions::ability_action_remove_modifier::ability_action_remove_modifier_event;
+use crate::actions::ability_action_remove_unique_modifier::ability_action_remove_unique_modifier_event;
 use crate::actions::ability_action_set_global_value::ability_action_set_global_value_event;
 use crate::actions::ability_action_set_global_value_to_override_map::ability_action_set_global_value_to_override_map_event;
 use crate::actions::ability_action_set_override_map_value::ability_action_set_override_map_value_event;
 use crate::actions::ability_action_set_random_override_map_value::ability_action_set_random_override_map_value_event;
-use crate::mixins::execute_mixin_system;
+use crate::actions::ability_action_trigger_ability::ability_action_trigger_ability_event;
+use crate::mixins::ability_mixin_attach_to_normal_attack::ability_mixin_attach_to_normal_attack_event;
+use crate::mixins::ability_mixin_do_action_by_element_reaction::ability_mixin_do_action_by_element_reaction_event;
+use crate::mixins::ability_mixin_do_action_by_killing::ability_mixin_do_action_by_killing_event;
+use crate::mixins::{
+    execute_mixin_system, update_ability_mixins, update_modifier_mixins, ActiveMixins,
+};
+use crate::modifier_timer::tick_modifier_timers;
+use crate::server_modifier::{notify_server_modifier_changes, AnnouncedServerModifiers};
+use crate::stamina_cost::{stamina_cost_modifier_producer, SentStaminaCostModifiers};
 
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use nod_krai_gi_entity::EntitySystemSet;
 use nod_krai_gi_event::ability::*;
 use nod_krai_gi_message::event::ClientMessageEvent;
-use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_message::invocation::InvocationSender;
 use nod_krai_gi_proto::normal::{
     AbilityInvocationsNotify, AbilityInvokeArgument, AbilityInvokeEntry,
     ClientAbilitiesInitFinishCombineNotify, ClientAbilityChangeNotify,
-    ClientAbilityInitFinishNotify, ForwardType,
+    ClientAbilityInitFinishNotify,
 };
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
@@ -39,17 +55,23 @@ pub enum AbilitySystemSet {
 }
 
 mod actions;
+pub mod coverage;
 mod enums;
 mod handler;
 mod mixins;
+mod modifier_timer;
 mod server_invoke;
+mod server_modifier;
+mod stamina_cost;
 mod util;
 
 pub struct AbilityPlugin;
 
 impl Plugin for AbilityPlugin {
     fn build(&self, app: &mut App) {
-        app
+        app.init_resource::<ActiveMixins>()
+            .init_resource::<AnnouncedServerModifiers>()
+            .init_resource::<SentStaminaCostModifiers>()
             // Define system sets with execution order
             .configure_sets(
                 Update,
@@ -81,6 +103,50 @@ impl Plugin for AbilityPlugin {
                 )
                     .chain(),
             )
+            .add_systems(
+                Update,
+                ability_action_trigger_ability_event.in_set(AbilitySystemSet::Ability),
+            )
+            .add_systems(
+                Update,
+                update_ability_mixins.in_set(AbilitySystemSet::Ability),
+            )
+            .add_systems(
+                Update,
+                ability_mixin_do_action_by_element_reaction_event.in_set(AbilitySystemSet::Ability),
+            )
+            .add_systems(
+                Update,
+                ability_mixin_do_action_by_killing_event.in_set(AbilitySystemSet::Ability),
+            )
+            .add_systems(
+                Update,
+                tick_modifier_timers.in_set(AbilitySystemSet::Modifier),
+            )
+            .add_systems(
+                Update,
+                update_modifier_mixins.in_set(AbilitySystemSet::Modifier),
+            )
+            .add_systems(
+                Update,
+                ability_mixin_attach_to_normal_attack_event.in_set(AbilitySystemSet::Modifier),
+            )
+            .add_systems(
+                Update,
+                ability_action_apply_modifier_event.in_set(AbilitySystemSet::Modifier),
+            )
+            .add_systems(
+                Update,
+                ability_action_attach_modifier_event.in_set(AbilitySystemSet::Modifier),
+            )
+            .add_systems(
+                Update,
+                ability_action_remove_modifier_event.in_set(AbilitySystemSet::Modifier),
+            )
+            .add_systems(
+                Update,
+                ability_action_remove_unique_modifier_event.in_set(AbilitySystemSet::Modifier),
+            )
             .add_systems(
                 Update,
                 ability_action_set_override_map_value_event.in_set(AbilitySystemSet::GlobalValue),
@@ -138,13 +204,33 @@ impl Plugin for AbilityPlugin {
             .add_systems(
                 Update,
                 ability_action_execute_gadget_lua_event.in_set(AbilitySystemSet::Other),
+            )
+            .add_systems(
+                Update,
+                ability_action_avatar_skill_start_event.in_set(AbilitySystemSet::Other),
+            )
+            .add_systems(
+                Update,
+                ability_action_generate_elem_ball_event.in_set(AbilitySystemSet::Other),
+            )
+            .add_systems(
+                Update,
+                ability_action_modify_avatar_skill_cd_event.in_set(AbilitySystemSet::Other),
+            )
+            .add_systems(
+                Update,
+                notify_server_modifier_changes.after(AbilitySystemSet::Other),
+            )
+            .add_systems(
+                Update,
+                stamina_cost_modifier_producer.after(AbilitySystemSet::Other),
             );
     }
 }
 
 fn on_ability_notify(
     mut events: MessageReader<ClientMessageEvent>,
-    message_output: Res<MessageOutput>,
+    mut invocation_sender: InvocationSender,
     mut add_new_ability_events: MessageWriter<AddNewAbilityEvent>,
     mut modifier_events: MessageWriter<ModifierChangeEvent>,
     mut override_param_events: MessageWriter<OverrideParamEvent>,
@@ -154,179 +240,68 @@ fn on_ability_notify(
     mut server_invoke_events: MessageWriter<ServerInvokeEvent>,
 ) {
     for message in events.read() {
-        match message.message_name() {
-            "AbilityInvocationsNotify" => {
-                if let Some(notify) = message.decode::<AbilityInvocationsNotify>() {
-                    let mut invoke_list_to_all: Vec<AbilityInvokeEntry> = Vec::new();
-                    let mut invoke_list_to_ohers: Vec<AbilityInvokeEntry> = Vec::new();
-                    let mut invoke_list_to_host: Vec<AbilityInvokeEntry> = Vec::new();
-                    for invoke in notify.invokes {
-                        match invoke.forward_type() {
-                            ForwardType::ForwardToAll => {
-                                invoke_list_to_all.push(invoke.clone());
-                            }
-                            ForwardType::ForwardToAllExceptCur
-                            | ForwardType::ForwardToAllExistExceptCur => {
-                                invoke_list_to_ohers.push(invoke.clone());
-                            }
-                            ForwardType::ForwardToHost => {
-                                invoke_list_to_host.push(invoke.clone());
-                            }
-                            _ => {}
-                        }
-                        on_ability_invoke(
-                            invoke,
-                            message.version(),
-                            &mut add_new_ability_events,
-                            &mut modifier_events,
-                            &mut override_param_events,
-                            &mut reinit_overridemap_events,
-                            &mut global_float_value_events,
-                            &mut clear_global_float_value_events,
-                            &mut server_invoke_events,
-                        );
-                    }
-                    if !invoke_list_to_all.is_empty() {
-                        message_output.send_to_all(
-                            "AbilityInvocationsNotify",
-                            AbilityInvocationsNotify {
-                                invokes: invoke_list_to_all,
-                            },
-                        );
-                    }
-
-                    if !invoke_list_to_ohers.is_empty() {
-                        message_output.send_to_others(
-                            message.sender_uid(),
-                            "AbilityInvocationsNotify",
-                            AbilityInvocationsNotify {
-                                invokes: invoke_list_to_ohers,
-                            },
-                        );
-                    }
-
-                    if !invoke_list_to_host.is_empty() {
-                        message_output.send(
-                            message.sender_uid(),
-                            "AbilityInvocationsNotify",
-                            AbilityInvocationsNotify {
-                                invokes: invoke_list_to_host,
-                            },
-                        );
-                    }
-                } else {
-                    tracing::error!("AbilityInvocationsNotify forward_type not support");
+        // init finish invokes keep their entity id, the rest forward as ability invocations
+        let entity_invoke_lists: Vec<(Option<u32>, Vec<AbilityInvokeEntry>)> =
+            match message.message_name() {
+                "AbilityInvocationsNotify" => {
+                    let Some(notify) = message.decode::<AbilityInvocationsNotify>() else {
+                        tracing::error!("failed to decode AbilityInvocationsNotify");
+                        continue;
+                    };
+                    vec![(None, notify.invokes)]
                 }
-            }
-            "ClientAbilityInitFinishNotify" => {
-                if let Some(notify) = message.decode::<ClientAbilityInitFinishNotify>() {
-                    let mut invoke_list_to_all: Vec<AbilityInvokeEntry> = Vec::new();
-                    let mut invoke_list_to_ohers: Vec<AbilityInvokeEntry> = Vec::new();
-                    let mut invoke_list_to_host: Vec<AbilityInvokeEntry> = Vec::new();
-                    for invoke in notify.invokes {
-                        match invoke.forward_type() {
-                            ForwardType::ForwardToAll => {
-                                invoke_list_to_all.push(invoke.clone());
-                            }
-                            ForwardType::ForwardToAllExceptCur
-                            | ForwardType::ForwardToAllExistExceptCur => {
-                                invoke_list_to_ohers.push(invoke.clone());
-                            }
-                            ForwardType::ForwardToHost => {
-                                invoke_list_to_host.push(invoke.clone());
-                            }
-                            _ => {}
-                        }
-                        on_ability_invoke(
-                            invoke,
-                            message.version(),
-                            &mut add_new_ability_events,
-                            &mut modifier_events,
-                            &mut override_param_events,
-                            &mut reinit_overridemap_events,
-                            &mut global_float_value_events,
-                            &mut clear_global_float_value_events,
-                            &mut server_invoke_events,
-                        );
-                    }
-                    if !invoke_list_to_all.is_empty() {
-                        message_output.send_to_all(
-                            "ClientAbilityInitFinishNotify",
-                            ClientAbilityInitFinishNotify {
-                                entity_id: notify.entity_id,
-                                invokes: invoke_list_to_all,
-                            },
-                        );
-                    }
-
-                    if !invoke_list_to_ohers.is_empty() {
-                        message_output.send_to_others(
-                            message.sender_uid(),
-                            "ClientAbilityInitFinishNotify",
-                            ClientAbilityInitFinishNotify {
-                                entity_id: notify.entity_id,
-                                invokes: invoke_list_to_ohers,
-                            },
-                        );
-                    }
-
-                    if !invoke_list_to_host.is_empty() {
-                        message_output.send(
-                            message.sender_uid(),
-                            "ClientAbilityInitFinishNotify",
-                            ClientAbilityInitFinishNotify {
-                                entity_id: notify.entity_id,
-                                invokes: invoke_list_to_host,
-                            },
-                        );
-                    }
-                } else {
-                    tracing::error!("ClientAbilityInitFinishNotify forward_type not support");
+                "ClientAbilityInitFinishNotify" => {
+                    let Some(notify) = message.decode::<ClientAbilityInitFinishNotify>() else {
+                        tracing::error!("failed to decode ClientAbilityInitFinishNotify");
+                        continue;
+                    };
+                    vec![(Some(notify.entity_id), notify.invokes)]
                 }
-            }
-            "ClientAbilitiesInitFinishCombineNotify" => {
-                if let Some(notify) = message.decode::<ClientAbilitiesInitFinishCombineNotify>() {
-                    for invoke_list in notify.entity_invoke_list {
-                        for invoke in invoke_list.invokes {
-                            on_ability_invoke(
-                                invoke,
-                                message.version(),
-                                &mut add_new_ability_events,
-                                &mut modifier_events,
-                                &mut override_param_events,
-                                &mut reinit_overridemap_events,
-                                &mut global_float_value_events,
-                                &mut clear_global_float_value_events,
-                                &mut server_invoke_events,
-                            );
-                        }
-                    }
-                } else {
-                    tracing::error!(
-                        "ClientAbilitiesInitFinishCombineNotify forward_type not support"
-                    );
+                "ClientAbilitiesInitFinishCombineNotify" => {
+                    let Some(notify) = message.decode::<ClientAbilitiesInitFinishCombineNotify>()
+                    else {
+                        tracing::error!("failed to decode ClientAbilitiesInitFinishCombineNotify");
+                        continue;
+                    };
+                    notify
+                        .entity_invoke_list
+                        .into_iter()
+                        .map(|invoke_list| (Some(invoke_list.entity_id), invoke_list.invokes))
+                        .collect()
                 }
-            }
-            "ClientAbilityChangeNotify" => {
-                if let Some(notify) = message.decode::<ClientAbilityChangeNotify>() {
-                    for invoke in notify.invokes {
-                        on_ability_invoke(
-                            invoke,
-                            message.version(),
-                            &mut add_new_ability_events,
-                            &mut modifier_events,
-                            &mut override_param_events,
-                            &mut reinit_overridemap_events,
-                            &mut global_float_value_events,
-                            &mut clear_global_float_value_events,
-                            &mut server_invoke_events,
-                        );
-                    }
-                } else {
-                    tracing::error!("ClientAbilityChangeNotify forward_type not support");
+                "ClientAbilityChangeNotify" => {
+                    let Some(notify) = message.decode::<ClientAbilityChangeNotify>() else {
+                        tracing::error!("failed to decode ClientAbilityChangeNotify");
+                        continue;
+                    };
+                    vec![(None, notify.invokes)]
+                }
+                &_ => continue,
+            };
+
+        for (entity_id, invokes) in entity_invoke_lists {
+            for invoke in invokes {
+                match entity_id {
+                    Some(entity_id) => invocation_sender.forward_ability_init_finish_invoke(
+                        message.sender_uid(),
+                        entity_id,
+                        invoke.clone(),
+                    ),
+                    None => invocation_sender
+                        .forward_ability_invoke(message.sender_uid(), invoke.clone()),
                 }
+                on_ability_invoke(
+                    invoke,
+                    message.version(),
+                    &mut add_new_ability_events,
+                    &mut modifier_events,
+                    &mut override_param_events,
+                    &mut reinit_overridemap_events,
+                    &mut global_float_value_events,
+                    &mut clear_global_float_value_events,
+                    &mut server_invoke_events,
+                );
             }
-            &_ => {}
         }
     }
 }
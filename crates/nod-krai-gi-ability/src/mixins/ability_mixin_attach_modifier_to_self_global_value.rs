@@ -0,0 +1,85 @@
+use bevy_ecs::prelude::*;
+
+use crate::actions::ability_action_remove_modifier::remove_modifiers;
+use crate::mixins::{apply_mixin_modifier, ActiveMixin, MixinContext};
+use crate::util::eval;
+
+// the highest value step reached by the global value picks the modifier to keep on self,
+// an empty step name means no modifier at that step
+pub(crate) fn on_tick(entity: Entity, active_mixin: &mut ActiveMixin, context: &mut MixinContext) {
+    let mixin = active_mixin.mixin;
+    let Some(ability) = context.ability(active_mixin) else {
+        return;
+    };
+
+    let value = context
+        .global_values_query
+        .get(entity)
+        .ok()
+        .and_then(|global_values| global_values.0.get(&mixin.global_value_key).copied())
+        .unwrap_or_default();
+    let fight_properties = context.fight_props_query.get(entity).ok();
+    let step = mixin
+        .value_steps
+        .iter()
+        .rposition(|value_step| value >= eval(ability, fight_properties, value_step, 0.0));
+    let modifier_name = step
+        .and_then(|step| mixin.modifier_name_steps.get(step))
+        .filter(|modifier_name| !modifier_name.is_empty())
+        .copied();
+
+    if modifier_name == active_mixin.applied_modifier {
+        return;
+    }
+
+    tracing::debug!(target: "ability",
+        "[AttachModifierToSelfGlobalValueMixin] entity {} {}={} step {:?} -> {:?}",
+        entity,
+        mixin.global_value_key,
+        value,
+        active_mixin.applied_modifier,
+        modifier_name
+    );
+
+    if mixin.remove_applied_modifier {
+        remove_applied_modifier(entity, active_mixin, context);
+    }
+
+    active_mixin.applied_modifier = modifier_name;
+    if let Some(modifier_name) = modifier_name {
+        apply_mixin_modifier(active_mixin, entity, &modifier_name, context);
+    }
+}
+
+pub(crate) fn on_detach(
+    entity: Entity,
+    active_mixin: &mut ActiveMixin,
+    context: &mut MixinContext,
+) {
+    remove_applied_modifier(entity, active_mixin, context);
+}
+
+fn remove_applied_modifier(
+    entity: Entity,
+    active_mixin: &mut ActiveMixin,
+    context: &mut MixinContext,
+) {
+    let Some(applied_modifier) = active_mixin.applied_modifier.take() else {
+        return;
+    };
+
+    let mut on_removed_events = Vec::new();
+    remove_modifiers(
+        entity,
+        |modifier| {
+            modifier.ability_index == Some(active_mixin.ability_index)
+                && modifier.name == applied_modifier
+        },
+        &[],
+        &context.abilities_query,
+        &mut context.modifiers_query,
+        &mut context.fight_props_query,
+        &mut on_removed_events,
+    );
+    context.execute_action_events.write_batch(on_removed_events);
+}
@@ -0,0 +1,41 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::ability::attack_tag_of_anim_event;
+use nod_krai_gi_event::ability::AttackLandedEvent;
+
+use crate::mixins::{
+    apply_mixin_modifier, mixin_modifier_names, ActiveMixins, MixinContext, MixinKind,
+};
+
+// a hit counts when its anim event is a normal attack, or matches the attack tags and anim
+// events the mixin names instead
+pub fn ability_mixin_attach_to_normal_attack_event(
+    mut events: MessageReader<AttackLandedEvent>,
+    active_mixins: Res<ActiveMixins>,
+    mut context: MixinContext,
+) {
+    for AttackLandedEvent(attacker_entity, target_entity, anim_event_id) in events.read() {
+        for active_mixin in
+            active_mixins.iter_kind(*attacker_entity, MixinKind::AttachToNormalAttack)
+        {
+            let mixin = active_mixin.mixin;
+            let is_attack = if mixin.attack_tags.is_empty() && mixin.anim_event_names.is_empty() {
+                attack_tag_of_anim_event(anim_event_id) == Some("Normal_Attack")
+            } else {
+                mixin.matches_attack(anim_event_id)
+            };
+            if !is_attack {
+                continue;
+            }
+
+            for modifier_name in mixin_modifier_names(active_mixin.mixin) {
+                tracing::debug!(target: "ability",
+                    "[AttachToNormalAttackMixin] {} attaches {} to {}",
+                    attacker_entity,
+                    modifier_name,
+                    target_entity
+                );
+                apply_mixin_modifier(active_mixin, *target_entity, &modifier_name, &mut context);
+            }
+        }
+    }
+}
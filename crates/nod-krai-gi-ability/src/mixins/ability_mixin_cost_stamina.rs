@@ -0,0 +1,23 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_event::ability::AbilityCostStaminaEvent;
+
+use crate::mixins::{ActiveMixin, MixinContext};
+use crate::util::eval_option;
+
+pub(crate) fn on_tick(entity: Entity, active_mixin: &mut ActiveMixin, context: &mut MixinContext) {
+    let Some(ability) = context.ability(active_mixin) else {
+        return;
+    };
+
+    let cost_stamina_delta = eval_option(
+        ability,
+        context.fight_props_query.get(entity).ok(),
+        &active_mixin.mixin.cost_stamina_delta,
+        0.0,
+    );
+    if cost_stamina_delta != 0.0 {
+        context
+            .cost_stamina_events
+            .write(AbilityCostStaminaEvent(entity, cost_stamina_delta));
+    }
+}
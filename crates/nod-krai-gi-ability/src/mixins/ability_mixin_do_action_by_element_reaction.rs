@@ -0,0 +1,36 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+use nod_krai_gi_event::combat::ElementReactionEvent;
+
+use crate::mixins::{ActiveMixins, MixinKind};
+
+// both sides of the reaction can carry the mixin, an empty reaction list takes any reaction
+pub fn ability_mixin_do_action_by_element_reaction_event(
+    mut events: MessageReader<ElementReactionEvent>,
+    active_mixins: Res<ActiveMixins>,
+    mut execute_action_events: MessageWriter<ExecuteActionEvent>,
+) {
+    for ElementReactionEvent(attacker_entity, defender_entity, reaction_type) in events.read() {
+        let reaction_name = format!("{:?}", reaction_type);
+
+        for entity in attacker_entity.iter().chain([defender_entity]) {
+            for active_mixin in
+                active_mixins.iter_kind(*entity, MixinKind::DoActionByElementReaction)
+            {
+                let reaction_types = &active_mixin.mixin.reaction_types;
+                if !reaction_types.is_empty()
+                    && !reaction_types.iter().any(|ty| *ty == reaction_name)
+                {
+                    continue;
+                }
+
+                tracing::debug!(target: "ability",
+                    "[DoActionByElementReactionMixin] entity {} reaction {}",
+                    entity,
+                    reaction_name
+                );
+                active_mixin.execute_actions(*defender_entity, &mut execute_action_events);
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+use nod_krai_gi_event::combat::EntityKilledEvent;
+
+use crate::mixins::{ActiveMixins, MixinKind};
+
+pub fn ability_mixin_do_action_by_killing_event(
+    mut events: MessageReader<EntityKilledEvent>,
+    active_mixins: Res<ActiveMixins>,
+    mut execute_action_events: MessageWriter<ExecuteActionEvent>,
+) {
+    for EntityKilledEvent(killer_entity, victim_entity) in events.read() {
+        let Some(killer_entity) = killer_entity else {
+            continue;
+        };
+
+        for active_mixin in active_mixins.iter_kind(*killer_entity, MixinKind::DoActionByKilling) {
+            tracing::debug!(target: "ability",
+                "[DoActionByKillingMixin] {} killed {}",
+                killer_entity,
+                victim_entity
+            );
+            active_mixin.execute_actions(*victim_entity, &mut execute_action_events);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_entity::common::DamageBonus;
+
+use crate::mixins::{ActiveMixin, MixinContext};
+use crate::util::eval_option;
+
+// the bonus only applies to hits matching the mixin's attack tags, so it is handed to the
+// damage path instead of being folded into the fight properties
+pub(crate) fn on_attach(
+    entity: Entity,
+    active_mixin: &mut ActiveMixin,
+    context: &mut MixinContext,
+) {
+    let Some(ability) = context.ability(active_mixin) else {
+        return;
+    };
+    let damage_percentage = eval_option(
+        ability,
+        context.fight_props_query.get(entity).ok(),
+        &active_mixin.mixin.damage_percentage,
+        0.0,
+    );
+    let Ok(mut damage_bonuses) = context.damage_bonuses_query.get_mut(entity) else {
+        return;
+    };
+    damage_bonuses.0.push(DamageBonus {
+        ability_entity: active_mixin.ability_entity,
+        ability_index: active_mixin.ability_index,
+        mixin: active_mixin.mixin,
+        damage_percentage,
+    });
+
+    tracing::debug!(target: "ability",
+        "[ModifyDamageMixin] entity {} damage bonus +{}",
+        entity,
+        damage_percentage
+    );
+}
+
+pub(crate) fn on_detach(
+    entity: Entity,
+    active_mixin: &mut ActiveMixin,
+    context: &mut MixinContext,
+) {
+    let Ok(mut damage_bonuses) = context.damage_bonuses_query.get_mut(entity) else {
+        return;
+    };
+    if let Some(index) = damage_bonuses.0.iter().position(|bonus| {
+        std::ptr::eq(bonus.mixin, active_mixin.mixin)
+            && bonus.ability_entity == active_mixin.ability_entity
+            && bonus.ability_index == active_mixin.ability_index
+    }) {
+        damage_bonuses.0.swap_remove(index);
+    }
+}
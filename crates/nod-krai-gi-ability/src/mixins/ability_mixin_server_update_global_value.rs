@@ -0,0 +1,30 @@
+use bevy_ecs::prelude::*;
+
+use crate::mixins::{ActiveMixin, MixinContext};
+use crate::util::eval_option;
+
+// the server owns the value, it starts from default_global_value_on_create when given
+pub(crate) fn on_attach(
+    entity: Entity,
+    active_mixin: &mut ActiveMixin,
+    context: &mut MixinContext,
+) {
+    let mixin = active_mixin.mixin;
+    if mixin.global_value_key.is_empty() || mixin.default_global_value_on_create.is_none() {
+        return;
+    }
+    let Some(ability) = context.ability(active_mixin) else {
+        return;
+    };
+
+    let value = eval_option(ability, None, &mixin.default_global_value_on_create, 0.0);
+    if let Ok(mut global_values) = context.global_values_query.get_mut(entity) {
+        tracing::debug!(target: "ability",
+            "[ServerUpdateGlobalValueMixin] entity {} {}={}",
+            entity,
+            mixin.global_value_key,
+            value
+        );
+        global_values.0.insert(mixin.global_value_key, value);
+    }
+}
@@ -0,0 +1,7 @@
+use bevy_ecs::prelude::*;
+
+use crate::mixins::{ActiveMixin, MixinContext};
+
+pub(crate) fn on_tick(entity: Entity, active_mixin: &mut ActiveMixin, context: &mut MixinContext) {
+    active_mixin.execute_actions(entity, &mut context.execute_action_events);
+}
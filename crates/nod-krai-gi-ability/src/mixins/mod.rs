@@ -1,14 +1,505 @@
+pub(crate) mod ability_mixin_attach_modifier_to_self_global_value;
+pub(crate) mod ability_mixin_attach_to_normal_attack;
+pub(crate) mod ability_mixin_cost_stamina;
+pub(crate) mod ability_mixin_do_action_by_element_reaction;
+pub(crate) mod ability_mixin_do_action_by_killing;
+pub(crate) mod ability_mixin_modify_damage;
+pub(crate) mod ability_mixin_server_update_global_value;
+pub(crate) mod ability_mixin_tile_attack;
+
+use crate::handler::modifier_change::apply_server_modifier;
+use crate::util::eval_option;
 use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemParam;
+use common::string_util::InternString;
+use nod_krai_gi_data::ability::{AbilityData, AbilityMixinData};
+use nod_krai_gi_entity::common::{
+    DamageBonuses, FightProperties, GlobalAbilityValues, InstancedAbilities, InstancedAbility,
+    InstancedModifier, InstancedModifiers, OwnerPlayerUID,
+};
 use nod_krai_gi_event::ability::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MixinKind {
+    AttachToNormalAttack,
+    DoActionByElementReaction,
+    DoActionByKilling,
+    ModifyDamage,
+    TileAttack,
+    AttachModifierToSelfGlobalValue,
+    CostStamina,
+    ServerUpdateGlobalValue,
+}
+
+impl MixinKind {
+    pub(crate) fn from_type_name(type_name: &str) -> Option<Self> {
+        match type_name {
+            "AttachToNormalAttackMixin" => Some(MixinKind::AttachToNormalAttack),
+            "DoActionByElementReactionMixin" => Some(MixinKind::DoActionByElementReaction),
+            "DoActionByKillingMixin" => Some(MixinKind::DoActionByKilling),
+            "ModifyDamageMixin" => Some(MixinKind::ModifyDamage),
+            "TileAttackMixin" => Some(MixinKind::TileAttack),
+            "AttachModifierToSelfGlobalValueMixin" => {
+                Some(MixinKind::AttachModifierToSelfGlobalValue)
+            }
+            "CostStaminaMixin" => Some(MixinKind::CostStamina),
+            "ServerUpdateGlobalValueMixin" => Some(MixinKind::ServerUpdateGlobalValue),
+            _ => None,
+        }
+    }
+
+    // None for the mixins that only react to attach, detach or combat events
+    fn tick_interval(
+        self,
+        mixin: &AbilityMixinData,
+        ability: &InstancedAbility,
+        fight_properties: Option<&FightProperties>,
+    ) -> Option<Duration> {
+        match self {
+            MixinKind::TileAttack => {
+                let interval = eval_option(ability, fight_properties, &mixin.interval, 1.0);
+                (interval > 0.0).then(|| Duration::from_secs_f32(interval))
+            }
+            // cost_stamina_delta is a per second cost
+            MixinKind::CostStamina => Some(Duration::from_secs(1)),
+            // follows the global value every frame
+            MixinKind::AttachModifierToSelfGlobalValue => Some(Duration::ZERO),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) struct ActiveMixin {
+    pub(crate) kind: MixinKind,
+    pub(crate) mixin: &'static AbilityMixinData,
+    pub(crate) ability_index: u32,
+    pub(crate) ability_entity: Entity,
+    pub(crate) tick_interval: Option<Duration>,
+    pub(crate) next_tick_at: Option<Instant>,
+    // what the attach hook changed, for the detach hook to revert
+    pub(crate) applied_value: f32,
+    pub(crate) applied_modifier: Option<InternString>,
+}
+
+impl ActiveMixin {
+    pub(crate) fn execute_actions(
+        &self,
+        target_entity: Entity,
+        execute_action_events: &mut MessageWriter<ExecuteActionEvent>,
+    ) {
+        for action in self.mixin.actions.iter() {
+            execute_action_events.write(ExecuteActionEvent(
+                self.ability_index,
+                self.ability_entity,
+                action.clone(),
+                Vec::new(),
+                Some(target_entity),
+            ));
+        }
+    }
+}
 
+// what registered a set of mixins, a modifier by its instanced id or an ability by its
+// slot and name so a replaced ability re-registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum MixinOwner {
+    Modifier(u32),
+    Ability(u32, InternString),
+}
+
+// the client runs the mixins of the entities its player owns, avatars included, and applies
+// their modifiers itself. running them here as well would count their properties twice
+pub(crate) type ServerOwned = Without<OwnerPlayerUID>;
+
+// the server side mixins of every ability and modifier on an entity
+#[derive(Resource, Default)]
+pub struct ActiveMixins(HashMap<Entity, HashMap<MixinOwner, Vec<ActiveMixin>>>);
+
+impl ActiveMixins {
+    pub(crate) fn iter_kind(
+        &self,
+        entity: Entity,
+        kind: MixinKind,
+    ) -> impl Iterator<Item = &ActiveMixin> {
+        self.0
+            .get(&entity)
+            .into_iter()
+            .flat_map(|mixins| mixins.values())
+            .flatten()
+            .filter(move |active_mixin| active_mixin.kind == kind)
+    }
+}
+
+#[derive(SystemParam)]
+pub struct MixinContext<'w, 's> {
+    pub(crate) abilities_query: Query<'w, 's, &'static InstancedAbilities>,
+    pub(crate) modifiers_query: Query<'w, 's, &'static mut InstancedModifiers>,
+    pub(crate) fight_props_query: Query<'w, 's, &'static mut FightProperties>,
+    pub(crate) global_values_query: Query<'w, 's, &'static mut GlobalAbilityValues>,
+    pub(crate) damage_bonuses_query: Query<'w, 's, &'static mut DamageBonuses>,
+    pub(crate) execute_action_events: MessageWriter<'w, ExecuteActionEvent>,
+    pub(crate) cost_stamina_events: MessageWriter<'w, AbilityCostStaminaEvent>,
+    pub(crate) server_owned: Query<'w, 's, (), ServerOwned>,
+}
+
+impl MixinContext<'_, '_> {
+    pub(crate) fn ability(&self, active_mixin: &ActiveMixin) -> Option<&InstancedAbility> {
+        self.abilities_query
+            .get(active_mixin.ability_entity)
+            .ok()
+            .and_then(|abilities| abilities.list.get(active_mixin.ability_index as usize))
+    }
+}
+
+// the mixins a client invokes directly, the registered ones run from the ability and
+// modifier lifecycles
 pub fn execute_mixin_system(mut events: MessageReader<ExecuteMixinEvent>) {
     for ExecuteMixinEvent(_ability_index, _ability_entity, mixin, _ability_data, _target_entity) in
         events.read()
     {
-        tracing::debug!(target: "ability", "[execute_mixin_system] mixin type: {}", mixin.type_name);
+        match MixinKind::from_type_name(mixin.type_name.as_str()) {
+            Some(kind) => {
+                tracing::debug!(target: "ability",
+                    "[execute_mixin_system] mixin {:?} is run by the server",
+                    kind
+                );
+            }
+            None => {
+                tracing::debug!(target: "ability",
+                    "[execute_mixin_system] mixin type: {} not handled",
+                    mixin.type_name
+                );
+            }
+        }
+    }
+}
 
-        match mixin.type_name.as_str() {
-            _ => {}
+// attaches the ability_mixins of added abilities and detaches those of removed ones
+pub fn update_ability_mixins(
+    entities: Query<(Entity, Ref<InstancedAbilities>), ServerOwned>,
+    mut active_mixins: ResMut<ActiveMixins>,
+    mut context: MixinContext,
+) {
+    let now = Instant::now();
+
+    for (entity, abilities) in entities.iter() {
+        if !abilities.is_changed() {
+            continue;
         }
+
+        let current: Vec<(MixinOwner, &'static AbilityData)> = abilities
+            .list
+            .iter()
+            .enumerate()
+            .filter_map(|(ability_index, ability)| {
+                let ability_data = ability.ability_data?;
+                Some((
+                    MixinOwner::Ability(ability_index as u32, ability_data.ability_name),
+                    ability_data,
+                ))
+            })
+            .collect();
+
+        let tracked = active_mixins.0.entry(entity).or_default();
+        let removed_owners: Vec<MixinOwner> = tracked
+            .keys()
+            .filter(|owner| {
+                matches!(owner, MixinOwner::Ability(..))
+                    && !current
+                        .iter()
+                        .any(|(current_owner, _)| current_owner == *owner)
+            })
+            .copied()
+            .collect();
+        let added: Vec<(MixinOwner, Vec<ActiveMixin>)> = current
+            .into_iter()
+            .filter(|(owner, _)| !tracked.contains_key(owner))
+            .map(|(owner, ability_data)| {
+                let MixinOwner::Ability(ability_index, _) = owner else {
+                    unreachable!();
+                };
+                (
+                    owner,
+                    new_active_mixins(&ability_data.ability_mixins, ability_index, entity),
+                )
+            })
+            .collect();
+
+        for owner in removed_owners {
+            for mut active_mixin in tracked.remove(&owner).unwrap_or_default() {
+                on_detach(entity, &mut active_mixin, &mut context);
+            }
+        }
+
+        for (owner, mut active_mixin_list) in added {
+            for active_mixin in active_mixin_list.iter_mut() {
+                on_attach(entity, active_mixin, &mut context, now);
+            }
+            tracked.insert(owner, active_mixin_list);
+        }
+    }
+}
+
+// attaches the mixins of new modifiers, detaches those of removed ones and ticks the rest
+pub fn update_modifier_mixins(
+    entities: Query<Entity, (With<InstancedModifiers>, ServerOwned)>,
+    mixin_owners: Query<(), Or<(With<InstancedModifiers>, With<InstancedAbilities>)>>,
+    mut active_mixins: ResMut<ActiveMixins>,
+    mut context: MixinContext,
+) {
+    let now = Instant::now();
+
+    // despawned entities take their mixins with them
+    active_mixins
+        .0
+        .retain(|entity, _| mixin_owners.contains(*entity));
+
+    for entity in entities.iter() {
+        let Ok(modifiers) = context.modifiers_query.get_mut(entity) else {
+            continue;
+        };
+        if !modifiers.is_changed() {
+            continue;
+        }
+
+        let tracked = active_mixins.0.entry(entity).or_default();
+        let detached_owners: Vec<MixinOwner> = tracked
+            .keys()
+            .filter(|owner| match owner {
+                MixinOwner::Modifier(instanced_modifier_id) => {
+                    !modifiers.modifiers.contains_key(instanced_modifier_id)
+                }
+                MixinOwner::Ability(..) => false,
+            })
+            .copied()
+            .collect();
+        let attached: Vec<(MixinOwner, Vec<ActiveMixin>)> = modifiers
+            .modifiers
+            .iter()
+            .filter(|(instanced_modifier_id, _)| {
+                !tracked.contains_key(&MixinOwner::Modifier(**instanced_modifier_id))
+            })
+            .map(|(instanced_modifier_id, modifier)| {
+                (
+                    MixinOwner::Modifier(*instanced_modifier_id),
+                    new_modifier_mixins(entity, modifier),
+                )
+            })
+            .collect();
+
+        for owner in detached_owners {
+            for mut active_mixin in tracked.remove(&owner).unwrap_or_default() {
+                on_detach(entity, &mut active_mixin, &mut context);
+            }
+        }
+
+        for (owner, mut active_mixin_list) in attached {
+            for active_mixin in active_mixin_list.iter_mut() {
+                on_attach(entity, active_mixin, &mut context, now);
+            }
+            tracked.insert(owner, active_mixin_list);
+        }
+    }
+
+    for (entity, tracked) in active_mixins.0.iter_mut() {
+        for active_mixin in tracked.values_mut().flatten() {
+            if active_mixin
+                .next_tick_at
+                .is_none_or(|next_tick_at| next_tick_at > now)
+            {
+                continue;
+            }
+            active_mixin.next_tick_at = active_mixin
+                .tick_interval
+                .map(|tick_interval| now + tick_interval);
+            on_tick(*entity, active_mixin, &mut context);
+        }
+    }
+}
+
+fn new_modifier_mixins(entity: Entity, modifier: &InstancedModifier) -> Vec<ActiveMixin> {
+    let (Some(modifier_data), Some(ability_index)) =
+        (modifier.modifier_data, modifier.ability_index)
+    else {
+        return Vec::new();
+    };
+
+    new_active_mixins(
+        &modifier_data.modifier_mixins,
+        ability_index,
+        modifier.target_entity.unwrap_or(entity),
+    )
+}
+
+fn new_active_mixins(
+    mixins: &'static [AbilityMixinData],
+    ability_index: u32,
+    ability_entity: Entity,
+) -> Vec<ActiveMixin> {
+    mixins
+        .iter()
+        .filter_map(|mixin| {
+            Some(ActiveMixin {
+                kind: MixinKind::from_type_name(mixin.type_name.as_str())?,
+                mixin,
+                ability_index,
+                ability_entity,
+                tick_interval: None,
+                next_tick_at: None,
+                applied_value: 0.0,
+                applied_modifier: None,
+            })
+        })
+        .collect()
+}
+
+fn on_attach(
+    entity: Entity,
+    active_mixin: &mut ActiveMixin,
+    context: &mut MixinContext,
+    now: Instant,
+) {
+    let Some(ability) = context.ability(active_mixin) else {
+        tracing::debug!(target: "ability",
+            "[on_attach] Ability not found for index: {} entity: {}",
+            active_mixin.ability_index,
+            active_mixin.ability_entity
+        );
+        return;
+    };
+
+    tracing::debug!(target: "ability",
+        "[on_attach] mixin {:?} attached on {}",
+        active_mixin.kind,
+        entity
+    );
+
+    active_mixin.tick_interval = active_mixin.kind.tick_interval(
+        active_mixin.mixin,
+        ability,
+        context.fight_props_query.get(entity).ok(),
+    );
+    active_mixin.next_tick_at = active_mixin
+        .tick_interval
+        .map(|tick_interval| now + tick_interval);
+
+    match active_mixin.kind {
+        MixinKind::ModifyDamage => {
+            ability_mixin_modify_damage::on_attach(entity, active_mixin, context)
+        }
+        MixinKind::ServerUpdateGlobalValue => {
+            ability_mixin_server_update_global_value::on_attach(entity, active_mixin, context)
+        }
+        _ => {}
+    }
+}
+
+fn on_detach(entity: Entity, active_mixin: &mut ActiveMixin, context: &mut MixinContext) {
+    tracing::debug!(target: "ability",
+        "[on_detach] mixin {:?} detached from {}",
+        active_mixin.kind,
+        entity
+    );
+
+    match active_mixin.kind {
+        MixinKind::ModifyDamage => {
+            ability_mixin_modify_damage::on_detach(entity, active_mixin, context)
+        }
+        MixinKind::AttachModifierToSelfGlobalValue => {
+            ability_mixin_attach_modifier_to_self_global_value::on_detach(
+                entity,
+                active_mixin,
+                context,
+            )
+        }
+        _ => {}
+    }
+}
+
+fn on_tick(entity: Entity, active_mixin: &mut ActiveMixin, context: &mut MixinContext) {
+    match active_mixin.kind {
+        MixinKind::TileAttack => ability_mixin_tile_attack::on_tick(entity, active_mixin, context),
+        MixinKind::CostStamina => {
+            ability_mixin_cost_stamina::on_tick(entity, active_mixin, context)
+        }
+        MixinKind::AttachModifierToSelfGlobalValue => {
+            ability_mixin_attach_modifier_to_self_global_value::on_tick(
+                entity,
+                active_mixin,
+                context,
+            )
+        }
+        _ => {}
+    }
+}
+
+// mixins name either one modifier or a list of them
+pub(crate) fn mixin_modifier_names(mixin: &AbilityMixinData) -> Vec<InternString> {
+    if let Some(modifier_name) = mixin.modifier_name.as_str() {
+        return vec![modifier_name.into()];
+    }
+    mixin
+        .modifier_name
+        .as_array()
+        .map(|modifier_names| {
+            modifier_names
+                .iter()
+                .filter_map(|modifier_name| modifier_name.as_str())
+                .map(Into::into)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// applies a modifier of the mixin's ability on target_entity, its on_added actions included.
+// a player owned target gets its modifiers from its client
+pub(crate) fn apply_mixin_modifier(
+    active_mixin: &ActiveMixin,
+    target_entity: Entity,
+    modifier_name: &InternString,
+    context: &mut MixinContext,
+) {
+    if !context.server_owned.contains(target_entity) {
+        return;
+    }
+    let Some(ability) = context
+        .abilities_query
+        .get(active_mixin.ability_entity)
+        .ok()
+        .and_then(|abilities| abilities.list.get(active_mixin.ability_index as usize))
+    else {
+        return;
+    };
+    let Ok(mut modifiers) = context.modifiers_query.get_mut(target_entity) else {
+        return;
+    };
+
+    let source_entity =
+        (active_mixin.ability_entity != target_entity).then_some(active_mixin.ability_entity);
+    let Some(modifier_data) = apply_server_modifier(
+        &mut modifiers,
+        active_mixin.ability_index,
+        ability,
+        source_entity,
+        modifier_name,
+        context
+            .fight_props_query
+            .get_mut(target_entity)
+            .ok()
+            .as_deref_mut(),
+    ) else {
+        return;
+    };
+
+    for on_added_action in modifier_data.on_added.iter() {
+        context.execute_action_events.write(ExecuteActionEvent(
+            active_mixin.ability_index,
+            active_mixin.ability_entity,
+            on_added_action.clone(),
+            Vec::new(),
+            Some(target_entity),
+        ));
     }
 }
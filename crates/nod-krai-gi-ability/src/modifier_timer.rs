@@ -0,0 +1,169 @@
+use crate::handler::modifier_change::remove_modifier_properties;
+use crate::server_modifier::forward_modifier_change;
+use crate::util::eval_option;
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::ability::AbilityModifier;
+use nod_krai_gi_entity::avatar::AvatarID;
+use nod_krai_gi_entity::common::{
+    FightProperties, InstancedAbilities, InstancedAbility, InstancedModifiers, ModifierTimer,
+    ProtocolEntityID,
+};
+use nod_krai_gi_event::ability::ExecuteActionEvent;
+use nod_krai_gi_message::invocation::InvocationSender;
+use nod_krai_gi_proto::normal::{
+    AbilityInvokeEntryHead, AbilityMetaModifierChange, ModifierAction,
+};
+use std::time::{Duration, Instant};
+
+// a non-positive duration or think interval means the modifier never expires or thinks
+pub(crate) fn new_modifier_timer(
+    modifier: &AbilityModifier,
+    ability: &InstancedAbility,
+    fight_properties: Option<&FightProperties>,
+    version: Option<&str>,
+) -> Option<ModifierTimer> {
+    let now = Instant::now();
+
+    let duration = eval_option(ability, fight_properties, &modifier.duration, 0.0);
+    let think_interval = eval_option(ability, fight_properties, &modifier.think_interval, 0.0);
+
+    let expire_at = (duration > 0.0).then(|| now + Duration::from_secs_f32(duration));
+    let think_interval = (think_interval > 0.0 && !modifier.on_think_interval.is_empty())
+        .then(|| Duration::from_secs_f32(think_interval));
+
+    if expire_at.is_none() && think_interval.is_none() {
+        return None;
+    }
+
+    Some(ModifierTimer {
+        expire_at,
+        think_interval,
+        next_think_at: think_interval.map(|think_interval| now + think_interval),
+        version: version.map(str::to_string),
+    })
+}
+
+// the client runs the think and removal of the modifiers it added to avatars, every other
+// modifier with a timer is ticked here
+pub fn tick_modifier_timers(
+    mut entities: Query<(
+        Entity,
+        &ProtocolEntityID,
+        &mut InstancedModifiers,
+        Has<AvatarID>,
+    )>,
+    abilities_query: Query<&InstancedAbilities>,
+    mut fight_props_query: Query<&mut FightProperties>,
+    mut execute_action_events: MessageWriter<ExecuteActionEvent>,
+    mut invocation_sender: InvocationSender,
+) {
+    let now = Instant::now();
+
+    for (entity, protocol_entity_id, mut modifiers, is_avatar) in entities.iter_mut() {
+        let mut expired_modifier_ids = Vec::new();
+
+        for (instanced_modifier_id, modifier) in modifiers.modifiers.iter_mut() {
+            if is_avatar && !modifier.is_server_added() {
+                continue;
+            }
+            let (Some(timer), Some(modifier_data), Some(ability_index)) = (
+                modifier.timer.as_mut(),
+                modifier.modifier_data,
+                modifier.ability_index,
+            ) else {
+                continue;
+            };
+            let ability_entity = modifier.target_entity.unwrap_or(entity);
+
+            if let (Some(think_interval), Some(next_think_at)) =
+                (timer.think_interval, timer.next_think_at.as_mut())
+            {
+                // a think falling after the expiry never happens
+                let think_until = timer.expire_at.map_or(now, |expire_at| expire_at.min(now));
+                while *next_think_at <= think_until {
+                    *next_think_at += think_interval;
+                    for action in modifier_data.on_think_interval.iter() {
+                        execute_action_events.write(ExecuteActionEvent(
+                            ability_index,
+                            ability_entity,
+                            action.clone(),
+                            Vec::new(),
+                            Some(entity),
+                        ));
+                    }
+                }
+            }
+
+            if timer.expire_at.is_some_and(|expire_at| expire_at <= now) {
+                expired_modifier_ids.push(*instanced_modifier_id);
+            }
+        }
+
+        for instanced_modifier_id in expired_modifier_ids {
+            let Some(modifier) = modifiers.modifiers.remove(&instanced_modifier_id) else {
+                continue;
+            };
+            let (Some(timer), Some(modifier_data), Some(ability_index)) = (
+                modifier.timer,
+                modifier.modifier_data,
+                modifier.ability_index,
+            ) else {
+                continue;
+            };
+
+            tracing::debug!(target: "ability",
+                "[ModifierTimer] entity {} instanced_modifier_id: {} modifier {} expired",
+                protocol_entity_id.0,
+                instanced_modifier_id,
+                modifier.name
+            );
+
+            let ability_entity = modifier.target_entity.unwrap_or(entity);
+            for action in modifier_data.on_removed.iter() {
+                execute_action_events.write(ExecuteActionEvent(
+                    ability_index,
+                    ability_entity,
+                    action.clone(),
+                    Vec::new(),
+                    Some(entity),
+                ));
+            }
+
+            // the ability lives on the entity that applied the modifier
+            let ability = abilities_query
+                .get(ability_entity)
+                .ok()
+                .and_then(|abilities| abilities.list.get(ability_index as usize));
+            if let Some(ability) = ability {
+                remove_modifier_properties(
+                    modifier_data,
+                    ability,
+                    fight_props_query.get_mut(entity).ok().as_deref_mut(),
+                );
+            }
+
+            // server added modifiers are announced by notify_server_modifier_changes
+            let Some(version) = timer.version else {
+                continue;
+            };
+            forward_modifier_change(
+                &mut invocation_sender,
+                &version,
+                protocol_entity_id.0,
+                AbilityInvokeEntryHead {
+                    instanced_ability_id: ability
+                        .and_then(|ability| ability.instanced_ability_id)
+                        .unwrap_or_default(),
+                    instanced_modifier_id,
+                    modifier_config_local_id: modifier.modifier_local_id,
+                    ..Default::default()
+                },
+                &AbilityMetaModifierChange {
+                    action: ModifierAction::Removed as i32,
+                    modifier_local_id: modifier.modifier_local_id,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_entity::common::{InstancedAbilities, InstancedModifiers, ProtocolEntityID};
+use nod_krai_gi_event::scene::WorldVersionConfig;
+use nod_krai_gi_message::invocation::InvocationSender;
+use nod_krai_gi_proto::normal::{
+    ability_string, AbilityInvokeArgument, AbilityInvokeEntry, AbilityInvokeEntryHead,
+    AbilityMetaModifierChange, AbilityString, ForwardType, ModifierAction,
+};
+use std::collections::HashMap;
+
+// what the clients were told about a server modifier, kept to announce its removal
+struct AnnouncedModifier {
+    head: AbilityInvokeEntryHead,
+    modifier_local_id: i32,
+}
+
+// server added modifiers the clients know about, per entity and instanced modifier id
+#[derive(Resource, Default)]
+pub struct AnnouncedServerModifiers(HashMap<Entity, HashMap<u32, AnnouncedModifier>>);
+
+// sender 0 is the server, the change reaches every player
+pub(crate) fn forward_modifier_change(
+    invocation_sender: &mut InvocationSender,
+    version: &str,
+    entity_id: u32,
+    head: AbilityInvokeEntryHead,
+    mod_change: &AbilityMetaModifierChange,
+) {
+    let Some(ability_data) = nod_krai_gi_proto::dy_parser::encode_to_vec_by_name_version(
+        version,
+        "AbilityMetaModifierChange",
+        mod_change,
+    ) else {
+        tracing::debug!(target: "ability",
+            "[forward_modifier_change] Failed to encode AbilityMetaModifierChange"
+        );
+        return;
+    };
+
+    invocation_sender.forward_ability_invoke(
+        0,
+        AbilityInvokeEntry {
+            entity_id,
+            argument_type: AbilityInvokeArgument::AbilityMetaModifierChange as i32,
+            forward_type: ForwardType::ForwardToAll as i32,
+            head: Some(head),
+            ability_data,
+            ..Default::default()
+        },
+    );
+}
+
+// the client hears of its own modifiers through ModifierChange, the ones the server adds and
+// drops are announced here once the frame's modifier systems have run
+pub fn notify_server_modifier_changes(
+    entities: Query<(Entity, &ProtocolEntityID, Ref<InstancedModifiers>)>,
+    abilities_query: Query<&InstancedAbilities>,
+    protocol_ids: Query<&ProtocolEntityID>,
+    world_version_config: Res<WorldVersionConfig>,
+    mut announced: ResMut<AnnouncedServerModifiers>,
+    mut invocation_sender: InvocationSender,
+) {
+    let version = world_version_config.protocol_version.as_str();
+
+    // a despawned entity takes its modifiers with it on the client as well
+    announced.0.retain(|entity, _| entities.contains(*entity));
+
+    for (entity, protocol_entity_id, modifiers) in entities.iter() {
+        if !modifiers.is_changed() {
+            continue;
+        }
+        let known = announced.0.entry(entity).or_default();
+
+        let removed_modifier_ids: Vec<u32> = known
+            .keys()
+            .filter(|instanced_modifier_id| {
+                !modifiers.modifiers.contains_key(instanced_modifier_id)
+            })
+            .copied()
+            .collect();
+        for instanced_modifier_id in removed_modifier_ids {
+            let Some(announced_modifier) = known.remove(&instanced_modifier_id) else {
+                continue;
+            };
+            forward_modifier_change(
+                &mut invocation_sender,
+                version,
+                protocol_entity_id.0,
+                announced_modifier.head,
+                &AbilityMetaModifierChange {
+                    action: ModifierAction::Removed as i32,
+                    modifier_local_id: announced_modifier.modifier_local_id,
+                    ..Default::default()
+                },
+            );
+        }
+
+        for (instanced_modifier_id, modifier) in modifiers.modifiers.iter() {
+            if !modifier.is_server_added() || known.contains_key(instanced_modifier_id) {
+                continue;
+            }
+            let Some(ability_index) = modifier.ability_index else {
+                continue;
+            };
+
+            let ability_entity = modifier.target_entity.unwrap_or(entity);
+            let ability = abilities_query
+                .get(ability_entity)
+                .ok()
+                .and_then(|abilities| abilities.list.get(ability_index as usize));
+            let ability_entity_id = protocol_ids
+                .get(ability_entity)
+                .map_or(protocol_entity_id.0, |id| id.0);
+
+            // target_id names the entity holding the ability, as in a client ModifierChange
+            let head = AbilityInvokeEntryHead {
+                instanced_ability_id: ability
+                    .and_then(|ability| ability.instanced_ability_id)
+                    .unwrap_or_default(),
+                instanced_modifier_id: *instanced_modifier_id,
+                modifier_config_local_id: modifier.modifier_local_id,
+                target_id: modifier.target_entity.map_or(0, |_| ability_entity_id),
+                ..Default::default()
+            };
+            let mod_change = AbilityMetaModifierChange {
+                action: ModifierAction::Added as i32,
+                modifier_local_id: modifier.modifier_local_id,
+                parent_ability_name: ability.and_then(|ability| ability.ability_data).map(
+                    |ability_data| AbilityString {
+                        r#type: Some(ability_string::Type::Str(
+                            ability_data.ability_name.as_str().to_string(),
+                        )),
+                    },
+                ),
+                apply_entity_id: ability_entity_id,
+                ..Default::default()
+            };
+
+            forward_modifier_change(
+                &mut invocation_sender,
+                version,
+                protocol_entity_id.0,
+                head.clone(),
+                &mod_change,
+            );
+            known.insert(
+                *instanced_modifier_id,
+                AnnouncedModifier {
+                    head,
+                    modifier_local_id: modifier.modifier_local_id,
+                },
+            );
+        }
+    }
+}
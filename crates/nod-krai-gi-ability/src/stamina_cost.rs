@@ -0,0 +1,95 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_entity::avatar::CurrentPlayerAvatarMarker;
+use nod_krai_gi_entity::common::{
+    FightProperties, InstancedAbilities, InstancedModifiers, OwnerPlayerUID,
+};
+use nod_krai_gi_event::combat::StaminaCostModifierEvent;
+use std::collections::HashMap;
+
+use crate::util::eval;
+
+// cost ratios sent per player, keyed by the modifier they came from
+#[derive(Resource, Default)]
+pub struct SentStaminaCostModifiers(HashMap<u32, HashMap<u32, f32>>);
+
+fn modifier_source(instanced_modifier_id: u32) -> String {
+    format!("modifier_{instanced_modifier_id}")
+}
+
+// talent passives reach the avatar as modifiers, the ones with a stamina
+// cost ratio on the current avatar scale what the player's stamina pays
+pub fn stamina_cost_modifier_producer(
+    current_avatars: Query<
+        (
+            Entity,
+            &OwnerPlayerUID,
+            &InstancedModifiers,
+            Option<&FightProperties>,
+        ),
+        With<CurrentPlayerAvatarMarker>,
+    >,
+    abilities_query: Query<&InstancedAbilities>,
+    mut sent: ResMut<SentStaminaCostModifiers>,
+    mut events: MessageWriter<StaminaCostModifierEvent>,
+) {
+    let mut current: HashMap<u32, HashMap<u32, f32>> = HashMap::new();
+
+    for (entity, owner_uid, modifiers, fight_properties) in current_avatars.iter() {
+        let ratios = current.entry(owner_uid.0).or_default();
+        for (instanced_modifier_id, modifier) in modifiers.modifiers.iter() {
+            let Some(cost_ratio) = modifier
+                .modifier_data
+                .and_then(|modifier_data| modifier_data.properties.as_ref())
+                .and_then(|properties| properties.actor_stamina_cost_ratio.as_ref())
+            else {
+                continue;
+            };
+            let Some(ability) = modifier.ability_index.and_then(|ability_index| {
+                abilities_query
+                    .get(modifier.target_entity.unwrap_or(entity))
+                    .ok()
+                    .and_then(|abilities| abilities.list.get(ability_index as usize))
+            }) else {
+                continue;
+            };
+
+            // the property is a delta on the cost, -0.2 pays a fifth less
+            let delta = eval(ability, fight_properties, cost_ratio, 0.0);
+            ratios.insert(*instanced_modifier_id, 1.0 + delta);
+        }
+    }
+
+    // a ratio of one takes the source off, for modifiers gone or left on a switched out avatar
+    for (uid, sent_ratios) in sent.0.iter() {
+        let ratios = current.get(uid);
+        for instanced_modifier_id in sent_ratios.keys() {
+            if ratios.is_none_or(|ratios| !ratios.contains_key(instanced_modifier_id)) {
+                events.write(StaminaCostModifierEvent(
+                    *uid,
+                    modifier_source(*instanced_modifier_id),
+                    1.0,
+                    None,
+                ));
+            }
+        }
+    }
+
+    for (uid, ratios) in current.iter() {
+        let sent_ratios = sent.0.get(uid);
+        for (instanced_modifier_id, ratio) in ratios.iter() {
+            if sent_ratios.and_then(|sent_ratios| sent_ratios.get(instanced_modifier_id))
+                != Some(ratio)
+            {
+                // the modifier's own timer ends it, so the ratio has no duration here
+                events.write(StaminaCostModifierEvent(
+                    *uid,
+                    modifier_source(*instanced_modifier_id),
+                    *ratio,
+                    None,
+                ));
+            }
+        }
+    }
+
+    sent.0 = current;
+}
@@ -0,0 +1,357 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::excel::common::{ElementReactionType, ElementType};
+
+// aura gauge is 80% of the applied units, and a 1U aura lasts 9.5s
+const AURA_TAX: f32 = 0.8;
+const DEFAULT_APPLY_UNITS: f32 = 1.0;
+const FROZEN_DECAY_PER_SEC: f32 = 0.4;
+
+#[derive(Debug, Clone)]
+pub struct AppliedElement {
+    pub element: ElementType,
+    pub gauge: f32,
+    pub decay_per_sec: f32,
+}
+
+#[derive(Component, Debug, Clone, Default)]
+pub struct ElementAura {
+    pub auras: Vec<AppliedElement>,
+    pub last_update_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ElementReaction {
+    pub reaction_type: ElementReactionType,
+    pub trigger: ElementType,
+    pub aura: ElementType,
+}
+
+impl ElementReaction {
+    // vaporize and melt scale the hit itself, 2x when the stronger side triggers
+    pub fn amplify_multiplier(&self) -> Option<f32> {
+        use ElementReactionType as R;
+        use ElementType as E;
+
+        match (self.reaction_type, self.trigger) {
+            (R::AntiFire, E::Water) | (R::Melt, E::Fire) => Some(2.0),
+            (R::AntiFire, E::Fire) | (R::Melt, E::Ice) => Some(1.5),
+            _ => None,
+        }
+    }
+
+    // the hit's damage after the reaction. only the amplify bonus can be missing from what
+    // the client reports, amplify_rate is set when it's already folded in. transformative
+    // damage is always part of the reported number
+    pub fn apply_damage(&self, damage: f32, amplify_rate: f32, element_mastery: f32) -> f32 {
+        let Some(multiplier) = self.amplify_multiplier() else {
+            return damage;
+        };
+        if amplify_rate > 0.0 {
+            return damage;
+        }
+        let mastery_bonus = 2.78 * element_mastery / (element_mastery + 1400.0);
+        damage * multiplier * (1.0 + mastery_bonus)
+    }
+}
+
+impl ElementAura {
+    pub fn decay(&mut self, now_ms: u64) {
+        let elapsed = now_ms.saturating_sub(self.last_update_ms) as f32 / 1000.0;
+        self.last_update_ms = now_ms;
+        for aura in self.auras.iter_mut() {
+            aura.gauge -= aura.decay_per_sec * elapsed;
+        }
+        self.auras.retain(|aura| aura.gauge > 0.0);
+    }
+
+    pub fn has(&self, element: ElementType) -> bool {
+        self.auras.iter().any(|aura| aura.element == element)
+    }
+
+    fn gauge_mut(&mut self, element: ElementType) -> Option<&mut AppliedElement> {
+        self.auras.iter_mut().find(|aura| aura.element == element)
+    }
+
+    fn consume(&mut self, element: ElementType, amount: f32) {
+        if let Some(aura) = self.gauge_mut(element) {
+            aura.gauge -= amount;
+        }
+        self.auras.retain(|aura| aura.gauge > 0.0);
+    }
+
+    fn attach(&mut self, element: ElementType, units: f32) {
+        let gauge = units * AURA_TAX;
+        match self.gauge_mut(element) {
+            Some(aura) => {
+                // reapplying refreshes the gauge but keeps the original decay speed
+                aura.gauge = aura.gauge.max(gauge);
+            }
+            None => self.auras.push(AppliedElement {
+                element,
+                gauge,
+                decay_per_sec: gauge / (2.5 * units + 7.0),
+            }),
+        }
+    }
+
+    // applies the element of an incoming hit and resolves the first reaction it triggers
+    pub fn apply(
+        &mut self,
+        element: ElementType,
+        units: f32,
+        now_ms: u64,
+    ) -> Option<ElementReaction> {
+        self.decay(now_ms);
+
+        let units = if units > 0.0 {
+            units
+        } else {
+            DEFAULT_APPLY_UNITS
+        };
+
+        let can_attach = matches!(
+            element,
+            ElementType::Fire
+                | ElementType::Water
+                | ElementType::Grass
+                | ElementType::Electric
+                | ElementType::Ice
+        );
+
+        let Some((aura, reaction_type, consume_rate)) = self.auras.iter().find_map(|aura| {
+            resolve_reaction(element, aura.element)
+                .map(|(reaction_type, rate)| (aura.element, reaction_type, rate))
+        }) else {
+            if can_attach {
+                self.attach(element, units);
+            }
+            return None;
+        };
+
+        match reaction_type {
+            ElementReactionType::Freeze => {
+                let frozen_units = self
+                    .gauge_mut(aura)
+                    .map(|aura| aura.gauge)
+                    .unwrap_or_default()
+                    .min(units);
+                self.consume(aura, frozen_units);
+                let frozen_gauge = 2.0 * frozen_units;
+                match self.gauge_mut(ElementType::Frozen) {
+                    Some(frozen) => frozen.gauge = frozen.gauge.max(frozen_gauge),
+                    None => self.auras.push(AppliedElement {
+                        element: ElementType::Frozen,
+                        gauge: frozen_gauge,
+                        decay_per_sec: FROZEN_DECAY_PER_SEC,
+                    }),
+                }
+            }
+            ElementReactionType::FrozenBroken => {
+                self.consume(ElementType::Frozen, f32::MAX);
+            }
+            ElementReactionType::Stream => {
+                // hydro and electro coexist and tick each other down
+                self.consume(aura, consume_rate * units);
+                self.attach(element, units);
+            }
+            _ => {
+                self.consume(aura, consume_rate * units);
+            }
+        }
+
+        Some(ElementReaction {
+            reaction_type,
+            trigger: element,
+            aura,
+        })
+    }
+}
+
+// (reaction, gauge consumed per trigger unit)
+fn resolve_reaction(trigger: ElementType, aura: ElementType) -> Option<(ElementReactionType, f32)> {
+    use ElementReactionType as R;
+    use ElementType as E;
+
+    match (trigger, aura) {
+        // AntiFire is the client's name for vaporize
+        (E::Water, E::Fire) => Some((R::AntiFire, 2.0)),
+        (E::Fire, E::Water) => Some((R::AntiFire, 0.5)),
+        (E::Fire, E::Ice) | (E::Fire, E::Frozen) => Some((R::Melt, 2.0)),
+        (E::Ice, E::Fire) => Some((R::Melt, 0.5)),
+        (E::Fire, E::Electric) | (E::Electric, E::Fire) => Some((R::Explode, 1.0)),
+        (E::Water, E::Electric) | (E::Electric, E::Water) => Some((R::Stream, 0.4)),
+        (E::Water, E::Ice) | (E::Ice, E::Water) => Some((R::Freeze, 1.0)),
+        (E::Electric, E::Ice) | (E::Ice, E::Electric) | (E::Electric, E::Frozen) => {
+            Some((R::Superconductor, 1.0))
+        }
+        (E::Wind, E::Fire) => Some((R::SwirlFire, 0.5)),
+        (E::Wind, E::Water) => Some((R::SwirlWater, 0.5)),
+        (E::Wind, E::Electric) => Some((R::SwirlElectric, 0.5)),
+        (E::Wind, E::Ice | E::Frozen) => Some((R::SwirlIce, 0.5)),
+        (E::Rock, E::Frozen) => Some((R::FrozenBroken, 1.0)),
+        (E::Rock, E::Fire) => Some((R::CrystallizeFire, 0.5)),
+        (E::Rock, E::Water) => Some((R::CrystallizeWater, 0.5)),
+        (E::Rock, E::Electric) => Some((R::CrystallizeElectric, 0.5)),
+        (E::Rock, E::Ice) => Some((R::CrystallizeIce, 0.5)),
+        (E::Fire, E::Grass) | (E::Grass, E::Fire) => Some((R::Burning, 0.5)),
+        (E::Water, E::Grass) | (E::Grass, E::Water) => Some((R::Overgrow, 0.5)),
+        (E::Electric, E::Grass) | (E::Grass, E::Electric) => Some((R::Overdose, 1.0)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attach_taxes_gauge_and_decays() {
+        let mut aura = ElementAura::default();
+        assert!(aura.apply(ElementType::Fire, 1.0, 0).is_none());
+        assert!((aura.auras[0].gauge - 0.8).abs() < 1e-6);
+
+        // a 1U aura is gone after 9.5s
+        aura.decay(9_400);
+        assert!(aura.has(ElementType::Fire));
+        aura.decay(9_600);
+        assert!(!aura.has(ElementType::Fire));
+    }
+
+    #[test]
+    fn wind_and_rock_never_attach() {
+        let mut aura = ElementAura::default();
+        assert!(aura.apply(ElementType::Wind, 1.0, 0).is_none());
+        assert!(aura.apply(ElementType::Rock, 1.0, 0).is_none());
+        assert!(aura.auras.is_empty());
+    }
+
+    #[test]
+    fn reapply_keeps_stronger_gauge() {
+        let mut aura = ElementAura::default();
+        aura.apply(ElementType::Water, 2.0, 0);
+        aura.apply(ElementType::Water, 1.0, 0);
+        assert_eq!(aura.auras.len(), 1);
+        assert!((aura.auras[0].gauge - 1.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn vaporize_consumes_by_direction() {
+        let mut strong = ElementAura::default();
+        strong.apply(ElementType::Fire, 2.0, 0);
+        let reaction = strong.apply(ElementType::Water, 0.5, 0).unwrap();
+        assert_eq!(reaction.reaction_type, ElementReactionType::AntiFire);
+        assert!((strong.auras[0].gauge - 0.6).abs() < 1e-6);
+
+        let mut weak = ElementAura::default();
+        weak.apply(ElementType::Water, 2.0, 0);
+        weak.apply(ElementType::Fire, 1.0, 0).unwrap();
+        assert!((weak.auras[0].gauge - 1.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn freeze_then_shatter() {
+        let mut aura = ElementAura::default();
+        aura.apply(ElementType::Water, 1.0, 0);
+        let reaction = aura.apply(ElementType::Ice, 1.0, 0).unwrap();
+        assert_eq!(reaction.reaction_type, ElementReactionType::Freeze);
+        assert!(aura.has(ElementType::Frozen));
+        assert!(!aura.has(ElementType::Water));
+
+        let reaction = aura.apply(ElementType::Rock, 1.0, 0).unwrap();
+        assert_eq!(reaction.reaction_type, ElementReactionType::FrozenBroken);
+        assert!(!aura.has(ElementType::Frozen));
+    }
+
+    #[test]
+    fn electro_charged_keeps_both_auras() {
+        let mut aura = ElementAura::default();
+        aura.apply(ElementType::Water, 1.0, 0);
+        let reaction = aura.apply(ElementType::Electric, 1.0, 0).unwrap();
+        assert_eq!(reaction.reaction_type, ElementReactionType::Stream);
+        assert!(aura.has(ElementType::Water));
+        assert!(aura.has(ElementType::Electric));
+    }
+
+    #[test]
+    fn swirl_and_crystallize_follow_the_aura() {
+        use ElementReactionType as R;
+        use ElementType as E;
+
+        for (aura_element, swirl, crystallize) in [
+            (E::Fire, R::SwirlFire, R::CrystallizeFire),
+            (E::Water, R::SwirlWater, R::CrystallizeWater),
+            (E::Electric, R::SwirlElectric, R::CrystallizeElectric),
+            (E::Ice, R::SwirlIce, R::CrystallizeIce),
+        ] {
+            assert_eq!(resolve_reaction(E::Wind, aura_element).unwrap().0, swirl);
+            assert_eq!(
+                resolve_reaction(E::Rock, aura_element).unwrap().0,
+                crystallize
+            );
+        }
+    }
+
+    #[test]
+    fn reaction_table() {
+        use ElementReactionType as R;
+        use ElementType as E;
+
+        let table = [
+            (E::Fire, E::Ice, Some(R::Melt)),
+            (E::Ice, E::Fire, Some(R::Melt)),
+            (E::Fire, E::Electric, Some(R::Explode)),
+            (E::Ice, E::Electric, Some(R::Superconductor)),
+            (E::Fire, E::Grass, Some(R::Burning)),
+            (E::Water, E::Grass, Some(R::Overgrow)),
+            (E::Electric, E::Grass, Some(R::Overdose)),
+            (E::Fire, E::Fire, None),
+            (E::Grass, E::Ice, None),
+            (E::Wind, E::Grass, None),
+        ];
+        for (trigger, aura, expected) in table {
+            assert_eq!(
+                resolve_reaction(trigger, aura).map(|(reaction, _)| reaction),
+                expected,
+                "{:?} on {:?}",
+                trigger,
+                aura
+            );
+        }
+    }
+
+    #[test]
+    fn reaction_damage() {
+        use ElementReactionType as R;
+        use ElementType as E;
+
+        let reaction = |reaction_type, trigger, aura| ElementReaction {
+            reaction_type,
+            trigger,
+            aura,
+        };
+
+        let forward_vaporize = reaction(R::AntiFire, E::Water, E::Fire);
+        assert_eq!(forward_vaporize.apply_damage(100.0, 0.0, 0.0), 200.0);
+        // the client already counted the amplify bonus
+        assert_eq!(forward_vaporize.apply_damage(100.0, 1.5, 0.0), 100.0);
+        let reverse_melt = reaction(R::Melt, E::Ice, E::Fire);
+        assert_eq!(reverse_melt.apply_damage(100.0, 0.0, 0.0), 150.0);
+        // 1400 em adds 2.78 * 1400 / 2800 = 139%
+        assert!((reverse_melt.apply_damage(100.0, 0.0, 1400.0) - 358.5).abs() < 1e-3);
+
+        // transformative damage comes in with the client's number as is
+        let overload = reaction(R::Explode, E::Fire, E::Electric);
+        assert_eq!(overload.apply_damage(100.0, 0.0, 2000.0), 100.0);
+        let freeze = reaction(R::Freeze, E::Ice, E::Water);
+        assert_eq!(freeze.apply_damage(100.0, 0.0, 0.0), 100.0);
+    }
+
+    #[test]
+    fn reaction_numbering_matches_client() {
+        assert_eq!(ElementReactionType::Explode as u32, 1);
+        assert_eq!(ElementReactionType::Melt as u32, 7);
+        assert_eq!(ElementReactionType::AntiFire as u32, 9);
+        assert_eq!(ElementReactionType::Superconductor as u32, 16);
+        assert_eq!(ElementReactionType::FrozenBroken as u32, 31);
+    }
+}
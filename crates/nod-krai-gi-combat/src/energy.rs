@@ -0,0 +1,370 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::custom::resolve_drop;
+use nod_krai_gi_data::excel::common::ElementType;
+use nod_krai_gi_data::excel::{
+    avatar_skill_depot_excel_config_collection, avatar_skill_excel_config_collection,
+    material_excel_config_collection, ItemUseOp,
+};
+use nod_krai_gi_data::prop_type::FightPropType;
+use nod_krai_gi_entity::avatar::{CurrentPlayerAvatarMarker, CurrentTeam, SkillDepot};
+use nod_krai_gi_entity::common::{
+    EntityById, FightProperties, LifeState, OwnerPlayerUID, ProtocolEntityID,
+};
+use nod_krai_gi_entity::monster::{get_monster_hp_drop_ids, MonsterID};
+use nod_krai_gi_entity::transform::Transform;
+use nod_krai_gi_event::combat::{AvatarEnergyAddEvent, ElemBallGenerateEvent, ElemBallPickupEvent};
+use nod_krai_gi_event::entity::{ChangeReason, EntityFightPropChangeReasonNotifyEvent};
+use nod_krai_gi_event::inventory::ItemDropEvent;
+use nod_krai_gi_message::event::ClientMessageEvent;
+use nod_krai_gi_proto::normal::{ChangeEnergyReason, EvtDoSkillSuccNotify, PropChangeReason};
+use std::collections::HashMap;
+
+// orbs sit below the particles in the material table and are worth three of them
+const ELEM_ORB_MAX_ITEM_ID: u32 = 2008;
+const ELEM_ORB_BASE_ENERGY: f32 = 3.0;
+const ELEM_PARTICLE_BASE_ENERGY: f32 = 1.0;
+const SAME_ELEMENT_RATIO: f32 = 3.0;
+const COLORLESS_RATIO: f32 = 2.0;
+const OTHER_ELEMENT_RATIO: f32 = 1.0;
+// off-field avatars lose a tenth per team member, never dropping below a tenth
+const OFF_FIELD_PENALTY_PER_MEMBER: f32 = 0.1;
+const MIN_OFF_FIELD_RATIO: f32 = 0.1;
+
+type TeamAvatarEnergy<'a> = (
+    &'a mut FightProperties,
+    &'a SkillDepot,
+    &'a OwnerPlayerUID,
+    &'a ProtocolEntityID,
+    &'a LifeState,
+    Has<CurrentPlayerAvatarMarker>,
+);
+
+// lowest hp percent seen per monster, each hp drop threshold pays out once
+#[derive(Resource, Default)]
+pub struct MonsterHpDropTracker(HashMap<Entity, f32>);
+
+fn energy_prop_types(element: ElementType) -> (FightPropType, FightPropType) {
+    use FightPropType::*;
+    match element {
+        ElementType::Fire => (FIGHT_PROP_CUR_FIRE_ENERGY, FIGHT_PROP_MAX_FIRE_ENERGY),
+        ElementType::Water => (FIGHT_PROP_CUR_WATER_ENERGY, FIGHT_PROP_MAX_WATER_ENERGY),
+        ElementType::Grass => (FIGHT_PROP_CUR_GRASS_ENERGY, FIGHT_PROP_MAX_GRASS_ENERGY),
+        ElementType::Electric => (FIGHT_PROP_CUR_ELEC_ENERGY, FIGHT_PROP_MAX_ELEC_ENERGY),
+        ElementType::Ice => (FIGHT_PROP_CUR_ICE_ENERGY, FIGHT_PROP_MAX_ICE_ENERGY),
+        ElementType::Wind => (FIGHT_PROP_CUR_WIND_ENERGY, FIGHT_PROP_MAX_WIND_ENERGY),
+        ElementType::Rock => (FIGHT_PROP_CUR_ROCK_ENERGY, FIGHT_PROP_MAX_ROCK_ENERGY),
+        _ => (FIGHT_PROP_CUR_SPECIAL_ENERGY, FIGHT_PROP_MAX_SPECIAL_ENERGY),
+    }
+}
+
+fn elem_particle_item_id(element: ElementType) -> u32 {
+    match element {
+        ElementType::Fire => 2017,
+        ElementType::Water => 2018,
+        ElementType::Grass => 2019,
+        ElementType::Electric => 2020,
+        ElementType::Wind => 2021,
+        ElementType::Ice => 2022,
+        ElementType::Rock => 2023,
+        _ => 2024,
+    }
+}
+
+// (skill id, element, cost) of the burst in a skill depot, its element is the avatar's energy element
+fn energy_skill(skill_depot_id: u32) -> Option<(u32, ElementType, f32)> {
+    let energy_skill_id = avatar_skill_depot_excel_config_collection::get()
+        .get(&skill_depot_id)?
+        .energy_skill;
+    let skill_config = avatar_skill_excel_config_collection::get().get(&energy_skill_id)?;
+    Some((
+        energy_skill_id,
+        skill_config.cost_elem_type,
+        skill_config.cost_elem_val as f32,
+    ))
+}
+
+// element and base energy of a particle or orb material
+fn elem_ball_energy(item_id: u32) -> Option<(ElementType, f32)> {
+    let material_config = material_excel_config_collection::get().get(&item_id)?;
+    let item_use = material_config
+        .item_use
+        .iter()
+        .find(|item_use| item_use.use_op == ItemUseOp::AddElemEnergy)?;
+    let element = item_use
+        .use_param
+        .first()
+        .and_then(|param| param.as_str().parse::<u32>().ok())
+        .map(ElementType::from)
+        .unwrap_or_default();
+    let base_energy = if item_id <= ELEM_ORB_MAX_ITEM_ID {
+        ELEM_ORB_BASE_ENERGY
+    } else {
+        ELEM_PARTICLE_BASE_ENERGY
+    };
+    Some((element, base_energy))
+}
+
+// returns the energy actually gained after clamping to the max
+fn change_energy(fight_props: &mut FightProperties, element: ElementType, delta: f32) -> f32 {
+    let (cur_prop, max_prop) = energy_prop_types(element);
+    let cur_energy = fight_props.get_property(cur_prop);
+    let new_energy = (cur_energy + delta).clamp(0.0, fight_props.get_property(max_prop));
+    if new_energy != cur_energy {
+        fight_props.set_property(cur_prop, new_energy);
+    }
+    new_energy - cur_energy
+}
+
+fn energy_change_reason(
+    entity_id: u32,
+    element: ElementType,
+    delta: f32,
+    reason: PropChangeReason,
+    change_energy_reason: ChangeEnergyReason,
+) -> EntityFightPropChangeReasonNotifyEvent {
+    EntityFightPropChangeReasonNotifyEvent {
+        entity_id,
+        prop_type: energy_prop_types(element).0,
+        value: delta,
+        param_list: None,
+        reason,
+        change_reason: ChangeReason::ChangeEnergyReason(change_energy_reason),
+    }
+}
+
+pub fn generate_elem_ball_system(
+    mut events: MessageReader<ElemBallGenerateEvent>,
+    avatars: Query<(&SkillDepot, &OwnerPlayerUID)>,
+    mut item_drop_events: MessageWriter<ItemDropEvent>,
+) {
+    for ElemBallGenerateEvent(avatar_entity, config_id, count, pos) in events.read() {
+        let Ok((skill_depot, owner_player_uid)) = avatars.get(*avatar_entity) else {
+            continue;
+        };
+
+        let item_id = if elem_ball_energy(*config_id).is_some() {
+            *config_id
+        } else {
+            let element = energy_skill(skill_depot.0)
+                .map(|(_, element, _)| element)
+                .unwrap_or_default();
+            elem_particle_item_id(element)
+        };
+
+        tracing::debug!(
+            "uid {} generates {} elem balls of item {}",
+            owner_player_uid.0,
+            count,
+            item_id
+        );
+        item_drop_events.write(ItemDropEvent(
+            owner_player_uid.0,
+            Some(*pos),
+            vec![(item_id, 1); *count as usize],
+        ));
+    }
+}
+
+pub fn monster_hp_drop_system(
+    monsters: Query<(Entity, &MonsterID, &FightProperties, &Transform), Changed<FightProperties>>,
+    mut removed_monsters: RemovedComponents<MonsterID>,
+    mut tracker: ResMut<MonsterHpDropTracker>,
+    mut item_drop_events: MessageWriter<ItemDropEvent>,
+) {
+    for entity in removed_monsters.read() {
+        tracker.0.remove(&entity);
+    }
+
+    for (entity, monster_id, fight_props, transform) in monsters.iter() {
+        let max_hp = fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP);
+        if max_hp <= 0.0 {
+            continue;
+        }
+        let hp_percent =
+            (fight_props.get_property(FightPropType::FIGHT_PROP_CUR_HP) / max_hp * 100.0).max(0.0);
+
+        let Some(lowest_percent) = tracker.0.get_mut(&entity) else {
+            tracker.0.insert(entity, hp_percent);
+            continue;
+        };
+        if hp_percent >= *lowest_percent {
+            continue;
+        }
+        let from_percent = std::mem::replace(lowest_percent, hp_percent);
+
+        let drop_vec: Vec<(u32, u32)> =
+            get_monster_hp_drop_ids(monster_id.0, from_percent, hp_percent)
+                .into_iter()
+                .flat_map(|drop_id| resolve_drop(drop_id, 1))
+                .collect();
+        if drop_vec.is_empty() {
+            continue;
+        }
+
+        tracing::debug!(
+            "monster {} hp {} -> {}, hp drop_vec is {:?}",
+            monster_id.0,
+            from_percent,
+            hp_percent,
+            drop_vec
+        );
+        item_drop_events.write(ItemDropEvent(
+            0,
+            Some((
+                transform.position.x,
+                transform.position.y + 0.5,
+                transform.position.z,
+            )),
+            drop_vec,
+        ));
+    }
+}
+
+pub fn elem_ball_pickup_system(
+    mut events: MessageReader<ElemBallPickupEvent>,
+    mut avatars: Query<TeamAvatarEnergy, With<CurrentTeam>>,
+    mut reason_events: MessageWriter<EntityFightPropChangeReasonNotifyEvent>,
+) {
+    for ElemBallPickupEvent(uid, item_id, count) in events.read() {
+        let Some((ball_element, base_energy)) = elem_ball_energy(*item_id) else {
+            tracing::debug!("item {} is not an elem ball", item_id);
+            continue;
+        };
+
+        let team_size = avatars
+            .iter()
+            .filter(|(_, _, owner_player_uid, ..)| owner_player_uid.0 == *uid)
+            .count();
+        let off_field_ratio =
+            (1.0 - OFF_FIELD_PENALTY_PER_MEMBER * team_size as f32).max(MIN_OFF_FIELD_RATIO);
+
+        for (mut fight_props, skill_depot, _, entity_id, life_state, is_cur_avatar) in avatars
+            .iter_mut()
+            .filter(|(_, _, owner_player_uid, ..)| owner_player_uid.0 == *uid)
+        {
+            if *life_state == LifeState::Dead {
+                continue;
+            }
+            let Some((_, avatar_element, _)) = energy_skill(skill_depot.0) else {
+                continue;
+            };
+
+            let element_ratio = if ball_element == ElementType::None {
+                COLORLESS_RATIO
+            } else if ball_element == avatar_element {
+                SAME_ELEMENT_RATIO
+            } else {
+                OTHER_ELEMENT_RATIO
+            };
+            let field_ratio = if is_cur_avatar { 1.0 } else { off_field_ratio };
+            let charge_efficiency =
+                1.0 + fight_props.get_property(FightPropType::FIGHT_PROP_CHARGE_EFFICIENCY);
+            let energy =
+                base_energy * *count as f32 * element_ratio * field_ratio * charge_efficiency;
+
+            let delta = change_energy(&mut fight_props, avatar_element, energy);
+            if delta <= 0.0 {
+                continue;
+            }
+            reason_events.write(energy_change_reason(
+                entity_id.0,
+                avatar_element,
+                delta,
+                PropChangeReason::PropChangeEnergyBall,
+                ChangeEnergyReason::ChangeEnergyBall,
+            ));
+        }
+    }
+}
+
+pub fn avatar_energy_add_system(
+    mut events: MessageReader<AvatarEnergyAddEvent>,
+    mut avatars: Query<
+        (
+            &mut FightProperties,
+            &SkillDepot,
+            &OwnerPlayerUID,
+            &ProtocolEntityID,
+        ),
+        With<CurrentPlayerAvatarMarker>,
+    >,
+    mut reason_events: MessageWriter<EntityFightPropChangeReasonNotifyEvent>,
+) {
+    for AvatarEnergyAddEvent(uid, energy) in events.read() {
+        let Some((mut fight_props, skill_depot, _, entity_id)) = avatars
+            .iter_mut()
+            .find(|(_, _, owner_player_uid, _)| owner_player_uid.0 == *uid)
+        else {
+            continue;
+        };
+        let Some((_, element, _)) = energy_skill(skill_depot.0) else {
+            continue;
+        };
+
+        let energy =
+            energy.unwrap_or_else(|| fight_props.get_property(energy_prop_types(element).1));
+        let delta = change_energy(&mut fight_props, element, energy);
+        if delta == 0.0 {
+            continue;
+        }
+        reason_events.write(energy_change_reason(
+            entity_id.0,
+            element,
+            delta,
+            PropChangeReason::PropChangeNone,
+            ChangeEnergyReason::ChangeEnergyQuest,
+        ));
+    }
+}
+
+// casting the burst drains its cost, the client only reports the successful cast
+pub fn handle_skill_energy_cost(
+    index: Res<EntityById>,
+    mut events: MessageReader<ClientMessageEvent>,
+    mut avatars: Query<(&mut FightProperties, &SkillDepot, &OwnerPlayerUID)>,
+    mut reason_events: MessageWriter<EntityFightPropChangeReasonNotifyEvent>,
+) {
+    for message in events.read() {
+        if message.message_name() != "EvtDoSkillSuccNotify" {
+            continue;
+        }
+        let Some(notify) = message.decode::<EvtDoSkillSuccNotify>() else {
+            continue;
+        };
+
+        let Some((mut fight_props, skill_depot, owner_player_uid)) = index
+            .0
+            .get(&notify.caster_id)
+            .and_then(|entity| avatars.get_mut(*entity).ok())
+        else {
+            continue;
+        };
+        if owner_player_uid.0 != message.sender_uid() {
+            continue;
+        }
+        let Some((energy_skill_id, element, cost)) = energy_skill(skill_depot.0) else {
+            continue;
+        };
+        if energy_skill_id != notify.skill_id {
+            continue;
+        }
+
+        let delta = change_energy(&mut fight_props, element, -cost);
+        tracing::debug!(
+            "uid {} cast energy skill {}, energy {}",
+            owner_player_uid.0,
+            energy_skill_id,
+            delta
+        );
+        if delta == 0.0 {
+            continue;
+        }
+        reason_events.write(energy_change_reason(
+            notify.caster_id,
+            element,
+            delta,
+            PropChangeReason::PropChangeAbility,
+            ChangeEnergyReason::ChangeEnergySkillStart,
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemParam;
+use common::player_cache::cache_get_is_tp;
+use nod_krai_gi_data::prop_type::FightPropType;
+use nod_krai_gi_data::scene::script_cache::get_scene_config_collection;
+use nod_krai_gi_entity::avatar::CurrentPlayerAvatarMarker;
+use nod_krai_gi_entity::common::{EntityById, FightProperties, OwnerPlayerUID};
+use nod_krai_gi_event::combat::{EntityMoveEvent, PlayerDieCauseEvent};
+use nod_krai_gi_event::entity::{ChangeReason, EntityFightPropChangeReasonNotifyEvent};
+use nod_krai_gi_event::scene::{EnterReason, ScenePlayerJumpEvent};
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::{ChangHpReason, MotionState, PlayerDieType, PropChangeReason};
+use nod_krai_gi_proto::server_only::VectorBin;
+use std::collections::{HashMap, HashSet};
+
+// landing speed thresholds (negative y velocity) and the share of max hp they take
+const FALL_DAMAGE_STEPS: [(f32, f32); 4] =
+    [(-28.0, 1.0), (-26.5, 0.66), (-25.0, 0.5), (-23.5, 0.33)];
+// falling out of the world is never lethal, the avatar is dragged back instead
+const VOID_OUT_DAMAGE_RATIO: f32 = 0.1;
+
+#[derive(Default)]
+struct EnvironmentTrack {
+    // y velocity reported with MotionLandSpeed, consumed by the following move
+    landing_speed: Option<f32>,
+    last_safe_pos: Option<VectorBin>,
+}
+
+// per avatar entity id
+#[derive(Resource, Default)]
+pub struct EnvironmentTracker(HashMap<u32, EnvironmentTrack>);
+
+#[derive(SystemParam)]
+pub struct EnvironmentDamageWriters<'w> {
+    player_die_cause_events: MessageWriter<'w, PlayerDieCauseEvent>,
+    reason_events: MessageWriter<'w, EntityFightPropChangeReasonNotifyEvent>,
+    jump_events: MessageWriter<'w, ScenePlayerJumpEvent>,
+}
+
+// states where the avatar stands on something, a void-out returns it to the last of these
+fn is_grounded(state: MotionState) -> bool {
+    use MotionState::*;
+    matches!(
+        state,
+        MotionStandby
+            | MotionStandbyMove
+            | MotionWalk
+            | MotionRun
+            | MotionDash
+            | MotionFight
+            | MotionDangerStandby
+            | MotionDangerStandbyMove
+            | MotionDangerWalk
+            | MotionDangerRun
+            | MotionDangerDash
+            | MotionCrouchIdle
+            | MotionCrouchMove
+            | MotionSitIdle
+            | MotionSwimIdle
+            | MotionSwimMove
+    )
+}
+
+fn fall_damage_ratio(landing_speed: f32) -> f32 {
+    FALL_DAMAGE_STEPS
+        .iter()
+        .find(|(speed, _)| landing_speed < *speed)
+        .map(|(_, ratio)| *ratio)
+        .unwrap_or_default()
+}
+
+pub fn environment_damage_system(
+    index: Res<EntityById>,
+    mut events: MessageReader<EntityMoveEvent>,
+    mut avatars: Query<(&mut FightProperties, &OwnerPlayerUID), With<CurrentPlayerAvatarMarker>>,
+    players: Res<Players>,
+    mut tracker: ResMut<EnvironmentTracker>,
+    mut writers: EnvironmentDamageWriters,
+) {
+    let scene_config_collection = get_scene_config_collection();
+    let mut dragged_back = HashSet::new();
+
+    for EntityMoveEvent(originator_uid, info) in events.read() {
+        let Some((mut fight_props, owner_uid)) = index
+            .0
+            .get(&info.entity_id)
+            .and_then(|entity| avatars.get_mut(*entity).ok())
+        else {
+            continue;
+        };
+        if owner_uid.0 != *originator_uid || dragged_back.contains(originator_uid) {
+            continue;
+        }
+
+        if cache_get_is_tp(*originator_uid).unwrap_or(true) {
+            tracker.0.remove(&info.entity_id);
+            continue;
+        }
+
+        let Some(motion_info) = info.motion_info.as_ref() else {
+            continue;
+        };
+        let Some(pos) = motion_info.pos else {
+            continue;
+        };
+        let pos: VectorBin = pos.into();
+        let state = motion_info.state();
+        let track = tracker.0.entry(info.entity_id).or_default();
+
+        let scene_id = players
+            .get(*originator_uid)
+            .and_then(|player_info| player_info.scene_bin.as_ref())
+            .map(|player_scene_bin| player_scene_bin.my_cur_scene_id)
+            .unwrap_or_default();
+        let die_y = scene_config_collection
+            .get(&scene_id)
+            .and_then(|scene_config| scene_config.scene_config.die_y);
+
+        if die_y.is_some_and(|die_y| pos.y < die_y) {
+            let Some(last_safe_pos) = track.last_safe_pos else {
+                tracing::debug!(
+                    "uid {} fell out of scene {} with no safe position",
+                    originator_uid,
+                    scene_id
+                );
+                continue;
+            };
+            tracing::debug!(
+                "uid {} fell out of scene {} at {:?}, dragging back to {:?}",
+                originator_uid,
+                scene_id,
+                <(f32, f32, f32)>::from(pos),
+                <(f32, f32, f32)>::from(last_safe_pos)
+            );
+
+            let cur_hp = fight_props.get_property(FightPropType::FIGHT_PROP_CUR_HP);
+            let max_hp = fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP);
+            let damage = (max_hp * VOID_OUT_DAMAGE_RATIO).min(cur_hp - 1.0).max(0.0);
+            if damage > 0.0 {
+                fight_props.change_cur_hp(-damage);
+                writers
+                    .reason_events
+                    .write(EntityFightPropChangeReasonNotifyEvent {
+                        entity_id: info.entity_id,
+                        prop_type: FightPropType::FIGHT_PROP_CUR_HP,
+                        value: -damage,
+                        param_list: None,
+                        reason: PropChangeReason::PropChangeNone,
+                        change_reason: ChangeReason::ChangeHpReason(
+                            ChangHpReason::ChangeHpSubAbyss,
+                        ),
+                    });
+            }
+
+            track.landing_speed = None;
+            dragged_back.insert(*originator_uid);
+            writers.jump_events.write(ScenePlayerJumpEvent(
+                *originator_uid,
+                scene_id,
+                EnterReason::ForceDragBack,
+                last_safe_pos.into(),
+            ));
+            continue;
+        }
+
+        if is_grounded(state) {
+            track.last_safe_pos = Some(pos);
+        }
+
+        if state == MotionState::MotionLandSpeed {
+            track.landing_speed = motion_info.speed.map(|speed| speed.y);
+            continue;
+        }
+        // only a landing on the ground hurts, water or a glider catch cancels it
+        let Some(landing_speed) = track.landing_speed.take() else {
+            continue;
+        };
+        if state != MotionState::MotionFallOnGround {
+            continue;
+        }
+
+        let ratio = fall_damage_ratio(landing_speed);
+        if ratio <= 0.0 {
+            continue;
+        }
+
+        let cur_hp = fight_props.get_property(FightPropType::FIGHT_PROP_CUR_HP);
+        if cur_hp <= 0.0 {
+            continue;
+        }
+        let damage = fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP) * ratio;
+        fight_props.change_cur_hp(-damage);
+        tracing::debug!(
+            "uid {} landed at {} m/s, fall damage {}",
+            originator_uid,
+            landing_speed,
+            damage
+        );
+
+        writers
+            .reason_events
+            .write(EntityFightPropChangeReasonNotifyEvent {
+                entity_id: info.entity_id,
+                prop_type: FightPropType::FIGHT_PROP_CUR_HP,
+                value: -damage,
+                param_list: None,
+                reason: PropChangeReason::PropChangeNone,
+                change_reason: ChangeReason::ChangeHpReason(ChangHpReason::ChangeHpSubFall),
+            });
+
+        if fight_props.get_property(FightPropType::FIGHT_PROP_CUR_HP) <= 0.0 {
+            writers.player_die_cause_events.write(PlayerDieCauseEvent(
+                *originator_uid,
+                PlayerDieType::PlayerDieFall,
+                0,
+            ));
+        }
+    }
+}
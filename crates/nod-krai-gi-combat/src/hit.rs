@@ -1,14 +1,75 @@
+use crate::element::ElementAura;
 use bevy_ecs::prelude::*;
+use nod_krai_gi_data::dynamic_float::DynamicFloat;
+use nod_krai_gi_data::excel::common::ElementType;
+use nod_krai_gi_data::prop_type::FightPropType;
+use nod_krai_gi_data::scene::group_entity_state_cache::hp_percent;
+use nod_krai_gi_data::scene::{EventType, LuaEvt};
 use nod_krai_gi_entity::common::{
-    ConfigId, EntityById, FightProperties, GroupId, OwnerPlayerUID, ProtocolEntityID,
+    ConfigId, DamageBonuses, EntityById, FightProperties, GroupId, InstancedModifiers,
+    OwnerPlayerUID, ProtocolEntityID,
 };
 use nod_krai_gi_entity::gadget::GadgetID;
+use nod_krai_gi_entity::monster::{MonsterHpLock, MonsterID};
+use nod_krai_gi_event::ability::AttackLandedEvent;
 use nod_krai_gi_event::combat::*;
-use nod_krai_gi_event::lua::OnBeHurtEvent;
+use nod_krai_gi_event::lua::{LuaTriggerEvent, OnBeHurtEvent};
 use nod_krai_gi_event::scene::WorldVersionConfig;
-use nod_krai_gi_proto::normal::ProtEntityType;
+use nod_krai_gi_proto::normal::{AttackResult, PlayerDieType, ProtEntityType};
+use std::collections::HashMap;
+
+// configs count element durability in 25 per gauge unit
+const DURABILITY_PER_UNIT: f32 = 25.0;
+const DEFAULT_ELEMENT_DURABILITY: f32 = 25.0;
+
+// group scripts hear about a monster's hp at most this often, the latest percent wins
+const HP_CHANGE_NOTIFY_INTERVAL_MS: u64 = 500;
+
+pub struct HpChangeNotify {
+    sent_percent: u32,
+    sent_at_ms: u64,
+    // (group id, evt) of a change that came inside the window, sent once it ends
+    pending: Option<(u32, LuaEvt)>,
+}
+
+// what each group monster last told its group
+#[derive(Resource, Default)]
+pub struct MonsterHpChangeTracker(HashMap<Entity, HpChangeNotify>);
+
+// gauge units an attack applies, the durability of the modifier it came from scaled by
+// the attenuation the client reports for repeated hits
+fn element_units(
+    attack_result: &AttackResult,
+    index: &EntityById,
+    modifier_owners: &Query<&InstancedModifiers>,
+) -> f32 {
+    let durability = attack_result
+        .ability_identifier
+        .as_ref()
+        .filter(|identifier| identifier.instanced_modifier_id != 0)
+        .and_then(|identifier| {
+            let owner = index.0.get(&identifier.modifier_owner_id)?;
+            let modifiers = modifier_owners.get(*owner).ok()?;
+            let modifier = modifiers.get_by_id(identifier.instanced_modifier_id)?;
+            match modifier.modifier_data?.element_durability {
+                Some(DynamicFloat::Number(durability)) => Some(durability as f32),
+                _ => None,
+            }
+        })
+        .unwrap_or(DEFAULT_ELEMENT_DURABILITY);
+
+    // an unset attenuation means the hit wasn't attenuated at all
+    let attenuation = if attack_result.element_durability_attenuation > 0.0 {
+        attack_result.element_durability_attenuation
+    } else {
+        1.0
+    };
+
+    durability / DURABILITY_PER_UNIT * attenuation
+}
 
 pub fn deal_damage_on_hit(
+    mut commands: Commands,
     index: Res<EntityById>,
     mut events: MessageReader<EntityBeingHitEvent>,
     mut entities: Query<(
@@ -18,10 +79,22 @@ pub fn deal_damage_on_hit(
         Option<&GroupId>,
         Option<&ConfigId>,
         Option<&GadgetID>,
+        Option<&mut ElementAura>,
     )>,
+    hp_locks: Query<&MonsterHpLock>,
+    modifier_owners: Query<&InstancedModifiers>,
+    damage_bonuses: Query<&DamageBonuses>,
     mut on_be_hurt_events: MessageWriter<OnBeHurtEvent>,
+    mut attack_landed_events: MessageWriter<AttackLandedEvent>,
+    mut element_reaction_events: MessageWriter<ElementReactionEvent>,
+    mut entity_killed_events: MessageWriter<EntityKilledEvent>,
+    mut player_die_cause_events: MessageWriter<PlayerDieCauseEvent>,
     world_version_config: Res<WorldVersionConfig>,
 ) {
+    // auras first applied this frame, inserted once every hit has been read so a later
+    // hit on the same target sees them
+    let mut new_auras: HashMap<Entity, ElementAura> = HashMap::new();
+
     for EntityBeingHitEvent(originator_uid, attack_result) in events.read() {
         let entity_type = attack_result.attacker_id >> world_version_config.ty_value;
         tracing::debug!("entity_type : {}", entity_type);
@@ -33,7 +106,7 @@ pub fn deal_damage_on_hit(
                 None => continue,
             };
 
-            let Ok((_, _, attacker_owner, _, _, _)) = entities.get(attacker_entity) else {
+            let Ok((_, _, attacker_owner, _, _, _, _)) = entities.get(attacker_entity) else {
                 tracing::debug!("attacker with id {} not found", attack_result.attacker_id);
                 continue;
             };
@@ -55,21 +128,112 @@ pub fn deal_damage_on_hit(
             None => continue,
         };
 
-        let Ok((mut defender_props, _, _, group_id_comp, config_id_comp, gadget_id_comp)) =
-            entities.get_mut(defense_entity)
+        // level and mp level attackers have no entity of their own
+        let attacker_entity = index.0.get(&attack_result.attacker_id).copied();
+        let attacker_mastery = attacker_entity
+            .and_then(|attacker_entity| entities.get(attacker_entity).ok())
+            .map(|(attacker_props, ..)| {
+                attacker_props.get_property(FightPropType::FIGHT_PROP_ELEMENT_MASTERY)
+            })
+            .unwrap_or_default();
+
+        let Ok((
+            mut defender_props,
+            _,
+            defender_owner,
+            group_id_comp,
+            config_id_comp,
+            gadget_id_comp,
+            defender_aura,
+        )) = entities.get_mut(defense_entity)
         else {
             tracing::debug!("defender with id {} not found", attack_result.defense_id);
             continue;
         };
 
-        defender_props.change_cur_hp(-attack_result.damage);
+        let element_type = ElementType::from(attack_result.element_type);
+        let reaction = if element_type == ElementType::None || attack_result.mute_element_hurt {
+            None
+        } else {
+            let now = common::time_util::unix_timestamp_ms();
+            let units = element_units(attack_result, &index, &modifier_owners);
+            match defender_aura {
+                Some(mut aura) => aura.apply(element_type, units, now),
+                None => {
+                    new_auras
+                        .entry(defense_entity)
+                        .or_default()
+                        .apply(element_type, units, now)
+                }
+            }
+        };
+
+        if let Some(attacker_entity) = attacker_entity {
+            attack_landed_events.write(AttackLandedEvent(
+                attacker_entity,
+                defense_entity,
+                attack_result.anim_event_id.clone(),
+            ));
+        }
+
+        // ModifyDamage bonuses of server run attackers, only for the attacks they name
+        let damage_bonus = attacker_entity
+            .and_then(|attacker_entity| damage_bonuses.get(attacker_entity).ok())
+            .map(|bonuses| bonuses.percentage_for(&attack_result.anim_event_id))
+            .unwrap_or_default();
+        let mut damage = attack_result.damage * (1.0 + damage_bonus);
+        if let Some(reaction) = reaction {
+            damage =
+                reaction.apply_damage(damage, attack_result.element_amplify_rate, attacker_mastery);
+            element_reaction_events.write(ElementReactionEvent(
+                attacker_entity,
+                defense_entity,
+                reaction.reaction_type,
+            ));
+            tracing::debug!(
+                "defender (id: {}) reaction {:?} ({:?} on {:?})",
+                attack_result.defense_id,
+                reaction.reaction_type,
+                reaction.trigger,
+                reaction.aura
+            );
+        }
+
+        let cur_hp = defender_props.get_property(FightPropType::FIGHT_PROP_CUR_HP);
+        if let Ok(hp_lock) = hp_locks.get(defense_entity) {
+            damage = damage.min(cur_hp - hp_lock.min_hp).max(0.0);
+        }
+
+        defender_props.change_cur_hp(-damage);
         tracing::debug!(
             "attacker (id: {}) dealt {} dmg to defender (id: {})",
             attack_result.attacker_id,
-            attack_result.damage,
+            damage,
             attack_result.defense_id
         );
 
+        if cur_hp > 0.0 && defender_props.get_property(FightPropType::FIGHT_PROP_CUR_HP) <= 0.0 {
+            entity_killed_events.write(EntityKilledEvent(attacker_entity, defense_entity));
+
+            let defense_type = attack_result.defense_id >> world_version_config.ty_value;
+            if let Some(owner_uid) =
+                defender_owner.filter(|_| defense_type == ProtEntityType::ProtEntityAvatar as u32)
+            {
+                let die_type = if entity_type == ProtEntityType::ProtEntityMonster as u32 {
+                    PlayerDieType::PlayerDieKillByMonster
+                } else if entity_type == ProtEntityType::ProtEntityGadget as u32 {
+                    PlayerDieType::PlayerDieKillByGear
+                } else {
+                    PlayerDieType::PlayerDieNone
+                };
+                player_die_cause_events.write(PlayerDieCauseEvent(
+                    owner_uid.0,
+                    die_type,
+                    attack_result.attacker_id,
+                ));
+            }
+        }
+
         tracing::debug!("group_id_comp : {}", group_id_comp.is_some());
         tracing::debug!("config_id_comp : {}", config_id_comp.is_some());
         tracing::debug!("gadget_id_comp : {}", gadget_id_comp.is_some());
@@ -106,9 +270,90 @@ pub fn deal_damage_on_hit(
         on_be_hurt_events.write(OnBeHurtEvent {
             lua_name,
             element_type: attack_result.element_type,
+            reaction_type: reaction
+                .map(|reaction| reaction.reaction_type as u32)
+                .unwrap_or_default(),
             strike_type: 0,
             is_host: true,
             lua_context,
         });
     }
+
+    for (entity, aura) in new_auras {
+        commands.entity(entity).insert(aura);
+    }
+}
+
+pub fn monster_hp_change_notify_system(
+    monsters: Query<(
+        Entity,
+        &MonsterID,
+        &FightProperties,
+        &ProtocolEntityID,
+        &GroupId,
+        &ConfigId,
+    )>,
+    mut removed_monsters: RemovedComponents<MonsterID>,
+    mut tracker: ResMut<MonsterHpChangeTracker>,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+) {
+    // a monster gone before its window ended still gets its last percent out
+    for entity in removed_monsters.read() {
+        if let Some(HpChangeNotify {
+            pending: Some((group_id, evt)),
+            ..
+        }) = tracker.0.remove(&entity)
+        {
+            lua_trigger_events.write(LuaTriggerEvent {
+                group_id,
+                event_type: EventType::EventSpecificMonsterHpChange,
+                evt,
+            });
+        }
+    }
+
+    let now = common::time_util::unix_timestamp_ms();
+    for (entity, monster_id, fight_props, entity_id, group_id, config_id) in monsters.iter() {
+        let percent = hp_percent(
+            fight_props.get_property(FightPropType::FIGHT_PROP_CUR_HP),
+            fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP),
+        );
+        let Some(notify) = tracker.0.get_mut(&entity) else {
+            tracker.0.insert(
+                entity,
+                HpChangeNotify {
+                    sent_percent: percent,
+                    sent_at_ms: 0,
+                    pending: None,
+                },
+            );
+            continue;
+        };
+        if percent == notify.sent_percent {
+            notify.pending = None;
+            continue;
+        }
+
+        let evt = LuaEvt {
+            param1: config_id.0,
+            param2: monster_id.0,
+            param3: percent,
+            source_eid: entity_id.0,
+            target_eid: entity_id.0,
+        };
+        // a death never waits for the window
+        if percent > 0 && now.saturating_sub(notify.sent_at_ms) < HP_CHANGE_NOTIFY_INTERVAL_MS {
+            notify.pending = Some((group_id.0, evt));
+            continue;
+        }
+        notify.sent_percent = percent;
+        notify.sent_at_ms = now;
+        notify.pending = None;
+
+        lua_trigger_events.write(LuaTriggerEvent {
+            group_id: group_id.0,
+            event_type: EventType::EventSpecificMonsterHpChange,
+            evt,
+        });
+    }
 }
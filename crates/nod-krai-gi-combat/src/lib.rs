@@ -1,34 +1,84 @@
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
-use hit::deal_damage_on_hit;
-use movement::{entity_movement, track_player_position};
+use energy::{
+    avatar_energy_add_system, elem_ball_pickup_system, generate_elem_ball_system,
+    handle_skill_energy_cost, monster_hp_drop_system, MonsterHpDropTracker,
+};
+use environment::{environment_damage_system, EnvironmentTracker};
+use hit::{deal_damage_on_hit, monster_hp_change_notify_system, MonsterHpChangeTracker};
+use movement::{
+    entity_movement, reset_movement_on_jump, track_player_position, MovementAuditLog,
+    MovementValidator,
+};
 use nod_krai_gi_message::event::*;
-use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_message::invocation::InvocationSender;
 use nod_krai_gi_proto::normal::{
-    CombatInvocationsNotify, CombatInvokeEntry, EntityMoveInfo, EvtAnimatorParameterInfo,
-    EvtBeingHitInfo, ForwardType,
+    CombatInvocationsNotify, EntityMoveInfo, EvtAnimatorParameterInfo, EvtBeingHitInfo,
+};
+use stamina::{
+    handle_drown_req, stamina_ability_cost_system, stamina_cost_modifier_system,
+    stamina_motion_system, stamina_tick_system, PlayerStaminas,
 };
 use tracing::{error, instrument};
 
+pub mod element;
+mod energy;
+mod environment;
 mod hit;
-mod movement;
+pub mod movement;
+pub mod stamina;
 
 use nod_krai_gi_event::combat::{EntityBeingHitEvent, EntityMoveEvent, PlayerMoveEvent};
-use std::sync::atomic::{AtomicU32, Ordering};
-
-static COUNTER: AtomicU32 = AtomicU32::new(0);
-
-pub fn next_seq() -> u32 {
-    COUNTER.fetch_add(1, Ordering::Relaxed) + 1
-}
 
 pub struct CombatPlugin;
 
 impl Plugin for CombatPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PreUpdate, combat_invocation_processor)
-            .add_systems(Update, entity_movement)
-            .add_systems(Update, deal_damage_on_hit)
+        app.init_resource::<PlayerStaminas>()
+            .init_resource::<MovementValidator>()
+            .init_resource::<MovementAuditLog>()
+            .init_resource::<EnvironmentTracker>()
+            .init_resource::<MonsterHpDropTracker>()
+            .init_resource::<MonsterHpChangeTracker>()
+            .add_systems(PreUpdate, combat_invocation_processor)
+            .add_systems(PreUpdate, handle_drown_req)
+            .add_systems(
+                Update,
+                (
+                    reset_movement_on_jump,
+                    entity_movement,
+                    environment_damage_system,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    stamina_motion_system,
+                    stamina_ability_cost_system,
+                    stamina_cost_modifier_system,
+                    stamina_tick_system,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    deal_damage_on_hit,
+                    monster_hp_drop_system,
+                    monster_hp_change_notify_system,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_skill_energy_cost,
+                    generate_elem_ball_system,
+                    elem_ball_pickup_system,
+                    avatar_energy_add_system,
+                ),
+            )
             .add_systems(PostUpdate, track_player_position)
             .add_message::<PlayerMoveEvent>();
     }
@@ -37,7 +87,7 @@ impl Plugin for CombatPlugin {
 #[instrument(skip_all)]
 fn combat_invocation_processor(
     mut events: MessageReader<ClientMessageEvent>,
-    message_output: Res<MessageOutput>,
+    mut invocation_sender: InvocationSender,
     mut movement_events: MessageWriter<EntityMoveEvent>,
     mut hit_events: MessageWriter<EntityBeingHitEvent>,
 ) {
@@ -45,9 +95,6 @@ fn combat_invocation_processor(
         match message.message_name() {
             "CombatInvocationsNotify" => {
                 if let Some(notify) = message.decode::<CombatInvocationsNotify>() {
-                    let mut invoke_list_to_all: Vec<CombatInvokeEntry> = Vec::new();
-                    let mut invoke_list_to_ohers: Vec<CombatInvokeEntry> = Vec::new();
-                    let mut invoke_list_to_host: Vec<CombatInvokeEntry> = Vec::new();
                     for mut invoke in notify.invoke_list {
                         use nod_krai_gi_proto::normal::CombatTypeArgument::*;
 
@@ -121,51 +168,7 @@ fn combat_invocation_processor(
                             _ => {}
                         }
 
-                        match invoke.forward_type() {
-                            ForwardType::ForwardToAll => {
-                                invoke_list_to_all.push(invoke.clone());
-                            }
-                            ForwardType::ForwardToAllExceptCur
-                            | ForwardType::ForwardToAllExistExceptCur => {
-                                invoke_list_to_ohers.push(invoke.clone());
-                            }
-                            ForwardType::ForwardToHost => {
-                                invoke_list_to_host.push(invoke.clone());
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    if !invoke_list_to_all.is_empty() {
-                        message_output.send_to_all(
-                            "CombatInvocationsNotify",
-                            CombatInvocationsNotify {
-                                invoke_list: invoke_list_to_all,
-                                client_sequence_id: next_seq(),
-                            },
-                        );
-                    }
-
-                    if !invoke_list_to_ohers.is_empty() {
-                        message_output.send_to_others(
-                            message.sender_uid(),
-                            "CombatInvocationsNotify",
-                            CombatInvocationsNotify {
-                                invoke_list: invoke_list_to_ohers,
-                                client_sequence_id: next_seq(),
-                            },
-                        );
-                    }
-
-                    if !invoke_list_to_host.is_empty() {
-                        message_output.send(
-                            message.sender_uid(),
-                            "CombatInvocationsNotify",
-                            CombatInvocationsNotify {
-                                invoke_list: invoke_list_to_host,
-                                client_sequence_id: next_seq(),
-                            },
-                        );
+                        invocation_sender.forward_combat_invoke(message.sender_uid(), invoke);
                     }
                 }
             }
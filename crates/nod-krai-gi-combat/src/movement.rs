@@ -1,5 +1,7 @@
 use bevy_ecs::prelude::*;
 use common::player_cache::cache_get_is_tp;
+use common::time_util::unix_timestamp_ms;
+use nod_krai_gi_data::GAME_SERVER_CONFIG;
 use nod_krai_gi_entity::common::{EntityById, Visible};
 use nod_krai_gi_entity::{
     avatar::CurrentPlayerAvatarMarker,
@@ -7,13 +9,156 @@ use nod_krai_gi_entity::{
     transform::Transform,
 };
 use nod_krai_gi_event::combat::*;
+use nod_krai_gi_event::scene::ScenePlayerJumpEvent;
+use nod_krai_gi_message::output::MessageOutput;
 use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::{MotionInfo, MotionState, SceneEntityMoveNotify};
+use nod_krai_gi_proto::server_only::VectorBin;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+// covers latency spikes and the client batching several moves into one packet
+const MOVE_TIME_SLACK: f32 = 0.5;
+const MOVE_GRACE_DISTANCE: f32 = 3.0;
+const MAX_RISE_SPEED: f32 = 30.0;
+const AUDIT_LOG_CAPACITY: usize = 256;
+
+// horizontal speed cap in meters per second, None for states the server moves the avatar in
+fn speed_cap(state: MotionState) -> Option<f32> {
+    use MotionState::*;
+    match state {
+        MotionReset | MotionNotify | MotionMoveFailAck | MotionForceSetPos
+        | MotionQuestForceDrag | MotionFollowRoute | MotionRollerCoaster | MotionOceanCurrent
+        | MotionWaterfall | MotionArcLight | MotionArcLightSafe | MotionDestroyVehicle
+        | MotionDebug => None,
+        MotionNone
+        | MotionStandby
+        | MotionStandbyMove
+        | MotionWalk
+        | MotionRun
+        | MotionFight
+        | MotionGoUpstairs
+        | MotionFallOnGround
+        | MotionSlip
+        | MotionLadderIdle
+        | MotionLadderMove
+        | MotionLadderSlip
+        | MotionStandbyToLadder
+        | MotionLadderToStandby
+        | MotionDangerStandby
+        | MotionDangerStandbyMove
+        | MotionDangerWalk
+        | MotionDangerRun
+        | MotionCrouchIdle
+        | MotionCrouchMove
+        | MotionSitIdle
+        | MotionAimMove => Some(12.0),
+        MotionDash | MotionDashBeforeShake | MotionDangerDash | MotionCrouchRoll => Some(25.0),
+        MotionClimb
+        | MotionClimbJump
+        | MotionStandbyToClimb
+        | MotionJumpUpWallForStandby
+        | MotionJumpOffWall => Some(15.0),
+        MotionSwimMove | MotionSwimIdle | MotionSwimDash | MotionSwimJump => Some(15.0),
+        MotionDiveIdle | MotionDiveMove | MotionDiveDash | MotionDiveDolphine
+        | MotionDiveSwimMove | MotionDiveSwimIdle | MotionDiveSwimDash => Some(20.0),
+        MotionJump
+        | MotionDrop
+        | MotionFly
+        | MotionFlyIdle
+        | MotionFlySlow
+        | MotionFlyFast
+        | MotionPoweredFly
+        | MotionLandSpeed
+        | MotionAirCompensation => Some(25.0),
+        // vehicles and anything newer than this list
+        _ => Some(40.0),
+    }
+}
+
+pub struct MovementViolation {
+    pub uid: u32,
+    pub entity_id: u32,
+    pub motion_state: MotionState,
+    pub from: VectorBin,
+    pub to: VectorBin,
+    pub speed: f32,
+    pub speed_cap: f32,
+    pub time_ms: u64,
+    pub corrected: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct MovementAuditLog(VecDeque<MovementViolation>);
+
+impl MovementAuditLog {
+    pub fn iter(&self) -> impl Iterator<Item = &MovementViolation> {
+        self.0.iter()
+    }
+
+    pub fn violation_count(&self, uid: u32) -> usize {
+        self.0
+            .iter()
+            .filter(|violation| violation.uid == uid)
+            .count()
+    }
+
+    fn record(&mut self, violation: MovementViolation) {
+        tracing::warn!(
+            "[MovementAudit] uid {} entity {} moved {:?} -> {:?} at {:.1} m/s in {:?}, cap {:.1}{}",
+            violation.uid,
+            violation.entity_id,
+            <(f32, f32, f32)>::from(violation.from),
+            <(f32, f32, f32)>::from(violation.to),
+            violation.speed,
+            violation.motion_state,
+            violation.speed_cap,
+            if violation.corrected {
+                ", corrected"
+            } else {
+                ""
+            }
+        );
+        if self.0.len() >= AUDIT_LOG_CAPACITY {
+            self.0.pop_front();
+        }
+        self.0.push_back(violation);
+    }
+}
+
+struct AcceptedMove {
+    uid: u32,
+    state: MotionState,
+    scene_time: u32,
+    received_at: Instant,
+}
+
+// last accepted move per entity id, the position itself lives in the Transform
+#[derive(Resource, Default)]
+pub struct MovementValidator(HashMap<u32, AcceptedMove>);
+
+// server side jumps put the avatar anywhere, the next move starts a fresh track
+pub fn reset_movement_on_jump(
+    mut events: MessageReader<ScenePlayerJumpEvent>,
+    mut validator: ResMut<MovementValidator>,
+) {
+    for ScenePlayerJumpEvent(uid, ..) in events.read() {
+        validator
+            .0
+            .retain(|_, accepted_move| accepted_move.uid != *uid);
+    }
+}
 
 pub fn entity_movement(
     index: Res<EntityById>,
     mut events: MessageReader<EntityMoveEvent>,
     mut entities: Query<(&mut Transform, &ProtocolEntityID, Option<&OwnerPlayerUID>)>,
+    mut validator: ResMut<MovementValidator>,
+    mut audit_log: ResMut<MovementAuditLog>,
+    message_output: Res<MessageOutput>,
 ) {
+    let now = Instant::now();
+
     for EntityMoveEvent(originator_uid, info) in events.read() {
         let move_entity = match index.0.get(&info.entity_id) {
             Some(e) => *e,
@@ -37,14 +182,108 @@ pub fn entity_movement(
         }
 
         if cache_get_is_tp(*originator_uid).unwrap_or(true) {
+            validator
+                .0
+                .retain(|_, accepted_move| accepted_move.uid != *originator_uid);
             continue;
         }
 
-        if let Some((Some(pos), Some(rot))) = info.motion_info.as_ref().map(|i| (i.pos, i.rot)) {
-            transform.position = pos.into();
-            transform.rotation = rot.into();
+        let Some(motion_info) = info.motion_info.as_ref() else {
+            continue;
+        };
+        let (Some(pos), Some(rot)) = (motion_info.pos, motion_info.rot) else {
+            continue;
+        };
+        let pos: VectorBin = pos.into();
+
+        // only player owned entities are checked, monsters follow their authority peer
+        if owner_uid.is_some() {
+            if let Some(violation) = check_move(
+                validator.0.get(&info.entity_id),
+                &transform,
+                pos,
+                motion_info.state(),
+                info.scene_time,
+                now,
+            ) {
+                let corrected = GAME_SERVER_CONFIG.plugin.movement_rubber_band;
+                audit_log.record(MovementViolation {
+                    uid: *originator_uid,
+                    entity_id: info.entity_id,
+                    motion_state: motion_info.state(),
+                    from: transform.position,
+                    to: pos,
+                    speed: violation.0,
+                    speed_cap: violation.1,
+                    time_ms: unix_timestamp_ms(),
+                    corrected,
+                });
+
+                if corrected {
+                    message_output.send(
+                        *originator_uid,
+                        "SceneEntityMoveNotify",
+                        SceneEntityMoveNotify {
+                            entity_id: info.entity_id,
+                            motion_info: Some(MotionInfo {
+                                pos: Some(transform.position.into()),
+                                rot: Some(transform.rotation.into()),
+                                state: MotionState::MotionForceSetPos as i32,
+                                ..Default::default()
+                            }),
+                            scene_time: info.scene_time,
+                            reliable_seq: info.reliable_seq,
+                        },
+                    );
+                    continue;
+                }
+            }
+
+            validator.0.insert(
+                info.entity_id,
+                AcceptedMove {
+                    uid: *originator_uid,
+                    state: motion_info.state(),
+                    scene_time: info.scene_time,
+                    received_at: now,
+                },
+            );
         }
+
+        transform.position = pos;
+        transform.rotation = rot.into();
+    }
+}
+
+// returns (speed, cap) of the offending move
+fn check_move(
+    last_move: Option<&AcceptedMove>,
+    transform: &Transform,
+    pos: VectorBin,
+    state: MotionState,
+    scene_time: u32,
+    now: Instant,
+) -> Option<(f32, f32)> {
+    let last_move = last_move?;
+    let speed_cap = speed_cap(last_move.state)?.max(speed_cap(state)?);
+
+    // the client clock paces the check, the server clock bounds how far it may run ahead
+    let server_elapsed = now.duration_since(last_move.received_at).as_secs_f32();
+    let client_elapsed = scene_time.saturating_sub(last_move.scene_time) as f32 / 1000.0;
+    let elapsed = client_elapsed.min(server_elapsed + MOVE_TIME_SLACK);
+
+    let from = transform.position;
+    let horizontal = ((pos.x - from.x).powi(2) + (pos.z - from.z).powi(2)).sqrt();
+    let rise = pos.y - from.y;
+
+    let speed = horizontal / elapsed.max(0.001);
+    if horizontal > speed_cap * elapsed + MOVE_GRACE_DISTANCE {
+        return Some((speed, speed_cap));
+    }
+    if rise > MAX_RISE_SPEED * elapsed + MOVE_GRACE_DISTANCE {
+        return Some((rise / elapsed.max(0.001), MAX_RISE_SPEED));
     }
+    None
 }
 
 pub fn track_player_position(
@@ -75,7 +314,7 @@ pub fn track_player_position(
                     transform.position.y,
                     transform.position.z,
                 ),
-                false
+                false,
             ));
 
             tracing::trace!(
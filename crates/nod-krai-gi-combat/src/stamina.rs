@@ -0,0 +1,453 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::prop_type::{
+    FightPropType, PROP_CUR_PERSIST_STAMINA, PROP_CUR_TEMPORARY_STAMINA, PROP_MAX_STAMINA,
+};
+use nod_krai_gi_entity::avatar::CurrentPlayerAvatarMarker;
+use nod_krai_gi_entity::common::{EntityById, OwnerPlayerUID};
+use nod_krai_gi_event::ability::AbilityCostStaminaEvent;
+use nod_krai_gi_event::combat::{EntityMoveEvent, PlayerDieCauseEvent, StaminaCostModifierEvent};
+use nod_krai_gi_event::entity::EntityPropertySeparateUpdateEvent;
+use nod_krai_gi_message::event::ClientMessageEvent;
+use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::{
+    prop_value, MotionState, PlayerDieType, PlayerPropNotify, PropValue, SceneEntityDrownReq,
+    SceneEntityDrownRsp,
+};
+use nod_krai_gi_proto::retcode::Retcode;
+use nod_krai_gi_proto::server_only::PlayerBasicCompBin;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// all amounts are in prop units, the client shows a hundredth of them
+const DEFAULT_MAX_STAMINA: f32 = 24000.0;
+const RECOVER_PER_SECOND: f32 = 2500.0;
+const RECOVER_DELAY: Duration = Duration::from_secs(1);
+const SYNC_INTERVAL: Duration = Duration::from_millis(200);
+// config cost_stamina_delta is in displayed stamina points
+const ABILITY_COST_SCALE: f32 = 100.0;
+// the estimate trails the client, a drown request is believed while it shows no more than this
+const DROWN_STAMINA_TOLERANCE: f32 = 2400.0;
+
+// paid once when the avatar enters the state
+fn start_cost(from: MotionState, to: MotionState) -> f32 {
+    use MotionState::*;
+    match to {
+        MotionDash | MotionDashBeforeShake
+            if !matches!(from, MotionDash | MotionDashBeforeShake) =>
+        {
+            1800.0
+        }
+        MotionStandbyToClimb => 500.0,
+        MotionClimbJump if from != MotionClimbJump => 2500.0,
+        MotionSwimDash if from != MotionSwimDash => 2000.0,
+        _ => 0.0,
+    }
+}
+
+fn cost_per_second(state: MotionState) -> f32 {
+    use MotionState::*;
+    match state {
+        MotionDash => 1800.0,
+        MotionClimb => 750.0,
+        MotionFly | MotionFlyIdle | MotionFlySlow | MotionFlyFast => 300.0,
+        MotionSwimMove => 400.0,
+        MotionSwimDash | MotionSkiffDash => 1020.0,
+        _ => 0.0,
+    }
+}
+
+// airborne, swimming and climbing states keep the bar where it is
+fn recovers_in(state: MotionState) -> bool {
+    use MotionState::*;
+    matches!(
+        state,
+        MotionNone
+            | MotionStandby
+            | MotionStandbyMove
+            | MotionWalk
+            | MotionRun
+            | MotionFight
+            | MotionGoUpstairs
+            | MotionFallOnGround
+            | MotionLadderIdle
+            | MotionLadderMove
+            | MotionDangerStandby
+            | MotionDangerStandbyMove
+            | MotionDangerWalk
+            | MotionDangerRun
+            | MotionCrouchIdle
+            | MotionCrouchMove
+            | MotionSitIdle
+            | MotionSkiffNormal
+            | MotionAimMove
+    )
+}
+
+fn in_water(state: MotionState) -> bool {
+    use MotionState::*;
+    matches!(
+        state,
+        MotionSwimMove | MotionSwimIdle | MotionSwimDash | MotionSwimJump
+    )
+}
+
+struct StaminaCostModifier {
+    ratio: f32,
+    expire_at: Option<Instant>,
+}
+
+pub struct PlayerStamina {
+    pub max: f32,
+    pub cur_persist: f32,
+    pub cur_temporary: f32,
+    motion_state: MotionState,
+    last_cost_at: Option<Instant>,
+    last_tick_at: Instant,
+    last_sync_at: Option<Instant>,
+    // values last sent in PlayerPropNotify
+    synced: (i64, i64, i64),
+    drowned: bool,
+    // food and talent reductions keyed by their source
+    cost_modifiers: HashMap<String, StaminaCostModifier>,
+}
+
+impl PlayerStamina {
+    fn new(max: f32, cur_persist: f32, cur_temporary: f32, now: Instant) -> Self {
+        Self {
+            max,
+            cur_persist,
+            cur_temporary,
+            motion_state: MotionState::MotionStandby,
+            last_cost_at: None,
+            last_tick_at: now,
+            last_sync_at: None,
+            synced: (max as i64, cur_persist as i64, cur_temporary as i64),
+            drowned: false,
+            cost_modifiers: HashMap::new(),
+        }
+    }
+
+    pub fn total(&self) -> f32 {
+        self.cur_persist + self.cur_temporary
+    }
+
+    // only checks a drown the client reports, the server never drowns anyone on its own
+    pub fn can_drown(&self) -> bool {
+        self.total() <= DROWN_STAMINA_TOLERANCE
+    }
+
+    fn cost_ratio(&self) -> f32 {
+        self.cost_modifiers
+            .values()
+            .map(|modifier| modifier.ratio)
+            .product::<f32>()
+            .max(0.0)
+    }
+
+    // temporary stamina is spent first and never comes back on its own
+    fn consume(&mut self, amount: f32, now: Instant) {
+        let amount = amount * self.cost_ratio();
+        if amount <= 0.0 {
+            return;
+        }
+        let from_temporary = amount.min(self.cur_temporary);
+        self.cur_temporary -= from_temporary;
+        self.cur_persist = (self.cur_persist - (amount - from_temporary)).max(0.0);
+        self.last_cost_at = Some(now);
+    }
+
+    fn recover(&mut self, amount: f32) {
+        self.cur_persist = (self.cur_persist + amount).min(self.max);
+    }
+
+    fn set_motion_state(&mut self, state: MotionState, now: Instant) {
+        if state == self.motion_state {
+            return;
+        }
+        let cost = start_cost(self.motion_state, state);
+        self.consume(cost, now);
+        if !in_water(state) {
+            self.drowned = false;
+        }
+        self.motion_state = state;
+    }
+
+    fn tick(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_tick_at).as_secs_f32();
+        self.last_tick_at = now;
+
+        self.cost_modifiers
+            .retain(|_, modifier| modifier.expire_at.is_none_or(|expire_at| expire_at > now));
+
+        let cost = cost_per_second(self.motion_state) * elapsed;
+        if cost > 0.0 {
+            self.consume(cost, now);
+        } else if recovers_in(self.motion_state)
+            && self
+                .last_cost_at
+                .is_none_or(|last_cost_at| now.duration_since(last_cost_at) >= RECOVER_DELAY)
+        {
+            self.recover(RECOVER_PER_SECOND * elapsed);
+        }
+    }
+
+    fn values(&self) -> (i64, i64, i64) {
+        (
+            self.max as i64,
+            self.cur_persist as i64,
+            self.cur_temporary as i64,
+        )
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerStaminas(HashMap<u32, PlayerStamina>);
+
+impl PlayerStaminas {
+    pub fn get(&self, uid: u32) -> Option<&PlayerStamina> {
+        self.0.get(&uid)
+    }
+
+    // built from the persisted basic bin the first time the player shows up
+    fn get_or_load(
+        &mut self,
+        players: &Players,
+        uid: u32,
+        now: Instant,
+    ) -> Option<&mut PlayerStamina> {
+        match self.0.entry(uid) {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let player_basic_bin = players.get(uid)?.basic_bin.as_ref()?;
+                let (max, cur_persist, cur_temporary) = persisted_stamina(player_basic_bin);
+                Some(entry.insert(PlayerStamina::new(max, cur_persist, cur_temporary, now)))
+            }
+        }
+    }
+}
+
+// (max, persist, temporary), a bin that never stored stamina starts full
+pub fn persisted_stamina(player_basic_bin: &PlayerBasicCompBin) -> (f32, f32, f32) {
+    if player_basic_bin.persist_stamina_limit <= 0.0 {
+        return (DEFAULT_MAX_STAMINA, DEFAULT_MAX_STAMINA, 0.0);
+    }
+    (
+        player_basic_bin.persist_stamina_limit,
+        player_basic_bin
+            .cur_persist_stamina
+            .clamp(0.0, player_basic_bin.persist_stamina_limit),
+        player_basic_bin.cur_temporary_stamina.max(0.0),
+    )
+}
+
+pub fn stamina_motion_system(
+    index: Res<EntityById>,
+    mut events: MessageReader<EntityMoveEvent>,
+    current_avatars: Query<&OwnerPlayerUID, With<CurrentPlayerAvatarMarker>>,
+    players: Res<Players>,
+    mut staminas: ResMut<PlayerStaminas>,
+) {
+    let now = Instant::now();
+
+    for EntityMoveEvent(originator_uid, info) in events.read() {
+        let Some(motion_info) = info.motion_info.as_ref() else {
+            continue;
+        };
+        let Some(entity) = index.0.get(&info.entity_id) else {
+            continue;
+        };
+        // only the avatar on the field drives the player stamina
+        let Ok(owner_uid) = current_avatars.get(*entity) else {
+            continue;
+        };
+        if owner_uid.0 != *originator_uid {
+            continue;
+        }
+        let Some(stamina) = staminas.get_or_load(&players, owner_uid.0, now) else {
+            continue;
+        };
+
+        stamina.set_motion_state(motion_info.state(), now);
+    }
+}
+
+pub fn stamina_ability_cost_system(
+    mut events: MessageReader<AbilityCostStaminaEvent>,
+    owners: Query<&OwnerPlayerUID>,
+    players: Res<Players>,
+    mut staminas: ResMut<PlayerStaminas>,
+) {
+    let now = Instant::now();
+
+    for AbilityCostStaminaEvent(entity, delta) in events.read() {
+        let Ok(owner_uid) = owners.get(*entity) else {
+            continue;
+        };
+        let Some(stamina) = staminas.get_or_load(&players, owner_uid.0, now) else {
+            continue;
+        };
+
+        tracing::debug!("ability stamina cost uid={} delta={}", owner_uid.0, delta);
+        stamina.consume(*delta * ABILITY_COST_SCALE, now);
+    }
+}
+
+pub fn stamina_cost_modifier_system(
+    mut events: MessageReader<StaminaCostModifierEvent>,
+    players: Res<Players>,
+    mut staminas: ResMut<PlayerStaminas>,
+) {
+    let now = Instant::now();
+
+    for StaminaCostModifierEvent(uid, source, ratio, duration) in events.read() {
+        // a ratio of one is no modifier at all, it takes the source off
+        if *ratio == 1.0 {
+            if let Some(stamina) = staminas.0.get_mut(uid) {
+                stamina.cost_modifiers.remove(source);
+            }
+            continue;
+        }
+        let Some(stamina) = staminas.get_or_load(&players, *uid, now) else {
+            continue;
+        };
+        stamina.cost_modifiers.insert(
+            source.clone(),
+            StaminaCostModifier {
+                ratio: *ratio,
+                expire_at: duration
+                    .map(|duration| now + Duration::from_secs_f32(duration.max(0.0))),
+            },
+        );
+    }
+}
+
+pub fn stamina_tick_system(
+    mut players: ResMut<Players>,
+    mut staminas: ResMut<PlayerStaminas>,
+    message_output: Res<MessageOutput>,
+) {
+    let now = Instant::now();
+
+    // a player who left the world takes their stamina with them
+    staminas.0.retain(|uid, _| players.get(*uid).is_some());
+
+    for (uid, stamina) in staminas.0.iter_mut() {
+        stamina.tick(now);
+
+        let values = stamina.values();
+        if values == stamina.synced {
+            continue;
+        }
+        // an empty or full bar goes out at once, everything in between is throttled
+        let is_edge = values.1 == 0 || values.1 == values.0;
+        if !is_edge
+            && stamina
+                .last_sync_at
+                .is_some_and(|last_sync_at| now.duration_since(last_sync_at) < SYNC_INTERVAL)
+        {
+            continue;
+        }
+
+        let mut prop_map = HashMap::new();
+        for (prop_type, value, synced_value) in [
+            (PROP_MAX_STAMINA, values.0, stamina.synced.0),
+            (PROP_CUR_PERSIST_STAMINA, values.1, stamina.synced.1),
+            (PROP_CUR_TEMPORARY_STAMINA, values.2, stamina.synced.2),
+        ] {
+            if value != synced_value {
+                prop_map.insert(
+                    prop_type,
+                    PropValue {
+                        r#type: prop_type,
+                        val: value,
+                        value: Some(prop_value::Value::Ival(value)),
+                    },
+                );
+            }
+        }
+        message_output.send(*uid, "PlayerPropNotify", PlayerPropNotify { prop_map });
+        stamina.synced = values;
+        stamina.last_sync_at = Some(now);
+
+        if let Some(player_basic_bin) = players
+            .get_mut(*uid)
+            .and_then(|player_info| player_info.basic_bin.as_mut())
+        {
+            player_basic_bin.persist_stamina_limit = stamina.max;
+            player_basic_bin.cur_persist_stamina = stamina.cur_persist;
+            player_basic_bin.cur_temporary_stamina = stamina.cur_temporary;
+        }
+    }
+}
+
+// the client decides when its avatar drowns, the server only turns it down when its own
+// estimate still shows a clear amount of stamina
+pub fn handle_drown_req(
+    index: Res<EntityById>,
+    mut events: MessageReader<ClientMessageEvent>,
+    avatars: Query<&OwnerPlayerUID, With<CurrentPlayerAvatarMarker>>,
+    mut staminas: ResMut<PlayerStaminas>,
+    mut update_separate_property_entity_events: MessageWriter<EntityPropertySeparateUpdateEvent>,
+    mut player_die_cause_events: MessageWriter<PlayerDieCauseEvent>,
+    message_output: Res<MessageOutput>,
+) {
+    for message in events.read() {
+        if message.message_name() != "SceneEntityDrownReq" {
+            continue;
+        }
+        let Some(req) = message.decode::<SceneEntityDrownReq>() else {
+            continue;
+        };
+
+        let uid = message.sender_uid();
+        let avatar_entity = index.0.get(&req.entity_id).copied().filter(|entity| {
+            avatars
+                .get(*entity)
+                .is_ok_and(|owner_uid| owner_uid.0 == uid)
+        });
+        // a player the estimate has not picked up yet is taken at their word
+        let stamina = staminas.0.get_mut(&uid);
+
+        let retcode = match (avatar_entity, stamina) {
+            (None, _) => Retcode::RetFail,
+            (Some(_), Some(stamina)) if !stamina.can_drown() => {
+                tracing::debug!(
+                    "uid {} drown req for entity {} rejected, {} stamina left",
+                    uid,
+                    req.entity_id,
+                    stamina.total()
+                );
+                Retcode::RetFail
+            }
+            // a repeated request for the same drown kills only once
+            (Some(_), Some(stamina)) if stamina.drowned => Retcode::RetSucc,
+            (Some(entity), stamina) => {
+                if let Some(stamina) = stamina {
+                    stamina.drowned = true;
+                }
+                update_separate_property_entity_events.write(EntityPropertySeparateUpdateEvent(
+                    entity,
+                    FightPropType::FIGHT_PROP_CUR_HP,
+                    -100000000.0,
+                ));
+                player_die_cause_events.write(PlayerDieCauseEvent(
+                    uid,
+                    PlayerDieType::PlayerDieDrawn,
+                    0,
+                ));
+                Retcode::RetSucc
+            }
+        };
+
+        message_output.send(
+            uid,
+            "SceneEntityDrownRsp",
+            SceneEntityDrownRsp {
+                retcode: retcode.into(),
+                entity_id: req.entity_id,
+            },
+        );
+    }
+}
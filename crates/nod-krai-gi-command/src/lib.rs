@@ -3,7 +3,7 @@ use bevy_ecs::prelude::*;
 use common::gm_util::{parse_command, Command, GachaAction, TpAction};
 use common::player_cache::cache_get_is_tp;
 use common::time_util::unix_timestamp;
-use nod_krai_gi_entity::common::{EntityCounter, Visible};
+use nod_krai_gi_entity::common::{EntityCounter, GlobalAbilityValues, Visible};
 use nod_krai_gi_entity::gadget::spawn_gadget_entity;
 use nod_krai_gi_entity::monster::spawn_monster_entity;
 use nod_krai_gi_event::command::*;
@@ -74,6 +74,7 @@ pub fn debug_command_handler(
                     0,
                     None,
                     0,
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
@@ -109,6 +110,7 @@ pub fn debug_command_handler(
                     None,
                     0,
                     0,
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
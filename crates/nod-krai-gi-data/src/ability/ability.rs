@@ -304,6 +304,58 @@ pub struct AbilityMixinData {
     pub value_steps: Vec<DynamicFloat>,
     #[serde(default)]
     pub remove_applied_modifier: bool,
+    #[serde(default, deserialize_with = "skip_strings_in_vec")]
+    pub actions: Vec<AbilityModifierAction>,
+    #[serde(default)]
+    pub reaction_types: Vec<InternString>,
+    #[serde(default)]
+    pub damage_percentage: Option<DynamicFloat>,
+    #[serde(default)]
+    pub interval: Option<DynamicFloat>,
+    #[serde(default, deserialize_with = "deserialize_string_or_vec")]
+    pub attack_tags: Vec<InternString>,
+    #[serde(default, deserialize_with = "deserialize_string_or_vec")]
+    pub anim_event_names: Vec<InternString>,
+}
+
+impl AbilityMixinData {
+    // whether a hit from anim_event_id meets the mixin's attack conditions, no condition
+    // means every hit does
+    pub fn matches_attack(&self, anim_event_id: &str) -> bool {
+        if !self.anim_event_names.is_empty()
+            && !self
+                .anim_event_names
+                .iter()
+                .any(|anim_event_name| anim_event_name.as_str() == anim_event_id)
+        {
+            return false;
+        }
+
+        self.attack_tags.is_empty()
+            || attack_tag_of_anim_event(anim_event_id).is_some_and(|attack_tag| {
+                self.attack_tags
+                    .iter()
+                    .any(|tag| tag.as_str().eq_ignore_ascii_case(attack_tag))
+            })
+    }
+}
+
+// a hit only names the anim event it came from, the attack tag follows from the event's name
+pub fn attack_tag_of_anim_event(anim_event_id: &str) -> Option<&'static str> {
+    let anim_event_id = anim_event_id.to_ascii_lowercase();
+    if anim_event_id.starts_with("attack") {
+        Some("Normal_Attack")
+    } else if anim_event_id.starts_with("extraattack") {
+        Some("Extra_Attack")
+    } else if anim_event_id.starts_with("fallingattack") {
+        Some("Plunge_Attack")
+    } else if anim_event_id.starts_with("elementalart") {
+        Some("Elemental_Art")
+    } else if anim_event_id.starts_with("elementalburst") {
+        Some("Elemental_Burst")
+    } else {
+        None
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -321,6 +373,9 @@ pub struct AbilityModifierProperty {
     #[serde(alias = "Actor_HealedAddDelta")]
     #[serde(default)]
     pub actor_healed_add_delta: Option<DynamicFloat>,
+    #[serde(alias = "Actor_StaminaCostRatio")]
+    #[serde(default)]
+    pub actor_stamina_cost_ratio: Option<DynamicFloat>,
 }
 
 #[derive(Debug, Default, Copy, Clone, serde::Deserialize, PartialEq, Eq)]
@@ -3,6 +3,7 @@ mod drop_sub_table_excel_config;
 mod drop_table_excel_config;
 mod gacha_banner;
 mod gadget_mapping;
+mod monster_pool;
 mod quest_encryption_key;
 
 pub use combined_drop::*;
@@ -10,6 +11,7 @@ pub use drop_sub_table_excel_config::*;
 pub use drop_table_excel_config::*;
 pub use gacha_banner::*;
 pub use gadget_mapping::*;
+pub use monster_pool::*;
 pub use quest_encryption_key::*;
 
 use paste::paste;
@@ -0,0 +1,67 @@
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+pub static MONSTER_POOL_COLLECTION: OnceLock<std::sync::Arc<HashMap<u32, MonsterPool>>> =
+    OnceLock::new();
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonsterPoolEntry {
+    pub monster_id: u32,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonsterPool {
+    pub pool_id: u32,
+    pub monster_list: Vec<MonsterPoolEntry>,
+}
+
+impl MonsterPool {
+    pub fn load(custom_output_path: &str) {
+        // MonsterPool.json is optional, pool tides just stay empty without it
+        let map = match std::fs::read(format!("{custom_output_path}/MonsterPool.json")) {
+            Ok(json) => match serde_json::from_slice::<Vec<MonsterPool>>(&json) {
+                Ok(pools) => pools.into_iter().map(|pool| (pool.pool_id, pool)).collect(),
+                Err(err) => {
+                    println!("MonsterPool.json parse fail: {}", err);
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+
+        MONSTER_POOL_COLLECTION
+            .set(std::sync::Arc::new(map))
+            .unwrap();
+    }
+
+    pub fn get_pool(pool_id: u32) -> Option<MonsterPool> {
+        MONSTER_POOL_COLLECTION
+            .get()
+            .and_then(|map| map.get(&pool_id).cloned())
+    }
+
+    pub fn random_monster_id(&self) -> Option<u32> {
+        let total_weight: u32 = self.monster_list.iter().map(|entry| entry.weight).sum();
+        if total_weight == 0 {
+            return None;
+        }
+
+        let mut roll = rand::thread_rng().gen_range(0..total_weight);
+        for entry in self.monster_list.iter() {
+            if roll < entry.weight {
+                return Some(entry.monster_id);
+            }
+            roll -= entry.weight;
+        }
+        None
+    }
+}
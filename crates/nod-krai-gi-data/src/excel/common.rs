@@ -109,6 +109,80 @@ lua_enum! {
     }
 }
 
+// same numbering as the client, AttackResult.amplify_reaction_type carries these
+lua_enum! {
+    pub enum ElementReactionType {
+        alias("None")
+        None = 0,
+        alias("Explode")
+        Explode = 1,
+        alias("Stream")
+        Stream = 2,
+        alias("Burning")
+        Burning = 3,
+        alias("Burned")
+        Burned = 4,
+        alias("Wet")
+        Wet = 5,
+        alias("Overgrow")
+        Overgrow = 6,
+        alias("Melt")
+        Melt = 7,
+        alias("Freeze")
+        Freeze = 8,
+        alias("AntiFire")
+        AntiFire = 9,
+        alias("Rock")
+        Rock = 10,
+        alias("SlowDown")
+        SlowDown = 11,
+        alias("Shock")
+        Shock = 12,
+        alias("Wind")
+        Wind = 13,
+        alias("Electric")
+        Electric = 14,
+        alias("Fire")
+        Fire = 15,
+        alias("Superconductor")
+        Superconductor = 16,
+        alias("SwirlFire")
+        SwirlFire = 17,
+        alias("SwirlWater")
+        SwirlWater = 18,
+        alias("SwirlElectric")
+        SwirlElectric = 19,
+        alias("SwirlIce")
+        SwirlIce = 20,
+        alias("SwirlFireAccu")
+        SwirlFireAccu = 21,
+        alias("SwirlWaterAccu")
+        SwirlWaterAccu = 22,
+        alias("SwirlElectricAccu")
+        SwirlElectricAccu = 23,
+        alias("SwirlIceAccu")
+        SwirlIceAccu = 24,
+        alias("StickRock")
+        StickRock = 25,
+        alias("StickWater")
+        StickWater = 26,
+        alias("CrystallizeFire")
+        CrystallizeFire = 27,
+        alias("CrystallizeWater")
+        CrystallizeWater = 28,
+        alias("CrystallizeElectric")
+        CrystallizeElectric = 29,
+        alias("CrystallizeIce")
+        CrystallizeIce = 30,
+        alias("FrozenBroken")
+        FrozenBroken = 31,
+        alias("StickGrass")
+        StickGrass = 32,
+        alias("Overdose")
+        Overdose = 33,
+    }
+}
+
 lua_enum! {
     pub enum EntityType {
         alias("NONE")
@@ -9,6 +9,8 @@ pub struct GatherExcelConfig {
     #[serde(default)]
     pub refresh_id: u32,
     #[serde(default)]
+    pub cd: u32,
+    #[serde(default)]
     pub extra_item_id_vec: Vec<u32>,
     #[serde(default)]
     pub is_forbid_guest: bool,
@@ -0,0 +1,59 @@
+use crate::excel;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+static LEVEL_TAG_BY_DYNAMIC_GROUP: std::sync::OnceLock<Arc<HashMap<u32, u32>>> =
+    std::sync::OnceLock::new();
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelTagExcelConfig {
+    pub id: u32,
+    #[serde(default)]
+    pub scene_id: u32,
+    #[serde(default)]
+    pub level_tag_name: String,
+    #[serde(default)]
+    pub load_dynamic_group_list: Vec<u32>,
+}
+
+pub trait LevelTagExcelConfigKeyed<K> {
+    fn key(&self) -> K;
+
+    fn load(excel_bin_output_path: &str) -> HashMap<K, LevelTagExcelConfig>;
+
+    fn get_level_tag_by_dynamic_group(group_id: u32) -> Option<u32>;
+}
+
+impl LevelTagExcelConfigKeyed<u32> for LevelTagExcelConfig {
+    fn key(&self) -> u32 {
+        self.id
+    }
+
+    fn load(excel_bin_output_path: &str) -> HashMap<u32, LevelTagExcelConfig> {
+        // older dumps have no level tags, scenes then just keep every group loadable
+        let Ok(json) =
+            std::fs::read(format!("{excel_bin_output_path}/LevelTagExcelConfigData.json"))
+        else {
+            println!("LevelTagExcelConfigData.json not found, level tags disabled");
+            return HashMap::new();
+        };
+        let list: Vec<LevelTagExcelConfig> = serde_json::from_slice(&json).unwrap();
+        list.iter().map(|item| (item.key(), item.clone())).collect()
+    }
+
+    fn get_level_tag_by_dynamic_group(group_id: u32) -> Option<u32> {
+        LEVEL_TAG_BY_DYNAMIC_GROUP
+            .get_or_init(|| {
+                let mut level_tag_by_dynamic_group = HashMap::new();
+                for level_tag in excel::level_tag_excel_config_collection::get().values() {
+                    for dynamic_group_id in level_tag.load_dynamic_group_list.iter() {
+                        level_tag_by_dynamic_group.insert(*dynamic_group_id, level_tag.id);
+                    }
+                }
+                Arc::new(level_tag_by_dynamic_group)
+            })
+            .get(&group_id)
+            .copied()
+    }
+}
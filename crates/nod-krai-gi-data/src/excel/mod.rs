@@ -16,6 +16,7 @@ mod env_animal_gather_excel_config;
 mod fetter_data_config;
 mod gadget_excel_config;
 mod gather_excel_config;
+mod level_tag_excel_config;
 mod map_layer_config;
 mod map_layer_floor_config;
 mod map_layer_group_config;
@@ -24,6 +25,7 @@ mod monster_excel_config;
 mod open_state_config;
 
 mod anecdote_excel_config;
+mod world_level_excel_config;
 mod material_excel_config;
 mod proud_skill_excel_config;
 mod reliquary_affix_excel_config;
@@ -36,6 +38,7 @@ mod weapon_excel_config;
 mod weapon_level_excel_config;
 
 pub use anecdote_excel_config::*;
+pub use world_level_excel_config::*;
 pub use avatar_costume_excel_config::*;
 pub use avatar_curve_excel_config::*;
 pub use avatar_excel_config::*;
@@ -52,6 +55,7 @@ pub use env_animal_gather_excel_config::*;
 pub use fetter_data_config::*;
 pub use gadget_excel_config::*;
 pub use gather_excel_config::*;
+pub use level_tag_excel_config::*;
 pub use map_layer_config::*;
 pub use map_layer_floor_config::*;
 pub use map_layer_group_config::*;
@@ -120,6 +124,7 @@ excel_loader! {
     FetterDataConfig;
     GadgetExcelConfig;
     GatherExcelConfig;
+    LevelTagExcelConfig;
     MapLayerConfig;
     MapLayerFloorConfig;
     MapLayerGroupConfig;
@@ -137,4 +142,5 @@ excel_loader! {
     WeaponExcelConfig;
     WeaponLevelExcelConfig;
     AnecdoteExcelConfig;
+    WorldLevelExcelConfig;
 }
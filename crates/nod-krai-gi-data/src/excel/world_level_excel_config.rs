@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldLevelExcelConfig {
+    pub level: u32,
+    #[serde(default)]
+    pub monster_level: u32,
+}
+
+pub trait WorldLevelExcelConfigKeyed<K> {
+    fn key(&self) -> K;
+
+    fn load(excel_bin_output_path: &str) -> HashMap<K, WorldLevelExcelConfig>;
+}
+
+impl WorldLevelExcelConfigKeyed<u32> for WorldLevelExcelConfig {
+    fn key(&self) -> u32 {
+        self.level
+    }
+
+    // older dumps ship without this table, drops then fall back to the entity level
+    fn load(excel_bin_output_path: &str) -> HashMap<u32, WorldLevelExcelConfig> {
+        let Ok(json) = std::fs::read(format!(
+            "{excel_bin_output_path}/WorldLevelExcelConfigData.json"
+        )) else {
+            println!("WorldLevelExcelConfigData.json not found, world level drops disabled");
+            return HashMap::new();
+        };
+        match serde_json::from_slice::<Vec<WorldLevelExcelConfig>>(&json) {
+            Ok(list) => list.iter().map(|item| (item.key(), item.clone())).collect(),
+            Err(err) => {
+                println!("failed to parse WorldLevelExcelConfigData.json: {}", err);
+                HashMap::new()
+            }
+        }
+    }
+}
@@ -326,7 +326,8 @@ mod tests {
         let scene_block_collection_clone =
             Arc::clone(scene::script_cache::SCENE_BLOCK_COLLECTION.get().unwrap());
 
-        for key in scene_block_collection_clone.keys() {
+        for entry in scene_block_collection_clone.iter() {
+            let key = entry.key();
             if key.0 == 3 {
                 println!("Loading scene groups... {:?}", key);
             }
@@ -7,6 +7,14 @@ pub fn get_group_entity_state_cache() -> Arc<GroupEntityStateCache> {
     Arc::clone(GROUP_ENTITY_STATE_CACHE.get_or_init(|| Arc::new(GroupEntityStateCache::new())))
 }
 
+// hp percent as seen by group scripts, rounded up so a living monster never reads 0
+pub fn hp_percent(cur_hp: f32, max_hp: f32) -> u32 {
+    if max_hp <= 0.0 {
+        return 0;
+    }
+    (cur_hp / max_hp * 100.0).ceil().clamp(0.0, 100.0) as u32
+}
+
 pub struct GroupEntityStateCache {
     user_caches: DashMap<u32, UserGroupStateCache>,
 }
@@ -14,6 +22,11 @@ pub struct GroupEntityStateCache {
 #[derive(Clone, Debug, Default)]
 pub struct UserGroupStateCache {
     pub group_states: DashMap<u32, GroupState>,
+    // (group_id, config_id) -> refresh time, 0 = never refresh
+    pub one_off_gadgets: DashMap<(u32, u32), u32>,
+    pub one_off_monsters: DashMap<(u32, u32), u32>,
+    // entity_id -> (group_id, config_id) of spawned monsters
+    pub monster_entities: DashMap<u32, (u32, u32)>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -29,6 +42,10 @@ pub struct MonsterEntityState {
     pub life_state: u32,
     pub cur_hp: f32,
     pub max_hp: f32,
+    pub ai_state: u32,
+    pub aggro_target_uid: u32,
+    pub aggro_uid_list: Vec<u32>,
+    pub pose_id: u32,
 }
 
 #[derive(Clone, Debug)]
@@ -70,8 +87,11 @@ impl GroupEntityStateCache {
         max_hp: f32,
     ) {
         let user_cache = self.get_or_create_user_cache(uid);
+        user_cache
+            .monster_entities
+            .insert(entity_id, (group_id, config_id));
         let group_state = user_cache.group_states.entry(group_id).or_default();
-        group_state.monsters.insert(
+        let replaced = group_state.monsters.insert(
             config_id,
             MonsterEntityState {
                 config_id,
@@ -79,8 +99,15 @@ impl GroupEntityStateCache {
                 life_state: 1,
                 cur_hp,
                 max_hp,
+                ai_state: 0,
+                aggro_target_uid: 0,
+                aggro_uid_list: vec![],
+                pose_id: 0,
             },
         );
+        if let Some(replaced) = replaced.filter(|m| m.entity_id != entity_id) {
+            user_cache.monster_entities.remove(&replaced.entity_id);
+        }
     }
 
     pub fn on_gadget_spawn(
@@ -148,6 +175,46 @@ impl GroupEntityStateCache {
         }
     }
 
+    pub fn on_monster_remove(&self, uid: u32, group_id: u32, config_id: u32) {
+        if let Some(user_cache) = self.user_caches.get(&uid) {
+            if let Some(group_state) = user_cache.group_states.get(&group_id) {
+                if let Some((_, monster)) = group_state.monsters.remove(&config_id) {
+                    user_cache.monster_entities.remove(&monster.entity_id);
+                }
+            }
+        }
+    }
+
+    pub fn on_gadget_remove(&self, uid: u32, group_id: u32, config_id: u32) {
+        if let Some(user_cache) = self.user_caches.get(&uid) {
+            if let Some(group_state) = user_cache.group_states.get(&group_id) {
+                group_state.gadgets.remove(&config_id);
+            }
+        }
+    }
+
+    pub fn on_monster_ai_update(
+        &self,
+        uid: u32,
+        group_id: u32,
+        config_id: u32,
+        ai_state: u32,
+        aggro_target_uid: u32,
+        aggro_uid_list: Vec<u32>,
+        pose_id: u32,
+    ) {
+        if let Some(user_cache) = self.user_caches.get(&uid) {
+            if let Some(group_state) = user_cache.group_states.get(&group_id) {
+                if let Some(mut monster_state) = group_state.monsters.get_mut(&config_id) {
+                    monster_state.ai_state = ai_state;
+                    monster_state.aggro_target_uid = aggro_target_uid;
+                    monster_state.aggro_uid_list = aggro_uid_list;
+                    monster_state.pose_id = pose_id;
+                }
+            }
+        }
+    }
+
     pub fn on_gadget_state_update(
         &self,
         uid: u32,
@@ -164,6 +231,50 @@ impl GroupEntityStateCache {
         }
     }
 
+    pub fn on_gadget_one_off(&self, uid: u32, group_id: u32, config_id: u32, refresh_time: u32) {
+        let user_cache = self.get_or_create_user_cache(uid);
+        user_cache
+            .one_off_gadgets
+            .insert((group_id, config_id), refresh_time);
+    }
+
+    pub fn on_monster_one_off(&self, uid: u32, group_id: u32, config_id: u32, refresh_time: u32) {
+        let user_cache = self.get_or_create_user_cache(uid);
+        user_cache
+            .one_off_monsters
+            .insert((group_id, config_id), refresh_time);
+    }
+
+    pub fn is_gadget_one_off(&self, uid: u32, group_id: u32, config_id: u32, now: u32) -> bool {
+        let Some(user_cache) = self.user_caches.get(&uid) else {
+            return false;
+        };
+        Self::check_one_off(&user_cache.one_off_gadgets, group_id, config_id, now)
+    }
+
+    pub fn is_monster_one_off(&self, uid: u32, group_id: u32, config_id: u32, now: u32) -> bool {
+        let Some(user_cache) = self.user_caches.get(&uid) else {
+            return false;
+        };
+        Self::check_one_off(&user_cache.one_off_monsters, group_id, config_id, now)
+    }
+
+    fn check_one_off(
+        one_off_map: &DashMap<(u32, u32), u32>,
+        group_id: u32,
+        config_id: u32,
+        now: u32,
+    ) -> bool {
+        let Some(refresh_time) = one_off_map.get(&(group_id, config_id)).map(|t| *t) else {
+            return false;
+        };
+        if refresh_time != 0 && refresh_time <= now {
+            one_off_map.remove(&(group_id, config_id));
+            return false;
+        }
+        true
+    }
+
     pub fn get_monster_state(
         &self,
         uid: u32,
@@ -186,6 +297,37 @@ impl GroupEntityStateCache {
         group_state.gadgets.get(&config_id).map(|g| g.clone())
     }
 
+    // returns (group_id, state)
+    pub fn find_monster_by_entity_id(
+        &self,
+        uid: u32,
+        entity_id: u32,
+    ) -> Option<(u32, MonsterEntityState)> {
+        let user_cache = self.user_caches.get(&uid)?;
+        let (group_id, config_id) = *user_cache.monster_entities.get(&entity_id)?;
+        let group_state = user_cache.group_states.get(&group_id)?;
+        let monster = group_state.monsters.get(&config_id)?;
+        // a respawn reuses the config_id under a new entity_id
+        (monster.entity_id == entity_id).then(|| (group_id, monster.clone()))
+    }
+
+    // returns (group_id, state)
+    pub fn find_gadget_by_entity_id(
+        &self,
+        uid: u32,
+        entity_id: u32,
+    ) -> Option<(u32, GadgetEntityState)> {
+        let user_cache = self.user_caches.get(&uid)?;
+        let found = user_cache.group_states.iter().find_map(|group_state| {
+            group_state
+                .gadgets
+                .iter()
+                .find(|g| g.entity_id == entity_id)
+                .map(|g| (*group_state.key(), g.value().clone()))
+        });
+        found
+    }
+
     pub fn get_alive_monster_count(&self, uid: u32, group_id: u32) -> u32 {
         if let Some(user_cache) = self.user_caches.get(&uid) {
             if let Some(group_state) = user_cache.group_states.get(&group_id) {
@@ -206,13 +348,14 @@ impl GroupEntityStateCache {
     pub fn remove_group(&self, uid: u32, group_id: u32) {
         if let Some(user_cache) = self.user_caches.get(&uid) {
             user_cache.group_states.remove(&group_id);
+            user_cache
+                .monster_entities
+                .retain(|_, (monster_group_id, _)| *monster_group_id != group_id);
         }
     }
 
     pub fn reset_group(&self, uid: u32, group_id: u32) {
-        if let Some(user_cache) = self.user_caches.get(&uid) {
-            user_cache.group_states.remove(&group_id);
-        }
+        self.remove_group(uid, group_id);
     }
 }
 
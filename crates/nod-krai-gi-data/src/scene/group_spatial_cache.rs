@@ -53,6 +53,21 @@ pub fn store_spatial_cache(scene_id: u32, cache: GroupSpatialCache) {
     cache_collection.insert(scene_id, cache);
 }
 
+// 热更后更新单个 group 的范围, rtree 下次查询时重建
+pub fn refresh_group_spatial_info(scene_id: u32, group: &SceneGroupTemplate) {
+    let cache_collection = GROUP_SPATIAL_CACHE_COLLECTION.get_or_init(|| Arc::new(DashMap::new()));
+    let Some(mut cache) = cache_collection.get_mut(&scene_id) else {
+        return;
+    };
+    match GroupSpatialInfo::from_scene_group(group) {
+        Some(info) => cache.add_group(scene_id, info),
+        None => {
+            cache.scene_groups.remove(&group.base_info.group_id);
+        }
+    }
+    cache.rtree_cache = Arc::new(OnceLock::new());
+}
+
 pub fn has_spatial_cache(scene_id: u32) -> bool {
     let cache_collection = GROUP_SPATIAL_CACHE_COLLECTION.get_or_init(|| Arc::new(DashMap::new()));
     cache_collection.contains_key(&scene_id)
@@ -72,18 +87,16 @@ pub fn build_scene_spatial_cache(
 
     let scene_blocks: Vec<_> = block_collection
         .iter()
-        .filter(|entry| entry.0 .0 == scene_id)
+        .filter(|entry| entry.key().0 == scene_id)
+        .map(|entry| (*entry.key(), entry.value().groups.clone()))
         .collect();
 
-    for entry in scene_blocks {
-        let (scene_id_key, block_id) = entry.0;
-        let block = entry.1;
-
-        for block_group in &block.groups {
+    for ((scene_id_key, block_id), groups) in scene_blocks {
+        for block_group in &groups {
             let group_id = block_group.id;
 
             if let Some(group) =
-                load_scene_group_from_cache(lua_root, &lua, *scene_id_key, *block_id, group_id)
+                load_scene_group_from_cache(lua_root, &lua, scene_id_key, block_id, group_id)
             {
                 if let Some(info) = GroupSpatialInfo::from_scene_group(&group) {
                     cache.add_group(scene_id, info);
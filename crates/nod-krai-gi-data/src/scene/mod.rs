@@ -653,6 +653,10 @@ pub enum ScriptCommand {
         param3: u32,
         param4: u32,
     },
+    PauseChallenge {
+        group_id: u32,
+        challenge_index: u32,
+    },
     StopChallenge {
         group_id: u32,
         challenge_index: u32,
@@ -667,18 +671,75 @@ pub enum ScriptCommand {
         group_id: u32,
         quest_param: String,
     },
+    // pos/rot override the group template, server_global_values are the initial SGV
     CreateGadget {
         group_id: u32,
         config_id: u32,
+        pos: Option<(f32, f32, f32)>,
+        rot: Option<(f32, f32, f32)>,
+        server_global_values: Vec<(String, f32)>,
     },
     CreateMonster {
         group_id: u32,
         config_id: u32,
+        pos: Option<(f32, f32, f32)>,
+        rot: Option<(f32, f32, f32)>,
+        server_global_values: Vec<(String, f32)>,
     },
     KillEntityByConfigId {
         group_id: u32,
         config_id: u32,
     },
+    RemoveEntityByConfigId {
+        group_id: u32,
+        entity_type: u32,
+        config_id: u32,
+    },
+    UnlockScenePoint {
+        scene_id: u32,
+        point_id: u32,
+    },
+    LockScenePoint {
+        scene_id: u32,
+        point_id: u32,
+    },
+    HideScenePoint {
+        scene_id: u32,
+        point_id: u32,
+    },
+    UnhideScenePoint {
+        scene_id: u32,
+        point_id: u32,
+    },
+    AddSceneTag {
+        scene_id: u32,
+        scene_tag_id: u32,
+    },
+    DelSceneTag {
+        scene_id: u32,
+        scene_tag_id: u32,
+    },
+    ChangeToTargetLevelTag {
+        level_tag_id: u32,
+    },
+    SetPlayerGroupVisionType {
+        uid_list: Vec<u32>,
+        vision_type_list: Vec<u32>,
+    },
+    AddPlayerGroupVisionType {
+        uid_list: Vec<u32>,
+        vision_type_list: Vec<u32>,
+    },
+    DelPlayerGroupVisionType {
+        uid_list: Vec<u32>,
+        vision_type_list: Vec<u32>,
+    },
+    ForbidPlayerRegionVision {
+        uid: u32,
+    },
+    RevertPlayerRegionVision {
+        uid: u32,
+    },
     SetIsAllowUseSkill {
         allow: bool,
     },
@@ -759,11 +820,52 @@ pub enum ScriptCommand {
         group_id: u32,
         suite_id: u32,
     },
+    GoBackGroupSuite {
+        group_id: u32,
+    },
+    KillExtraGroupSuite {
+        group_id: u32,
+        suite_id: u32,
+    },
+    SetFlowSuite {
+        group_id: u32,
+        suite_id: u32,
+    },
+    GoToFlowSuite {
+        group_id: u32,
+        suite_id: u32,
+    },
+    AddExtraFlowSuite {
+        group_id: u32,
+        suite_id: u32,
+        policy: u32,
+    },
+    RemoveExtraFlowSuite {
+        group_id: u32,
+        suite_id: u32,
+        policy: u32,
+    },
+    KillExtraFlowSuite {
+        group_id: u32,
+        suite_id: u32,
+        policy: u32,
+    },
     SetGroupVariableValueByGroup {
         group_id: u32,
         name: String,
         value: i32,
     },
+    SetGroupLogicStateValue {
+        group_id: u32,
+        name: String,
+        value: i32,
+    },
+    SetGroupDead {
+        group_id: u32,
+    },
+    RecoverDeadGroup {
+        group_id: u32,
+    },
     ChangeGroupVariableValueByGroup {
         group_id: u32,
         name: String,
@@ -779,14 +881,30 @@ pub enum ScriptCommand {
         source: String,
     },
     InitTimeAxis {
+        group_id: u32,
         identifier: String,
         delays: Vec<f64>,
         should_loop: bool,
     },
     EndTimeAxis {
+        group_id: u32,
+        identifier: String,
+    },
+    EndAllTimeAxis {
+        group_id: u32,
+    },
+    PauseTimeAxis {
+        group_id: u32,
+        identifier: String,
+    },
+    ContinueTimeAxis {
+        group_id: u32,
         identifier: String,
     },
-    EndAllTimeAxis,
+    CreateGroupTrigger {
+        group_id: u32,
+        trigger_name: String,
+    },
     TransPlayerToPos {
         uid_list: Vec<u32>,
         pos: (f32, f32, f32),
@@ -811,4 +929,53 @@ pub enum ScriptCommand {
         tide_count: u32,
         scene_limit: u32,
     },
+    AutoPoolMonsterTide {
+        group_id: u32,
+        source_id: u32,
+        pool_id_list: Vec<u32>,
+        point_config_id_list: Vec<u32>,
+        total_count: u32,
+        min_count: u32,
+        max_count: u32,
+        tag: String,
+    },
+    EndMonsterTide {
+        group_id: u32,
+        source_id: u32,
+        end_type: u32,
+    },
+    KillMonsterTide {
+        group_id: u32,
+        source_id: u32,
+    },
+    PauseMonsterTide {
+        group_id: u32,
+        source_id: u32,
+    },
+    ContinueMonsterTide {
+        group_id: u32,
+        source_id: u32,
+    },
+    CreateMonstersFromMonsterPool {
+        group_id: u32,
+        tag: String,
+    },
+    SetMonsterHp {
+        group_id: u32,
+        config_id: u32,
+        hp_percent: f32,
+    },
+    SetGadgetHp {
+        group_id: u32,
+        config_id: u32,
+        hp_percent: f32,
+    },
+    LockMonsterHp {
+        group_id: u32,
+        config_id: u32,
+    },
+    UnlockMonsterHp {
+        group_id: u32,
+        config_id: u32,
+    },
 }
@@ -6,6 +6,7 @@ pub struct BlockGroup {
     pub id: u32,
     pub refresh_id: Option<u32>,
     pub pos: Position,
+    pub vision_type: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -23,6 +23,8 @@ pub struct Monster {
     pub disable_wander: Option<bool>,
     pub pose_id: Option<u32>,
     pub area_id: Option<u32>,
+    #[serde(rename = "isOneoff")]
+    pub is_one_off: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -40,6 +42,8 @@ pub struct Gadget {
     pub state: Option<GadgetState>,
     pub persistent: Option<bool>,
     pub area_id: Option<u32>,
+    #[serde(rename = "isOneoff")]
+    pub is_one_off: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -49,6 +53,7 @@ pub struct Region {
     pub radius: Option<f32>,
     pub pos: Position,
     pub area_id: Option<u32>,
+    pub vision_type_list: Option<Vec<u32>>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -1,4 +1,7 @@
-use crate::excel::common::{ElementType, EntityType, QuestState, VisionLevelType};
+use crate::excel::common::{
+    ElementReactionType, ElementType, EntityType, QuestState, VisionLevelType,
+};
+use crate::scene::group_spatial_cache::refresh_group_spatial_info;
 use crate::scene::scene_block_template::*;
 use crate::scene::scene_config_template::*;
 use crate::scene::scene_group_template::*;
@@ -19,7 +22,7 @@ use std::{
 pub static SCENE_CONFIG_COLLECTION: OnceLock<Arc<HashMap<u32, SceneConfigTemplate>>> =
     OnceLock::new();
 
-pub static SCENE_BLOCK_COLLECTION: OnceLock<Arc<HashMap<(u32, u32), SceneBlockTemplate>>> =
+pub static SCENE_BLOCK_COLLECTION: OnceLock<Arc<DashMap<(u32, u32), SceneBlockTemplate>>> =
     OnceLock::new();
 
 pub static SCENE_GROUP_COLLECTION: OnceLock<Arc<DashMap<u32, Option<SceneGroupTemplate>>>> =
@@ -36,18 +39,42 @@ pub fn get_scene_config_collection() -> Arc<HashMap<u32, SceneConfigTemplate>> {
 
 pub static SCENE_LUA_VM: OnceLock<Lua> = OnceLock::new();
 
+// precompiled assets/lua/common modules, shared read-only by every world's lua state
+pub static SCENE_LUA_BYTECODE: OnceLock<Arc<HashMap<String, Vec<u8>>>> = OnceLock::new();
+
 pub fn load_lua_vm(root: &str) {
     let lua = Lua::new();
 
     register_all_enums(&lua);
 
     let modules = load_lua_directory(root);
+    let bytecode = Arc::new(compile_lua_modules(&lua, modules));
 
-    install_memory_require(&lua, modules);
+    install_memory_require(&lua, &bytecode);
 
+    SCENE_LUA_BYTECODE.set(bytecode).unwrap();
     SCENE_LUA_VM.set(lua).unwrap();
 }
 
+/// 新建一个独立的 lua state, 只共享预编译好的 common 字节码
+pub fn new_scene_lua_state() -> Lua {
+    let lua = Lua::new();
+
+    register_all_enums(&lua);
+
+    if let Some(bytecode) = SCENE_LUA_BYTECODE.get() {
+        install_memory_require(&lua, bytecode);
+    }
+
+    lua
+}
+
+pub fn compile_lua_chunk(lua: &Lua, name: &str, code: &str) -> mlua::Result<Vec<u8>> {
+    let code = code.replace("ScriptLib.", "ScriptLib:");
+    let function = lua.load(&code).set_name(name).into_function()?;
+    Ok(function.dump(false))
+}
+
 pub fn register_all_enums(lua: &Lua) {
     // 你所有的枚举都在这里统一注册
     inject_enum::<EventType>(lua, "EventType").unwrap();
@@ -58,6 +85,7 @@ pub fn register_all_enums(lua: &Lua) {
     inject_enum::<FatherChallengeProperty>(lua, "FatherChallengeProperty").unwrap();
     inject_enum::<ChallengeEventMarkType>(lua, "ChallengeEventMarkType").unwrap();
     inject_enum::<ElementType>(lua, "ElementType").unwrap();
+    inject_enum::<ElementReactionType>(lua, "ElementReactionType").unwrap();
     inject_enum::<EntityType>(lua, "EntityType").unwrap();
     inject_enum::<QuestState>(lua, "QuestState").unwrap();
     inject_enum::<VisionLevelType>(lua, "VisionLevelType").unwrap();
@@ -91,17 +119,38 @@ fn scan_dir(root: &Path, path: &Path, map: &mut HashMap<String, String>) {
     }
 }
 
-fn install_memory_require(lua: &Lua, modules: HashMap<String, String>) {
+fn compile_lua_modules(lua: &Lua, modules: HashMap<String, String>) -> HashMap<String, Vec<u8>> {
+    modules
+        .into_iter()
+        .filter_map(
+            |(module_name, code)| match compile_lua_chunk(lua, &module_name, &code) {
+                Ok(bytecode) => Some((module_name, bytecode)),
+                Err(err) => {
+                    println!("compile lua module {} error: {}", module_name, err);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+fn install_memory_require(lua: &Lua, bytecode: &Arc<HashMap<String, Vec<u8>>>) {
     let package: Table = lua.globals().get("package").unwrap();
     let preload: Table = package.get("preload").unwrap();
 
-    modules.into_iter().for_each(|(module_name, code)| {
-        let code = code.replace("ScriptLib.", "ScriptLib:");
+    bytecode.keys().for_each(|module_name| {
+        let bytecode = Arc::clone(bytecode);
+        let name = module_name.clone();
         let loader = lua
-            .create_function(move |lua, _: ()| lua.load(&code).into_function())
+            .create_function(move |lua, _: ()| {
+                lua.load(bytecode[&name].as_slice())
+                    .set_name(name.as_str())
+                    .set_mode(mlua::ChunkMode::Binary)
+                    .into_function()
+            })
             .unwrap();
 
-        preload.set(module_name, loader).unwrap();
+        preload.set(module_name.as_str(), loader).unwrap();
     });
 }
 
@@ -157,7 +206,9 @@ pub fn init_scene_static_templates(root: &str) {
     }
 
     SCENE_CONFIG_COLLECTION.set(Arc::new(config_map)).ok();
-    SCENE_BLOCK_COLLECTION.set(Arc::new(block_map)).ok();
+    SCENE_BLOCK_COLLECTION
+        .set(Arc::new(block_map.into_iter().collect()))
+        .ok();
     SCENE_GROUP_COLLECTION.set(Arc::new(DashMap::new())).ok();
 }
 
@@ -193,6 +244,79 @@ fn load_scene_block_file(path: &Path) -> Option<SceneBlockTemplate> {
     })
 }
 
+pub fn check_lua_syntax(name: &str, code: &str) -> Result<(), String> {
+    let lua = Lua::new();
+    let code = code.replace("ScriptLib.", "ScriptLib:");
+    lua.load(&code)
+        .set_name(name)
+        .into_function()
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+pub fn reload_scene_block(root: &str, scene_id: u32, block_id: u32) -> Result<(), String> {
+    let block_path = Path::new(root)
+        .join(scene_id.to_string())
+        .join(format!("scene{}_block{}.lua", scene_id, block_id));
+
+    let code = common::string_util::read_utf8_no_bom(&block_path).map_err(|err| err.to_string())?;
+    check_lua_syntax(&format!("scene{}_block{}", scene_id, block_id), &code)?;
+
+    let block = load_scene_block_file(&block_path)
+        .ok_or_else(|| format!("scene{}_block{} parse groups fail", scene_id, block_id))?;
+
+    let scene_block_collection_clone = Arc::clone(
+        SCENE_BLOCK_COLLECTION
+            .get()
+            .ok_or("scene blocks not loaded")?,
+    );
+    scene_block_collection_clone.insert((scene_id, block_id), block);
+    Ok(())
+}
+
+pub fn find_scene_group_block_id(scene_id: u32, group_id: u32) -> Option<u32> {
+    let scene_block_collection_clone = Arc::clone(SCENE_BLOCK_COLLECTION.get()?);
+    let block_id = scene_block_collection_clone
+        .iter()
+        .find(|entry| {
+            entry.key().0 == scene_id && entry.value().groups.iter().any(|g| g.id == group_id)
+        })
+        .map(|entry| entry.key().1);
+    block_id
+}
+
+// 重新读取 group 脚本并替换缓存中的模板, 语法错误会原样返回
+pub fn reload_scene_group(
+    lua_root: &str,
+    scene_id: u32,
+    group_id: u32,
+) -> Result<SceneGroupTemplate, String> {
+    let script_name = format!("scene{}_group{}", scene_id, group_id);
+    let script_path = format!("{}/scene/{}/{}.lua", lua_root, scene_id, script_name);
+
+    let code =
+        common::string_util::read_utf8_no_bom(&script_path).map_err(|err| err.to_string())?;
+    check_lua_syntax(&script_name, &code)?;
+
+    let block_id = find_scene_group_block_id(scene_id, group_id)
+        .ok_or_else(|| format!("{} is not in any block", script_name))?;
+
+    let lua = new_scene_lua_state();
+    let group = load_scene_group(lua_root, &lua, scene_id, block_id, group_id)
+        .ok_or_else(|| format!("{} parse fail", script_name))?;
+
+    let scene_group_collection_clone = Arc::clone(
+        SCENE_GROUP_COLLECTION
+            .get()
+            .ok_or("scene groups not loaded")?,
+    );
+    scene_group_collection_clone.insert(group_id, Some(group.clone()));
+
+    refresh_group_spatial_info(scene_id, &group);
+
+    Ok(group)
+}
+
 pub fn scene_group_is_bad(group_id: u32) -> bool {
     let scene_group_collection_clone = Arc::clone(SCENE_GROUP_COLLECTION.get().unwrap());
 
@@ -0,0 +1,446 @@
+use crate::avatar::CurrentPlayerAvatarMarker;
+use crate::common::{
+    ConfigId, EntityById, FightProperties, GroupId, LifeState, OwnerPlayerUID, ProtocolEntityID,
+    Visible,
+};
+use crate::transform::Transform;
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::prop_type::FightPropType;
+use nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache;
+use nod_krai_gi_data::scene::{EventType, LuaEvt};
+use nod_krai_gi_event::combat::{EntityBeingHitEvent, PlayerMoveEvent};
+use nod_krai_gi_event::lua::LuaTriggerEvent;
+use nod_krai_gi_event::scene::WorldOwnerUID;
+use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_proto::normal::{MonsterAlertChangeNotify, MonsterForceAlertNotify};
+use nod_krai_gi_proto::server_only::VectorBin;
+
+// player within this range of an idle monster gets noticed
+const ALERT_RANGE: f32 = 15.0;
+// alerted monster engages a player this close
+const ENGAGE_RANGE: f32 = 8.0;
+// monster (or its targets) farther than this from the born pos gives up
+const LEASH_RANGE: f32 = 40.0;
+const DISENGAGE_TIMEOUT_MS: u64 = 15_000;
+const RETURN_ARRIVE_RANGE: f32 = 3.0;
+const RETURN_TIMEOUT_MS: u64 = 8_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(u32)]
+pub enum MonsterAiState {
+    #[default]
+    Idle = 0,
+    Alert = 1,
+    Combat = 2,
+    Returning = 3,
+}
+
+#[derive(Clone, Debug)]
+pub struct AggroEntry {
+    pub uid: u32,
+    pub avatar_entity_id: u32,
+    pub threat: f32,
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct MonsterAi {
+    pub state: MonsterAiState,
+    pub state_time: u64,
+    pub last_threat_time: u64,
+    pub born_pos: VectorBin,
+    pub aggro_list: Vec<AggroEntry>,
+    pub target_uid: Option<u32>,
+    pub init_pose_id: u32,
+    pub pose_id: u32,
+}
+
+impl MonsterAi {
+    pub fn new(born_pos: VectorBin, init_pose_id: u32) -> Self {
+        Self {
+            state: MonsterAiState::Idle,
+            state_time: 0,
+            last_threat_time: 0,
+            born_pos,
+            aggro_list: vec![],
+            target_uid: None,
+            init_pose_id,
+            pose_id: init_pose_id,
+        }
+    }
+
+    // the scripted pose (sleeping, sitting...) is dropped once the monster fights
+    // and taken back when it is idle at its spawn again
+    fn set_state(&mut self, state: MonsterAiState, now: u64) {
+        self.state = state;
+        self.state_time = now;
+        match state {
+            MonsterAiState::Idle => self.pose_id = self.init_pose_id,
+            MonsterAiState::Combat => self.pose_id = 0,
+            MonsterAiState::Alert | MonsterAiState::Returning => {}
+        }
+    }
+
+    pub fn add_threat(&mut self, uid: u32, avatar_entity_id: u32, threat: f32, now: u64) {
+        match self.aggro_list.iter_mut().find(|entry| entry.uid == uid) {
+            None => self.aggro_list.push(AggroEntry {
+                uid,
+                avatar_entity_id,
+                threat,
+            }),
+            Some(entry) => {
+                entry.threat += threat;
+                if avatar_entity_id != 0 {
+                    entry.avatar_entity_id = avatar_entity_id;
+                }
+            }
+        }
+        self.last_threat_time = now;
+        self.select_target();
+    }
+
+    pub fn remove_threat(&mut self, uid: u32) {
+        self.aggro_list.retain(|entry| entry.uid != uid);
+        self.select_target();
+    }
+
+    pub fn clear_threat(&mut self) {
+        self.aggro_list.clear();
+        self.target_uid = None;
+    }
+
+    // highest threat wins, earlier entries win ties
+    fn select_target(&mut self) {
+        self.target_uid = self
+            .aggro_list
+            .iter()
+            .fold(None::<&AggroEntry>, |best, entry| match best {
+                Some(best) if best.threat >= entry.threat => Some(best),
+                _ => Some(entry),
+            })
+            .map(|entry| entry.uid);
+    }
+
+    pub fn target_avatar_entity_id(&self) -> u32 {
+        self.target_uid
+            .and_then(|uid| self.aggro_list.iter().find(|entry| entry.uid == uid))
+            .map(|entry| entry.avatar_entity_id)
+            .unwrap_or_default()
+    }
+}
+
+fn distance_xz(a: &VectorBin, b: (f32, f32, f32)) -> f32 {
+    ((a.x - b.0).powi(2) + (a.z - b.2).powi(2)).sqrt()
+}
+
+fn notify_alert_change(message_output: &MessageOutput, entity_id: u32, ai: &MonsterAi) {
+    let is_alert = matches!(ai.state, MonsterAiState::Alert | MonsterAiState::Combat);
+    message_output.send_to_all(
+        "MonsterAlertChangeNotify",
+        MonsterAlertChangeNotify {
+            monster_entity_list: vec![entity_id],
+            is_alert: is_alert as u32,
+            avatar_entity_id: if is_alert {
+                ai.target_avatar_entity_id()
+            } else {
+                0
+            },
+        },
+    );
+}
+
+fn sync_ai_state_cache(
+    world_owner_uid: u32,
+    group_id: Option<&GroupId>,
+    config_id: Option<&ConfigId>,
+    ai: &MonsterAi,
+) {
+    let (Some(group_id), Some(config_id)) = (group_id, config_id) else {
+        return;
+    };
+    get_group_entity_state_cache().on_monster_ai_update(
+        world_owner_uid,
+        group_id.0,
+        config_id.0,
+        ai.state as u32,
+        ai.target_uid.unwrap_or_default(),
+        ai.aggro_list.iter().map(|entry| entry.uid).collect(),
+        ai.pose_id,
+    );
+}
+
+pub fn sync_spawned_monster_ai(
+    monsters: Query<(&MonsterAi, Option<&GroupId>, Option<&ConfigId>), Added<MonsterAi>>,
+    world_owner_uid: Res<WorldOwnerUID>,
+) {
+    for (ai, group_id, config_id) in monsters.iter() {
+        sync_ai_state_cache(world_owner_uid.0, group_id, config_id, ai);
+    }
+}
+
+fn enter_combat(
+    message_output: &MessageOutput,
+    lua_trigger_events: &mut MessageWriter<LuaTriggerEvent>,
+    entity_id: u32,
+    group_id: Option<&GroupId>,
+    config_id: Option<&ConfigId>,
+    ai: &mut MonsterAi,
+    now: u64,
+) {
+    let was_alert = ai.state == MonsterAiState::Alert;
+    ai.set_state(MonsterAiState::Combat, now);
+
+    if !was_alert {
+        notify_alert_change(message_output, entity_id, ai);
+    }
+    message_output.send_to_all(
+        "MonsterForceAlertNotify",
+        MonsterForceAlertNotify {
+            monster_entity_id: entity_id,
+        },
+    );
+
+    if let (Some(group_id), Some(config_id)) = (group_id, config_id) {
+        lua_trigger_events.write(LuaTriggerEvent {
+            group_id: group_id.0,
+            event_type: EventType::EventMonsterBattle,
+            evt: LuaEvt {
+                param1: config_id.0,
+                param2: 0,
+                param3: 0,
+                source_eid: entity_id,
+                target_eid: ai.target_avatar_entity_id(),
+            },
+        });
+    }
+}
+
+pub fn handle_monster_ai_on_hit(
+    index: Res<EntityById>,
+    mut events: MessageReader<EntityBeingHitEvent>,
+    attackers: Query<&OwnerPlayerUID>,
+    avatars: Query<(&ProtocolEntityID, &OwnerPlayerUID), With<CurrentPlayerAvatarMarker>>,
+    mut monsters: Query<(
+        &ProtocolEntityID,
+        &LifeState,
+        &mut MonsterAi,
+        Option<&GroupId>,
+        Option<&ConfigId>,
+    )>,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+    message_output: Res<MessageOutput>,
+    world_owner_uid: Res<WorldOwnerUID>,
+) {
+    let now = ::common::time_util::unix_timestamp_ms();
+
+    for EntityBeingHitEvent(_, attack_result) in events.read() {
+        let Some(defense_entity) = index.0.get(&attack_result.defense_id) else {
+            continue;
+        };
+        let Ok((entity_id, life_state, mut ai, group_id, config_id)) =
+            monsters.get_mut(*defense_entity)
+        else {
+            continue;
+        };
+        if *life_state == LifeState::Dead || ai.state == MonsterAiState::Returning {
+            continue;
+        }
+
+        // only player owned attackers build up threat, monsters hitting each other don't
+        let Some(attacker_uid) = index
+            .0
+            .get(&attack_result.attacker_id)
+            .and_then(|attacker| attackers.get(*attacker).ok())
+            .map(|owner_uid| owner_uid.0)
+        else {
+            continue;
+        };
+
+        let avatar_entity_id = avatars
+            .iter()
+            .find(|(_, owner_uid)| owner_uid.0 == attacker_uid)
+            .map(|(avatar_entity_id, _)| avatar_entity_id.0)
+            .unwrap_or_default();
+
+        ai.add_threat(
+            attacker_uid,
+            avatar_entity_id,
+            attack_result.damage.max(1.0),
+            now,
+        );
+
+        if ai.state != MonsterAiState::Combat {
+            enter_combat(
+                &message_output,
+                &mut lua_trigger_events,
+                entity_id.0,
+                group_id,
+                config_id,
+                &mut ai,
+                now,
+            );
+        }
+
+        sync_ai_state_cache(world_owner_uid.0, group_id, config_id, &ai);
+    }
+}
+
+pub fn handle_monster_ai_on_player_move(
+    mut events: MessageReader<PlayerMoveEvent>,
+    avatars: Query<(&ProtocolEntityID, &OwnerPlayerUID), With<CurrentPlayerAvatarMarker>>,
+    mut monsters: Query<
+        (
+            &ProtocolEntityID,
+            &LifeState,
+            &Transform,
+            &mut MonsterAi,
+            Option<&GroupId>,
+            Option<&ConfigId>,
+        ),
+        With<Visible>,
+    >,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+    message_output: Res<MessageOutput>,
+    world_owner_uid: Res<WorldOwnerUID>,
+) {
+    let now = ::common::time_util::unix_timestamp_ms();
+
+    for PlayerMoveEvent(uid, _, pos, _) in events.read() {
+        let avatar_entity_id = avatars
+            .iter()
+            .find(|(_, owner_uid)| owner_uid.0 == *uid)
+            .map(|(avatar_entity_id, _)| avatar_entity_id.0)
+            .unwrap_or_default();
+
+        for (entity_id, life_state, transform, mut ai, group_id, config_id) in monsters.iter_mut() {
+            if *life_state == LifeState::Dead {
+                continue;
+            }
+
+            let distance = distance_xz(&transform.position, *pos);
+            let old_state = ai.state;
+            let old_target = ai.target_uid;
+
+            match ai.state {
+                MonsterAiState::Idle => {
+                    if distance <= ALERT_RANGE {
+                        ai.add_threat(*uid, avatar_entity_id, 0.0, now);
+                        ai.set_state(MonsterAiState::Alert, now);
+                        notify_alert_change(&message_output, entity_id.0, &ai);
+                    }
+                }
+                MonsterAiState::Alert => {
+                    if distance <= ENGAGE_RANGE {
+                        ai.add_threat(*uid, avatar_entity_id, 0.0, now);
+                        enter_combat(
+                            &message_output,
+                            &mut lua_trigger_events,
+                            entity_id.0,
+                            group_id,
+                            config_id,
+                            &mut ai,
+                            now,
+                        );
+                    } else if distance <= ALERT_RANGE {
+                        ai.add_threat(*uid, avatar_entity_id, 0.0, now);
+                    } else if distance > ALERT_RANGE * 1.5 {
+                        ai.remove_threat(*uid);
+                        if ai.aggro_list.is_empty() {
+                            ai.set_state(MonsterAiState::Idle, now);
+                            notify_alert_change(&message_output, entity_id.0, &ai);
+                        }
+                    }
+                }
+                MonsterAiState::Combat => {
+                    if distance <= ENGAGE_RANGE {
+                        ai.add_threat(*uid, avatar_entity_id, 0.0, now);
+                    } else if distance_xz(&ai.born_pos, *pos) > LEASH_RANGE {
+                        ai.remove_threat(*uid);
+                        if ai.aggro_list.is_empty() {
+                            ai.clear_threat();
+                            ai.set_state(MonsterAiState::Returning, now);
+                            notify_alert_change(&message_output, entity_id.0, &ai);
+                        }
+                    }
+                }
+                MonsterAiState::Returning => {}
+            }
+
+            if ai.state != old_state || ai.target_uid != old_target {
+                sync_ai_state_cache(world_owner_uid.0, group_id, config_id, &ai);
+            }
+        }
+    }
+}
+
+pub fn tick_monster_ai(
+    avatars: Query<(&OwnerPlayerUID, &LifeState, &Transform), With<CurrentPlayerAvatarMarker>>,
+    mut monsters: Query<(
+        &ProtocolEntityID,
+        &LifeState,
+        &Transform,
+        &mut MonsterAi,
+        &mut FightProperties,
+        Option<&GroupId>,
+        Option<&ConfigId>,
+    )>,
+    message_output: Res<MessageOutput>,
+    world_owner_uid: Res<WorldOwnerUID>,
+) {
+    let now = ::common::time_util::unix_timestamp_ms();
+
+    for (entity_id, life_state, transform, mut ai, mut fight_props, group_id, config_id) in
+        monsters.iter_mut()
+    {
+        if *life_state == LifeState::Dead {
+            continue;
+        }
+
+        let born_distance = distance_xz(
+            &transform.position,
+            (ai.born_pos.x, ai.born_pos.y, ai.born_pos.z),
+        );
+
+        match ai.state {
+            MonsterAiState::Combat => {
+                // a target standing its ground still counts as a threat
+                let target_nearby = avatars
+                    .iter()
+                    .any(|(owner_uid, avatar_life_state, avatar)| {
+                        let avatar_pos = (avatar.position.x, avatar.position.y, avatar.position.z);
+                        *avatar_life_state != LifeState::Dead
+                            && ai.aggro_list.iter().any(|entry| entry.uid == owner_uid.0)
+                            && distance_xz(&transform.position, avatar_pos) <= LEASH_RANGE
+                    });
+                if target_nearby {
+                    ai.last_threat_time = now;
+                }
+
+                let leashed = born_distance > LEASH_RANGE;
+                if leashed || now.saturating_sub(ai.last_threat_time) > DISENGAGE_TIMEOUT_MS {
+                    ai.clear_threat();
+                    ai.set_state(MonsterAiState::Returning, now);
+                    notify_alert_change(&message_output, entity_id.0, &ai);
+
+                    // leashed monsters heal back up like the client does
+                    if leashed {
+                        let max_hp = fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP);
+                        if fight_props.get_property(FightPropType::FIGHT_PROP_CUR_HP) < max_hp {
+                            fight_props.set_property(FightPropType::FIGHT_PROP_CUR_HP, max_hp);
+                        }
+                    }
+
+                    sync_ai_state_cache(world_owner_uid.0, group_id, config_id, &ai);
+                }
+            }
+            MonsterAiState::Returning => {
+                if born_distance <= RETURN_ARRIVE_RANGE
+                    || now.saturating_sub(ai.state_time) > RETURN_TIMEOUT_MS
+                {
+                    ai.set_state(MonsterAiState::Idle, now);
+                    sync_ai_state_cache(world_owner_uid.0, group_id, config_id, &ai);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use crate::fight_props;
 use bevy_ecs::prelude::*;
 use common::string_util::InternString;
-use nod_krai_gi_data::ability::{get_ability_data, AbilityData, AbilityModifier};
+use nod_krai_gi_data::ability::{get_ability_data, AbilityData, AbilityMixinData, AbilityModifier};
 use nod_krai_gi_data::excel::common::EquipType;
 use nod_krai_gi_data::excel::{
     reliquary_affix_excel_config_collection, reliquary_excel_config_collection,
@@ -28,6 +29,8 @@ pub struct BlockId(pub u32);
 pub struct GroupId(pub u32);
 #[derive(Component)]
 pub struct ConfigId(pub u32);
+#[derive(Component)]
+pub struct OneOff;
 
 #[derive(Component)]
 pub struct DropTag(pub Option<String>);
@@ -35,6 +38,10 @@ pub struct DropTag(pub Option<String>);
 #[derive(Component)]
 pub struct ChestDropId(pub u32);
 
+// set once the death drops are out, a dead entity never drops again
+#[derive(Component)]
+pub struct Dropped;
+
 #[derive(Component)]
 pub struct Level(pub u32);
 
@@ -59,6 +66,28 @@ pub struct FightProperties(pub HashMap<FightPropType, f32>, pub HashSet<FightPro
 #[derive(Component, Default)]
 pub struct GlobalAbilityValues(pub HashMap<InternString, f32>);
 
+impl GlobalAbilityValues {
+    // server global values, the SGV_ prefixed entries the client needs on appear
+    pub fn build_sgv_sync_info(&self) -> nod_krai_gi_proto::normal::AbilitySyncStateInfo {
+        use nod_krai_gi_proto::normal::{ability_string, AbilityScalarValueEntry, AbilityString};
+
+        nod_krai_gi_proto::normal::AbilitySyncStateInfo {
+            sgv_dynamic_value_map: self
+                .0
+                .iter()
+                .filter(|(key, _)| key.as_str().starts_with("SGV_"))
+                .map(|(key, value)| AbilityScalarValueEntry {
+                    float_value: *value,
+                    key: Some(AbilityString {
+                        r#type: Some(ability_string::Type::Str(key.as_str().to_string())),
+                    }),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Component, Default)]
 pub struct InstancedAbilities {
     pub list: Vec<InstancedAbility>,
@@ -165,6 +194,15 @@ impl InstancedAbilities {
         }
     }
 
+    pub fn find_by_ability_name(
+        &self,
+        ability_name: &InternString,
+    ) -> Option<(u32, &InstancedAbility)> {
+        self.by_name
+            .get(ability_name)
+            .map(|&index| (index as u32, &self.list[index]))
+    }
+
     pub fn find_by_instanced_ability_id(
         &self,
         instanced_ability_id: u32,
@@ -203,6 +241,9 @@ pub struct InstancedModifiers {
     pub modifiers: HashMap<u32, InstancedModifier>,
 }
 
+// client ids stay within 1..=2000, modifiers applied by the server count up past them
+pub const MAX_CLIENT_MODIFIER_ID: u32 = 2000;
+
 impl InstancedModifiers {
     /// look up a modifier in either sub‑map
     pub fn get(&self, id: &u32) -> Option<&InstancedModifier> {
@@ -228,6 +269,16 @@ impl InstancedModifiers {
             .map(|(_, m)| m)
     }
 
+    pub fn next_server_modifier_id(&self) -> u32 {
+        self.modifiers
+            .keys()
+            .copied()
+            .filter(|id| *id > MAX_CLIENT_MODIFIER_ID)
+            .max()
+            .unwrap_or(MAX_CLIENT_MODIFIER_ID)
+            + 1
+    }
+
     pub fn insert(&mut self, id: u32, ctrl: InstancedModifier) {
         self.modifiers.insert(id, ctrl);
     }
@@ -244,6 +295,18 @@ pub struct InstancedModifier {
     pub target_entity: Option<Entity>,
     pub ability_index: Option<u32>,
     pub modifier_data: Option<&'static AbilityModifier>,
+    pub modifier_local_id: i32,
+    pub timer: Option<ModifierTimer>,
+}
+
+// server side schedule of a modifier's duration and think interval
+pub struct ModifierTimer {
+    pub expire_at: Option<Instant>,
+    pub think_interval: Option<Duration>,
+    pub next_think_at: Option<Instant>,
+    // protocol version of the client that added the modifier, used to encode the removal,
+    // none for modifiers the server applied itself, those are announced on their own
+    pub version: Option<String>,
 }
 
 impl InstancedModifier {
@@ -260,8 +323,36 @@ impl InstancedModifier {
             target_entity,
             ability_index: Some(ability_index),
             modifier_data: None,
+            modifier_local_id: 0,
+            timer: None,
         }
     }
+
+    pub fn is_server_added(&self) -> bool {
+        self.instanced_modifier_id > MAX_CLIENT_MODIFIER_ID
+    }
+}
+
+// ModifyDamage mixins running on an entity, its outgoing hits get the bonus of every one
+// whose attack conditions they meet
+#[derive(Component, Default)]
+pub struct DamageBonuses(pub Vec<DamageBonus>);
+
+pub struct DamageBonus {
+    pub ability_entity: Entity,
+    pub ability_index: u32,
+    pub mixin: &'static AbilityMixinData,
+    pub damage_percentage: f32,
+}
+
+impl DamageBonuses {
+    pub fn percentage_for(&self, anim_event_id: &str) -> f32 {
+        self.0
+            .iter()
+            .filter(|bonus| bonus.mixin.matches_attack(anim_event_id))
+            .map(|bonus| bonus.damage_percentage)
+            .sum()
+    }
 }
 
 #[derive(Component, Clone, Copy, PartialEq, Eq)]
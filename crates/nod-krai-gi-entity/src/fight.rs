@@ -59,7 +59,20 @@ pub fn notify_fight_properties_change_reason_to_clients(
 ) {
     for event in events.read() {
         match event.change_reason {
-            ChangeReason::ChangeHpReason(_) => {}
+            ChangeReason::ChangeHpReason(change_hp_reason) => {
+                message_output.send_to_all(
+                    "EntityFightPropChangeReasonNotify",
+                    EntityFightPropChangeReasonNotify {
+                        entity_id: event.entity_id,
+                        prop_type: event.prop_type as u32,
+                        prop_delta: event.value,
+                        param_list: event.param_list.clone().unwrap_or_default(),
+                        reason: event.reason as i32,
+                        change_hp_reason: change_hp_reason as i32,
+                        ..Default::default()
+                    },
+                );
+            }
             ChangeReason::ChangeHpDebtsReason(change_hp_debts_reason) => {
                 message_output.send_to_all(
                     "EntityFightPropChangeReasonNotify",
@@ -74,7 +87,20 @@ pub fn notify_fight_properties_change_reason_to_clients(
                     },
                 );
             }
-            ChangeReason::ChangeEnergyReason(_) => {}
+            ChangeReason::ChangeEnergyReason(change_energy_reason) => {
+                message_output.send_to_all(
+                    "EntityFightPropChangeReasonNotify",
+                    EntityFightPropChangeReasonNotify {
+                        entity_id: event.entity_id,
+                        prop_type: event.prop_type as u32,
+                        prop_delta: event.value,
+                        param_list: event.param_list.clone().unwrap_or_default(),
+                        reason: event.reason as i32,
+                        change_energy_reson: change_energy_reason as i32,
+                        ..Default::default()
+                    },
+                );
+            }
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::ability::Ability;
+use crate::one_off::{gather_refresh_time, record_gadget_one_off};
 use crate::util::{create_fight_properties_by_gadget_config, to_protocol_entity_id};
 use crate::{common::*, int_prop_pair, transform::Transform, EntityDisappearEvent};
 use bevy_ecs::{prelude::*, query::QueryData};
@@ -48,6 +49,7 @@ pub struct GadgetBundle {
     pub fight_properties: FightProperties,
     pub instanced_abilities: InstancedAbilities,
     pub instanced_modifiers: InstancedModifiers,
+    pub damage_bonuses: DamageBonuses,
     pub global_ability_values: GlobalAbilityValues,
     pub life_state: LifeState,
 }
@@ -112,7 +114,7 @@ pub fn notify_appear_gadget_entities(
                 entity_client_data: Some(EntityClientData::default()),
                 entity_environment_info_list: Vec::with_capacity(0),
                 entity_authority_info: Some(EntityAuthorityInfo {
-                    ability_info: Some(AbilitySyncStateInfo::default()),
+                    ability_info: Some(gadget_data.global_ability_values.build_sgv_sync_info()),
                     born_pos: Some(gadget_data.transform.position.into()),
                     client_extra_info: Some(EntityClientExtraInfo {
                         skill_anchor_position: Some(Vector::default()),
@@ -197,6 +199,7 @@ pub fn spawn_gadget_entity(
     drop_tag: Option<String>,
     chest_drop_id: u32,
     state: u32,
+    global_ability_values: GlobalAbilityValues,
 ) -> Option<(u32, Entity, f32, f32)> {
     let gadget_excel_config_collection_clone =
         std::sync::Arc::clone(gadget_excel_config_collection::get());
@@ -266,7 +269,8 @@ pub fn spawn_gadget_entity(
         fight_properties,
         instanced_abilities: inst,
         instanced_modifiers: InstancedModifiers::default(),
-        global_ability_values: GlobalAbilityValues::default(),
+        damage_bonuses: DamageBonuses::default(),
+        global_ability_values,
         life_state: LifeState::Alive,
     });
 
@@ -277,13 +281,17 @@ pub fn handle_gadget_interact(
     mut events: MessageReader<GadgetInteractEvent>,
     index: Res<EntityById>,
     mut commands: Commands,
-    players: Res<Players>,
+    mut players: ResMut<Players>,
+    world_owner_uid: Res<WorldOwnerUID>,
     gadgets: Query<(
         &Level,
         &Transform,
         Option<&GadgetContent>,
         &DropTag,
         &ChestDropId,
+        Option<&GroupId>,
+        Option<&ConfigId>,
+        Option<&OneOff>,
     )>,
     mut item_add_events: MessageWriter<ItemAddEvent>,
     mut item_drop_events: MessageWriter<ItemDropEvent>,
@@ -300,7 +308,16 @@ pub fn handle_gadget_interact(
         match index.0.get(&gadget_entity_id) {
             None => {}
             Some(entity) => match gadgets.get(*entity) {
-                Ok((level, transform, gadget_content, drop_tag, chest_drop_id)) => {
+                Ok((
+                    level,
+                    transform,
+                    gadget_content,
+                    drop_tag,
+                    chest_drop_id,
+                    group_id,
+                    config_id,
+                    one_off,
+                )) => {
                     // animal
                     match env_animal_gather_excel_config_collection_clone.get(gadget_id) {
                         None => {}
@@ -321,6 +338,16 @@ pub fn handle_gadget_interact(
                                 }
                             }
 
+                            if one_off.is_some() {
+                                record_gadget_one_off(
+                                    &mut players,
+                                    world_owner_uid.0,
+                                    group_id,
+                                    config_id,
+                                    0,
+                                );
+                            }
+
                             disappear_events.write(EntityDisappearEvent(
                                 *gadget_entity_id,
                                 VisionType::VisionMiss.into(),
@@ -362,6 +389,16 @@ pub fn handle_gadget_interact(
                                             )],
                                         ));
 
+                                        if one_off.is_some() {
+                                            record_gadget_one_off(
+                                                &mut players,
+                                                world_owner_uid.0,
+                                                group_id,
+                                                config_id,
+                                                0,
+                                            );
+                                        }
+
                                         disappear_events.write(EntityDisappearEvent(
                                             *gadget_entity_id,
                                             VisionType::VisionGatherEscape.into(),
@@ -384,6 +421,18 @@ pub fn handle_gadget_interact(
                                         )],
                                     ));
 
+                                    record_gadget_one_off(
+                                        &mut players,
+                                        world_owner_uid.0,
+                                        group_id,
+                                        config_id,
+                                        if one_off.is_some() {
+                                            0
+                                        } else {
+                                            gather_refresh_time(*gadget_id)
+                                        },
+                                    );
+
                                     disappear_events.write(EntityDisappearEvent(
                                         *gadget_entity_id,
                                         VisionType::VisionGatherEscape.into(),
@@ -429,6 +478,16 @@ pub fn handle_gadget_interact(
                             ));
                         }
 
+                        if one_off.is_some() {
+                            record_gadget_one_off(
+                                &mut players,
+                                world_owner_uid.0,
+                                group_id,
+                                config_id,
+                                0,
+                            );
+                        }
+
                         disappear_events.write(EntityDisappearEvent(
                             *gadget_entity_id,
                             VisionType::VisionGatherEscape.into(),
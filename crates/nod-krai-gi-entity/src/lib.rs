@@ -14,12 +14,14 @@ use nod_krai_gi_event::entity::{
 use nod_krai_gi_event::inventory::ItemDropEvent;
 use nod_krai_gi_event::lua::{LuaTriggerEvent, MonsterKillEvent};
 use nod_krai_gi_event::quest::QuestContentProgressEvent;
-use nod_krai_gi_event::scene::{WorldOwnerUID, WorldVersionConfig};
+use nod_krai_gi_event::scene::{WorldLevel, WorldOwnerUID, WorldVersionConfig};
 use nod_krai_gi_message::event::ClientMessageEvent;
 use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_persistence::Players;
 use std::collections::HashMap;
 
 pub mod ability;
+pub mod ai;
 pub mod avatar;
 pub mod client_gadget;
 pub mod common;
@@ -27,6 +29,7 @@ pub mod fight;
 pub mod gadget;
 pub mod monster;
 pub mod mp_level;
+pub mod one_off;
 pub mod play_team;
 pub mod team;
 pub mod transform;
@@ -34,14 +37,15 @@ pub mod util;
 pub mod weapon;
 
 use crate::avatar::CurrentPlayerAvatarMarker;
-use crate::common::{ChestDropId, ConfigId, DropTag, GroupId, Level, Visible};
+use crate::common::{ChestDropId, ConfigId, DropTag, Dropped, GroupId, Level, OneOff, Visible};
 use crate::gadget::GadgetID;
-use crate::monster::MonsterID;
+use crate::monster::{get_monster_kill_drop_id, record_monster_kill, MonsterID};
+use crate::one_off::{gather_refresh_time, record_gadget_one_off, record_monster_one_off};
 use crate::transform::Transform;
 use nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache;
 use nod_krai_gi_proto::normal::{
     GadgetInteractReq, GadgetInteractRsp, LifeStateChangeNotify, ProtEntityType,
-    SceneEntityDisappearNotify, SceneEntityDrownReq, SelectWorktopOptionReq,
+    SceneEntityDisappearNotify, SelectWorktopOptionReq,
     SelectWorktopOptionRsp, VisionType,
 };
 
@@ -58,6 +62,7 @@ impl Plugin for EntityPlugin {
         app.insert_resource(EntityCounter::default())
             .insert_resource(EntityById::default())
             .add_message::<SetWorktopOptionsEvent>()
+            .add_systems(Startup, one_off::restore_one_off_state)
             .add_systems(
                 PreUpdate,
                 update_entity_index.in_set(EntitySystemSet::HandleEntityIndexUpdate),
@@ -73,6 +78,16 @@ impl Plugin for EntityPlugin {
             .add_systems(Update, gadget::handle_set_worktop_options)
             .add_systems(Update, gadget::handle_gadget_state_change)
             .add_systems(Update, avatar::update_avatar_appearance)
+            .add_systems(
+                Update,
+                (
+                    ai::sync_spawned_monster_ai,
+                    ai::handle_monster_ai_on_hit,
+                    ai::handle_monster_ai_on_player_move,
+                    ai::tick_monster_ai,
+                )
+                    .chain(),
+            )
             .add_systems(
                 PostUpdate,
                 (
@@ -134,6 +149,15 @@ fn update_separate_property_entity(
     }
 }
 
+// drop tag tables are keyed by level, the world level lifts it to its monster level
+fn drop_level(level: u32, world_level: u32) -> u32 {
+    nod_krai_gi_data::excel::world_level_excel_config_collection::get()
+        .get(&world_level)
+        .map_or(level, |world_level_config| {
+            level.max(world_level_config.monster_level)
+        })
+}
+
 fn update_entity_life_state(
     mut commands: Commands,
     mut entities: Query<
@@ -150,6 +174,8 @@ fn update_entity_life_state(
             Option<&ChestDropId>,
             Option<&GroupId>,
             Option<&ConfigId>,
+            Option<&OneOff>,
+            Has<Dropped>,
         ),
         Changed<FightProperties>,
     >,
@@ -159,7 +185,9 @@ fn update_entity_life_state(
     mut monster_kill_events: MessageWriter<MonsterKillEvent>,
     mut quest_content_events: MessageWriter<QuestContentProgressEvent>,
     message_output: Res<MessageOutput>,
+    mut players: ResMut<Players>,
     world_owner_uid: Res<WorldOwnerUID>,
+    world_level: Res<WorldLevel>,
     world_version_config: Res<WorldVersionConfig>,
 ) {
     let gather_excel_config_collection_clone =
@@ -180,6 +208,8 @@ fn update_entity_life_state(
         chest_drop_id,
         group_id,
         config_id,
+        one_off,
+        dropped,
     ) in entities.iter_mut()
     {
         let cur_hp = fight_props.get_property(FightPropType::FIGHT_PROP_CUR_HP);
@@ -193,7 +223,7 @@ fn update_entity_life_state(
                     .entity(entity)
                     .remove::<CurrentPlayerAvatarMarker>()
                     .remove::<Visible>();
-            } else {
+            } else if !dropped {
                 match gadget_id {
                     None => {}
                     Some(gadget_id) => {
@@ -203,6 +233,15 @@ fn update_entity_life_state(
                         {
                             None => {}
                             Some((_, gather_config)) => {
+                                if one_off.is_none() && *life_state != LifeState::Dead {
+                                    record_gadget_one_off(
+                                        &mut players,
+                                        world_owner_uid.0,
+                                        group_id,
+                                        config_id,
+                                        gather_refresh_time(gather_config.gadget_id),
+                                    );
+                                }
                                 item_drop_events.write(ItemDropEvent(
                                     0,
                                     Some((
@@ -218,16 +257,21 @@ fn update_entity_life_state(
                 }
 
                 let mut drop_id = 0;
+                let mut drop_count = 1;
 
                 match drop_tag {
                     None => {}
                     Some(drop_tag) => match &drop_tag.0 {
                         None => {}
                         Some(drop_tag) => {
-                            match CombinedDrop::get_drop_config(drop_tag.clone(), level.0) {
+                            match CombinedDrop::get_drop_config(
+                                drop_tag.clone(),
+                                drop_level(level.0, world_level.0),
+                            ) {
                                 None => {}
                                 Some(drop_config) => {
                                     drop_id = drop_config.drop_id;
+                                    drop_count = drop_config.drop_count.max(1);
                                 }
                             }
                         }
@@ -243,24 +287,58 @@ fn update_entity_life_state(
                     }
                 }
 
+                let mut drop_vec = vec![];
+
                 if drop_id != 0 {
                     tracing::debug!("drop_id is {}", drop_id);
-                    let drop_vec = resolve_drop(drop_id, 1);
+                    drop_vec.extend(resolve_drop(drop_id, drop_count));
+                }
+
+                if let Some(monster_id) = monster_id {
+                    if *life_state != LifeState::Dead {
+                        let kill_drop_id = get_monster_kill_drop_id(monster_id.0);
+                        if kill_drop_id != 0 {
+                            tracing::debug!("kill_drop_id is {}", kill_drop_id);
+                            drop_vec.extend(resolve_drop(kill_drop_id, 1));
+                        }
+
+                        record_monster_kill(&mut players, world_owner_uid.0, monster_id.0);
+                    }
+                }
+
+                if !drop_vec.is_empty() {
                     tracing::debug!("drop_vec is {:#?}", drop_vec);
-                    if !drop_vec.is_empty() {
-                        item_drop_events.write(ItemDropEvent(
+                    item_drop_events.write(ItemDropEvent(
+                        0,
+                        Some((
+                            transform.position.x,
+                            transform.position.y + 0.5,
+                            transform.position.z,
+                        )),
+                        drop_vec,
+                    ));
+                }
+
+                if one_off.is_some() && *life_state != LifeState::Dead {
+                    if monster_id.is_some() {
+                        record_monster_one_off(
+                            &mut players,
+                            world_owner_uid.0,
+                            group_id,
+                            config_id,
+                        );
+                    } else {
+                        record_gadget_one_off(
+                            &mut players,
+                            world_owner_uid.0,
+                            group_id,
+                            config_id,
                             0,
-                            Some((
-                                transform.position.x,
-                                transform.position.y + 0.5,
-                                transform.position.z,
-                            )),
-                            drop_vec,
-                        ));
+                        );
                     }
                 }
 
-                commands.entity(entity).insert(ToBeRemovedMarker);
+                commands.entity(entity).insert((Dropped, ToBeRemovedMarker));
             }
             disappear_events.write(EntityDisappearEvent(id.0, VisionType::VisionDie));
             if *life_state != LifeState::Dead {
@@ -422,7 +500,6 @@ pub fn handle_entity(
     message_output: Res<MessageOutput>,
     entities: Query<(&ProtocolEntityID, Option<&GroupId>, Option<&ConfigId>)>,
     mut gadget_interact_events: MessageWriter<GadgetInteractEvent>,
-    mut update_separate_property_entity_events: MessageWriter<EntityPropertySeparateUpdateEvent>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
 ) {
     for message in events.read() {
@@ -448,22 +525,6 @@ pub fn handle_entity(
                     ));
                 }
             }
-            "SceneEntityDrownReq" => {
-                if let Some(req) = message.decode::<SceneEntityDrownReq>() {
-                    match index.0.get(&req.entity_id) {
-                        None => {}
-                        Some(entity) => {
-                            update_separate_property_entity_events.write(
-                                EntityPropertySeparateUpdateEvent(
-                                    *entity,
-                                    FightPropType::FIGHT_PROP_CUR_HP,
-                                    -100000000.0,
-                                ),
-                            );
-                        }
-                    }
-                }
-            }
             "SelectWorktopOptionReq" => {
                 if let Some(req) = message.decode::<SelectWorktopOptionReq>() {
                     match index.0.get(&req.gadget_entity_id) {
@@ -1,3 +1,4 @@
+use crate::ai::MonsterAi;
 use crate::gadget::GadgetID;
 use crate::util::{create_fight_properties_by_monster_config, to_protocol_entity_id};
 use crate::weapon::{AffixMap, WeaponBundle, WeaponID, WeaponPromoteLevel, WeaponQueryReadOnly};
@@ -6,8 +7,9 @@ use bevy_ecs::{prelude::*, query::QueryData};
 use nod_krai_gi_data::excel::{gadget_excel_config_collection, monster_excel_config_collection};
 use nod_krai_gi_data::prop_type::FightPropType;
 use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_persistence::Players;
 use nod_krai_gi_proto::normal::ProtEntityType;
-use nod_krai_gi_proto::server_only::VectorBin;
+use nod_krai_gi_proto::server_only::{MonsterBeKilledNumBin, VectorBin};
 use std::collections::HashMap;
 use tracing::debug;
 
@@ -20,6 +22,12 @@ pub struct MonsterID(pub u32);
 #[derive(Component)]
 pub struct AffixList(pub Vec<u32>);
 
+// set by LockMonsterHp, damage never takes the monster below min_hp
+#[derive(Component)]
+pub struct MonsterHpLock {
+    pub min_hp: f32,
+}
+
 #[derive(Component)]
 pub struct PoseId(pub u32);
 
@@ -45,8 +53,10 @@ pub struct MonsterBundle {
     pub fight_properties: FightProperties,
     pub instanced_abilities: InstancedAbilities,
     pub instanced_modifiers: InstancedModifiers,
+    pub damage_bonuses: DamageBonuses,
     pub global_ability_values: GlobalAbilityValues,
     pub life_state: LifeState,
+    pub ai: MonsterAi,
 }
 
 #[derive(QueryData)]
@@ -67,6 +77,7 @@ pub struct MonsterQueryReadOnly {
     pub instanced_modifiers: &'static InstancedModifiers,
     pub global_ability_values: &'static GlobalAbilityValues,
     pub life_state: &'static LifeState,
+    pub ai: &'static MonsterAi,
 }
 
 pub fn notify_appear_monster_entities(
@@ -122,7 +133,9 @@ pub fn notify_appear_monster_entities(
                         entity_client_data: Some(EntityClientData::default()),
                         entity_environment_info_list: Vec::with_capacity(0),
                         entity_authority_info: Some(EntityAuthorityInfo {
-                            ability_info: Some(AbilitySyncStateInfo::default()),
+                            ability_info: Some(
+                                monster_data.global_ability_values.build_sgv_sync_info(),
+                            ),
                             born_pos: Some(monster_data.transform.position.into()),
                             client_extra_info: Some(EntityClientExtraInfo {
                                 skill_anchor_position: Some(Vector::default()),
@@ -141,7 +154,7 @@ pub fn notify_appear_monster_entities(
                             config_id: config_id.and_then(|t| Some(t.0)).unwrap_or_default(),
                             affix_list: monster_data.affix_list.0.clone(),
                             init_pose_id: monster_data.pose_id.0,
-                            pose_id: monster_data.pose_id.0,
+                            pose_id: monster_data.ai.pose_id,
                             title_id: monster_data.title_id.0,
                             special_name_id: monster_data.special_name_id.0,
                             weapon_list: monster_data
@@ -189,6 +202,7 @@ pub fn spawn_monster_entity(
     special_name_id: u32,
     drop_tag: Option<String>,
     chest_drop_id: u32,
+    global_ability_values: GlobalAbilityValues,
 ) -> Option<(u32, Entity, f32, f32)> {
     let monster_excel_config_collection_clone =
         std::sync::Arc::clone(monster_excel_config_collection::get());
@@ -278,9 +292,70 @@ pub fn spawn_monster_entity(
         fight_properties,
         instanced_abilities: InstancedAbilities::default(),
         instanced_modifiers: InstancedModifiers::default(),
-        global_ability_values: GlobalAbilityValues::default(),
+        damage_bonuses: DamageBonuses::default(),
+        global_ability_values,
         life_state: LifeState::Alive,
+        ai: MonsterAi::new(position, pose_id),
     });
 
     Some((entity_id, monster_entity.id(), cur_hp, max_hp))
 }
+
+pub fn get_monster_kill_drop_id(monster_id: u32) -> u32 {
+    let monster_excel_config_collection_clone =
+        std::sync::Arc::clone(monster_excel_config_collection::get());
+
+    monster_excel_config_collection_clone
+        .get(&monster_id)
+        .map(|monster_config| monster_config.kill_drop_id)
+        .unwrap_or_default()
+}
+
+// drops for every hp threshold passed when hp falls from from_percent to to_percent
+pub fn get_monster_hp_drop_ids(monster_id: u32, from_percent: f32, to_percent: f32) -> Vec<u32> {
+    let monster_excel_config_collection_clone =
+        std::sync::Arc::clone(monster_excel_config_collection::get());
+
+    monster_excel_config_collection_clone
+        .get(&monster_id)
+        .map(|monster_config| {
+            monster_config
+                .hp_drops
+                .iter()
+                .filter(|hp_drop| {
+                    hp_drop.drop_id != 0
+                        && hp_drop.hp_percent < from_percent
+                        && hp_drop.hp_percent >= to_percent
+                })
+                .map(|hp_drop| hp_drop.drop_id)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn record_monster_kill(players: &mut Players, uid: u32, monster_id: u32) {
+    let Some(player_info) = players.get_mut(uid) else {
+        return;
+    };
+    let Some(ref mut player_scene_bin) = player_info.scene_bin else {
+        return;
+    };
+
+    match player_scene_bin
+        .monster_killed_num_list
+        .iter_mut()
+        .find(|killed_num| killed_num.monster_id == monster_id)
+    {
+        None => {
+            player_scene_bin
+                .monster_killed_num_list
+                .push(MonsterBeKilledNumBin {
+                    monster_id,
+                    be_killed_num: 1,
+                });
+        }
+        Some(killed_num) => {
+            killed_num.be_killed_num += 1;
+        }
+    }
+}
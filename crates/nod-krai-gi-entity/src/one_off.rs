@@ -0,0 +1,123 @@
+use crate::common::{ConfigId, GroupId};
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache;
+use nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION;
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::server_only::GroupProductBin;
+
+const DEFAULT_GATHER_REFRESH_SECS: u32 = 24 * 60 * 60;
+
+pub fn restore_one_off_state(mut players: ResMut<Players>) {
+    let cache = get_group_entity_state_cache();
+    let now = ::common::time_util::unix_timestamp() as u32;
+
+    let uid_list: Vec<u32> = players.keys().copied().collect();
+    for uid in uid_list {
+        let Some(player_info) = players.get_mut(uid) else {
+            continue;
+        };
+        let Some(ref mut player_scene_bin) = player_info.scene_bin else {
+            continue;
+        };
+
+        for scene_product_bin in player_scene_bin.world_product_map.values_mut() {
+            for (group_id, group_product_bin) in scene_product_bin.group_product_map.iter_mut() {
+                group_product_bin
+                    .gadget_one_off_map
+                    .retain(|_, refresh_time| *refresh_time == 0 || *refresh_time > now);
+                group_product_bin
+                    .monster_one_off_map
+                    .retain(|_, refresh_time| *refresh_time == 0 || *refresh_time > now);
+
+                for (config_id, refresh_time) in group_product_bin.gadget_one_off_map.iter() {
+                    cache.on_gadget_one_off(uid, *group_id, *config_id, *refresh_time);
+                }
+                for (config_id, refresh_time) in group_product_bin.monster_one_off_map.iter() {
+                    cache.on_monster_one_off(uid, *group_id, *config_id, *refresh_time);
+                }
+            }
+            scene_product_bin.group_product_map.retain(|_, group_product_bin| {
+                !group_product_bin.gadget_one_off_map.is_empty()
+                    || !group_product_bin.monster_one_off_map.is_empty()
+            });
+        }
+    }
+}
+
+pub fn gather_refresh_time(gadget_id: u32) -> u32 {
+    let gather_excel_config_collection_clone =
+        std::sync::Arc::clone(nod_krai_gi_data::excel::gather_excel_config_collection::get());
+
+    let cd = gather_excel_config_collection_clone
+        .values()
+        .find(|gather_config| gather_config.gadget_id == gadget_id)
+        .map(|gather_config| gather_config.cd)
+        .filter(|cd| *cd != 0)
+        .unwrap_or(DEFAULT_GATHER_REFRESH_SECS);
+
+    ::common::time_util::unix_timestamp() as u32 + cd
+}
+
+pub fn record_gadget_one_off(
+    players: &mut Players,
+    uid: u32,
+    group_id: Option<&GroupId>,
+    config_id: Option<&ConfigId>,
+    refresh_time: u32,
+) {
+    let (Some(group_id), Some(config_id)) = (group_id, config_id) else {
+        return;
+    };
+
+    get_group_entity_state_cache().on_gadget_one_off(uid, group_id.0, config_id.0, refresh_time);
+
+    if let Some(group_product_bin) = get_group_product_bin_mut(players, uid, group_id.0) {
+        group_product_bin
+            .gadget_one_off_map
+            .insert(config_id.0, refresh_time);
+    }
+}
+
+pub fn record_monster_one_off(
+    players: &mut Players,
+    uid: u32,
+    group_id: Option<&GroupId>,
+    config_id: Option<&ConfigId>,
+) {
+    let (Some(group_id), Some(config_id)) = (group_id, config_id) else {
+        return;
+    };
+
+    get_group_entity_state_cache().on_monster_one_off(uid, group_id.0, config_id.0, 0);
+
+    if let Some(group_product_bin) = get_group_product_bin_mut(players, uid, group_id.0) {
+        group_product_bin.monster_one_off_map.insert(config_id.0, 0);
+    }
+}
+
+fn get_group_product_bin_mut(
+    players: &mut Players,
+    uid: u32,
+    group_id: u32,
+) -> Option<&mut GroupProductBin> {
+    let player_scene_bin = players.get_mut(uid)?.scene_bin.as_mut()?;
+
+    let scene_id = SCENE_GROUP_COLLECTION
+        .get()
+        .and_then(|collection| {
+            collection
+                .get(&group_id)
+                .and_then(|template| template.value().as_ref().map(|t| t.base_info.scene_id))
+        })
+        .unwrap_or(player_scene_bin.my_cur_scene_id);
+
+    Some(
+        player_scene_bin
+            .world_product_map
+            .entry(scene_id)
+            .or_default()
+            .group_product_map
+            .entry(group_id)
+            .or_default(),
+    )
+}
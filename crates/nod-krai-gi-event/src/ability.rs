@@ -224,4 +224,7 @@ pub struct AbilityActionAvatarSkillStartEvent(
 );
 
 #[derive(Message)]
-pub struct AttackLandedEvent(pub Entity, pub Entity); // attacker, target
\ No newline at end of file
+pub struct AttackLandedEvent(pub Entity, pub Entity, pub String); // attacker, target, anim event id
+
+#[derive(Message)]
+pub struct AbilityCostStaminaEvent(pub Entity, pub f32); // entity, stamina delta
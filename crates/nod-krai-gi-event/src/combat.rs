@@ -1,5 +1,7 @@
+use bevy_ecs::entity::Entity;
 use bevy_ecs::message::Message;
-use nod_krai_gi_proto::normal::{AttackResult, EntityMoveInfo};
+use nod_krai_gi_data::excel::common::ElementReactionType;
+use nod_krai_gi_proto::normal::{AttackResult, EntityMoveInfo, PlayerDieType};
 
 #[derive(Message)]
 pub struct EntityMoveEvent(pub u32, pub EntityMoveInfo);
@@ -7,3 +9,17 @@ pub struct EntityMoveEvent(pub u32, pub EntityMoveInfo);
 pub struct EntityBeingHitEvent(pub u32, pub AttackResult);
 #[derive(Message)]
 pub struct PlayerMoveEvent(pub u32, pub u32, pub (f32, f32, f32),pub bool);
+#[derive(Message)]
+pub struct ElementReactionEvent(pub Option<Entity>, pub Entity, pub ElementReactionType); // attacker, defender, reaction
+#[derive(Message)]
+pub struct EntityKilledEvent(pub Option<Entity>, pub Entity); // killer, victim
+#[derive(Message)]
+pub struct StaminaCostModifierEvent(pub u32, pub String, pub f32, pub Option<f32>); // uid, source, cost ratio, duration in seconds
+#[derive(Message)]
+pub struct PlayerDieCauseEvent(pub u32, pub PlayerDieType, pub u32); // uid, die type, murderer entity id
+#[derive(Message)]
+pub struct ElemBallGenerateEvent(pub Entity, pub u32, pub u32, pub (f32, f32, f32)); // avatar, config id, count, position
+#[derive(Message)]
+pub struct ElemBallPickupEvent(pub u32, pub u32, pub u32); // uid, item id, count
+#[derive(Message)]
+pub struct AvatarEnergyAddEvent(pub u32, pub Option<f32>); // uid, energy for the current avatar, none fills it up
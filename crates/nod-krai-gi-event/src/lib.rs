@@ -49,6 +49,7 @@ impl Plugin for EventRegistryPlugin {
             .add_message::<GadgetStateChangeEvent>()
             //lua
             .add_message::<LuaTriggerEvent>()
+            .add_message::<LuaSourceTriggerEvent>()
             .add_message::<ScriptCommandEvent>()
             .add_message::<SpawnGroupEntityEvent>()
             .add_message::<DespawnGroupEntityEvent>()
@@ -70,6 +71,7 @@ impl Plugin for EventRegistryPlugin {
             .add_message::<QuestExecEvent>()
             //scene
             .insert_resource(WorldOwnerUID(0))
+            .insert_resource(WorldLevel(0))
             .insert_resource(WorldVersionConfig {
                 protocol_version: "unknown version".to_string(),
                 ty_value: 24,
@@ -85,12 +87,22 @@ impl Plugin for EventRegistryPlugin {
             .add_message::<ScenePlayerJumpEvent>()
             .add_message::<ScenePlayerJumpByPointEvent>()
             .add_message::<ScenePlayerEnterDungeonEvent>()
+            .add_message::<ScenePointOperateEvent>()
+            .add_message::<SceneTagOperateEvent>()
+            .add_message::<LevelTagChangeEvent>()
             //luashell
             .add_message::<LuaShellEvent>()
             //combat
             .add_message::<EntityMoveEvent>()
             .add_message::<EntityBeingHitEvent>()
+            .add_message::<ElementReactionEvent>()
+            .add_message::<EntityKilledEvent>()
             .add_message::<PlayerMoveEvent>()
+            .add_message::<StaminaCostModifierEvent>()
+            .add_message::<PlayerDieCauseEvent>()
+            .add_message::<ElemBallGenerateEvent>()
+            .add_message::<ElemBallPickupEvent>()
+            .add_message::<AvatarEnergyAddEvent>()
             //ability
             .add_message::<AddNewAbilityEvent>()
             .add_message::<ModifierChangeEvent>()
@@ -120,6 +132,8 @@ impl Plugin for EventRegistryPlugin {
             .add_message::<AbilityActionAttachModifierEvent>()
             .add_message::<AbilityActionRemoveUniqueModifierEvent>()
             .add_message::<AbilityActionTriggerAbilityEvent>()
-            .add_message::<AbilityActionKillSelfEvent>();
+            .add_message::<AbilityActionKillSelfEvent>()
+            .add_message::<AttackLandedEvent>()
+            .add_message::<AbilityCostStaminaEvent>();
     }
 }
@@ -8,6 +8,15 @@ pub struct LuaTriggerEvent {
     pub evt: LuaEvt,
 }
 
+// only fires triggers whose source matches, source_name is exposed to lua as evt.source_name
+#[derive(Message)]
+pub struct LuaSourceTriggerEvent {
+    pub group_id: u32,
+    pub event_type: EventType,
+    pub source_name: String,
+    pub evt: LuaEvt,
+}
+
 #[derive(Message)]
 pub struct ScriptCommandEvent {
     pub command: ScriptCommand,
@@ -37,6 +46,8 @@ pub struct SpawnSuiteEntitiesEvent {
 pub struct DespawnSuiteEntitiesEvent {
     pub group_id: u32,
     pub suite_id: u32,
+    // killed entities die (and fire die triggers) instead of just leaving the scene
+    pub kill: bool,
 }
 
 #[derive(Message)]
@@ -59,6 +70,8 @@ pub struct OnClientExecuteReqEvent {
 pub struct OnBeHurtEvent {
     pub lua_name: String,
     pub element_type: u32,
+    // ElementReactionType the hit triggered, 0 for none
+    pub reaction_type: u32,
     pub strike_type: u32,
     pub is_host: bool,
     pub lua_context: LuaContext,
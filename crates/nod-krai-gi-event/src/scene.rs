@@ -81,6 +81,10 @@ impl From<u32> for EnterReason {
 #[derive(bevy_ecs::resource::Resource)]
 pub struct WorldOwnerUID(pub u32);
 
+// the world owner's PROP_PLAYER_WORLD_LEVEL
+#[derive(bevy_ecs::resource::Resource)]
+pub struct WorldLevel(pub u32);
+
 #[derive(bevy_ecs::resource::Resource)]
 pub struct WorldVersionConfig {
     pub protocol_version: String,
@@ -134,3 +138,33 @@ pub struct ScenePlayerJumpByPointEvent(pub u32, pub u32, pub u32);
 
 #[derive(Message)]
 pub struct ScenePlayerEnterDungeonEvent(pub u32, pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenePointOperation {
+    Unlock,
+    Lock,
+    Hide,
+    Unhide,
+}
+
+#[derive(Message)]
+pub struct ScenePointOperateEvent {
+    pub uid: u32,
+    pub scene_id: u32,
+    pub point_id: u32,
+    pub operation: ScenePointOperation,
+}
+
+#[derive(Message)]
+pub struct SceneTagOperateEvent {
+    pub uid: u32,
+    pub scene_id: u32,
+    pub scene_tag_id: u32,
+    pub is_add: bool,
+}
+
+#[derive(Message)]
+pub struct LevelTagChangeEvent {
+    pub uid: u32,
+    pub level_tag_id: u32,
+}
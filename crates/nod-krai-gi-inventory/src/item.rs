@@ -7,12 +7,14 @@ use nod_krai_gi_data::excel::{
     material_excel_config_collection, reliquary_affix_excel_config_collection,
     reliquary_excel_config_collection, reliquary_level_excel_config_collection,
     reliquary_main_prop_excel_config_collection, weapon_excel_config_collection,
-    weapon_level_excel_config_collection, ReliquaryAffixExcelConfig,
+    weapon_level_excel_config_collection, ItemUseOp, MaterialExcelConfig,
+    ReliquaryAffixExcelConfig,
 };
 use nod_krai_gi_data::prop_type::FightPropType;
 use nod_krai_gi_data::quest::quest_config::{QuestCond, QuestContent};
-use nod_krai_gi_entity::common::{EntityCounter, Visible};
+use nod_krai_gi_entity::common::{EntityCounter, GlobalAbilityValues, Visible};
 use nod_krai_gi_entity::gadget::spawn_gadget_entity;
+use nod_krai_gi_event::combat::ElemBallPickupEvent;
 use nod_krai_gi_event::command::{ConsoleChatNotifyEvent, GmCommandEvent};
 use nod_krai_gi_event::inventory::{ItemAddEvent, ItemDropEvent, StoreItemChangeEvent};
 use nod_krai_gi_event::quest::{QuestAcceptCondEvent, QuestContentProgressEvent};
@@ -32,6 +34,13 @@ use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
 use std::collections::HashMap;
 
+fn is_elem_ball(material_config: &MaterialExcelConfig) -> bool {
+    material_config
+        .item_use
+        .iter()
+        .any(|item_use| item_use.use_op == ItemUseOp::AddElemEnergy)
+}
+
 pub fn item_command_handler(
     mut events: MessageReader<GmCommandEvent>,
     mut item_add_events: MessageWriter<ItemAddEvent>,
@@ -76,6 +85,7 @@ pub fn item_add_handler(
     mut store_item_change_events: MessageWriter<StoreItemChangeEvent>,
     mut quest_content_events: MessageWriter<QuestContentProgressEvent>,
     mut quest_accept_events: MessageWriter<QuestAcceptCondEvent>,
+    mut elem_ball_pickup_events: MessageWriter<ElemBallPickupEvent>,
     mut players: ResMut<Players>,
 ) {
     let mut rng = SmallRng::from_entropy();
@@ -117,6 +127,15 @@ pub fn item_add_handler(
                 match material_excel_config_collection_clone.get(&item_id) {
                     None => {}
                     Some(material_config) => {
+                        // elemental particles and orbs turn into energy instead of being stored
+                        if material_config.use_on_gain && is_elem_ball(material_config) {
+                            elem_ball_pickup_events.write(ElemBallPickupEvent(
+                                *player_uid,
+                                *item_id,
+                                num.unwrap_or(1),
+                            ));
+                            continue;
+                        }
                         item_type = material_config.item_type;
                     }
                 }
@@ -374,7 +393,7 @@ pub fn item_drop_handler(
                     else {
                         continue;
                     };
-                    if material_config.use_on_gain {
+                    if material_config.use_on_gain && !is_elem_ball(material_config) {
                         continue;
                     }
                     gadget_id = material_config.gadget_id;
@@ -452,6 +471,7 @@ pub fn item_drop_handler(
                         None,
                         0,
                         0,
+                        GlobalAbilityValues::default(),
                     ) else {
                         continue;
                     };
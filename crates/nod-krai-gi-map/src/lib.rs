@@ -7,6 +7,8 @@ use nod_krai_gi_persistence::Players;
 use std::sync::Arc;
 
 mod on_map;
+mod scene_point;
+mod scene_tag;
 
 pub struct MapPlugin;
 
@@ -15,13 +17,17 @@ impl Plugin for MapPlugin {
         app.add_systems(PreUpdate, data_request_processor)
             .add_systems(PreUpdate, on_map::message_on_map)
             .add_systems(PreUpdate, sync_scene_info_list_on_scene_init)
-            .add_systems(PreUpdate, sync_group_unlimit_point_list_on_post_enter_scene);
+            .add_systems(PreUpdate, sync_group_unlimit_point_list_on_post_enter_scene)
+            .add_systems(Update, scene_point::handle_scene_point_operate)
+            .add_systems(Update, scene_tag::handle_scene_tag_operate)
+            .add_systems(Update, scene_tag::handle_level_tag_change);
     }
 }
 
 fn data_request_processor(
     mut events: MessageReader<ClientMessageEvent>,
     message_output: Res<MessageOutput>,
+    players: Res<Players>,
 ) {
     use nod_krai_gi_proto::normal::*;
 
@@ -83,6 +89,14 @@ fn data_request_processor(
                                 .values()
                                 .map(|point_data| point_data.area_id)
                                 .collect();
+                            let config_point_list: Vec<u32> =
+                                scene_config.points.keys().cloned().collect();
+                            let point_state = scene_point::get_scene_point_state(
+                                &players,
+                                message.sender_uid(),
+                                request.scene_id,
+                                &config_point_list,
+                            );
                             message_output.send(
                                 message.sender_uid(),
                                 "GetScenePointRsp",
@@ -91,16 +105,10 @@ fn data_request_processor(
                                     belong_uid: request.belong_uid,
                                     scene_id: request.scene_id,
                                     unlock_area_list,
-                                    unlocked_point_list: scene_config
-                                        .points
-                                        .keys()
-                                        .cloned()
-                                        .collect(),
-                                    unhide_point_list: scene_config
-                                        .points
-                                        .keys()
-                                        .cloned()
-                                        .collect(),
+                                    unlocked_point_list: point_state.unlocked_point_list,
+                                    locked_point_list: point_state.locked_point_list,
+                                    hide_point_list: point_state.hide_point_list,
+                                    unhide_point_list: point_state.unhide_point_list,
                                     // group_unlimit_point_list: scene_config
                                     //     .points
                                     //     .iter()
@@ -171,6 +179,7 @@ fn data_request_processor(
 pub fn sync_scene_info_list_on_scene_init(
     mut events: MessageReader<SceneInitFinishEvent>,
     message_output: Res<MessageOutput>,
+    players: Res<Players>,
 ) {
     use nod_krai_gi_proto::normal::*;
 
@@ -252,12 +261,21 @@ pub fn sync_scene_info_list_on_scene_init(
     });
 
     for SceneInitFinishEvent(uid) in events.read() {
+        let info_list = info_list
+            .iter()
+            .cloned()
+            .map(|mut info| {
+                info.scene_tag_id_list =
+                    scene_tag::get_scene_tag_id_list(&players, *uid, info.scene_id);
+                info
+            })
+            .collect();
         message_output.send(
             *uid,
             "PlayerWorldSceneInfoListNotify",
             PlayerWorldSceneInfoListNotify {
                 unlocked_area_id_list: vec![4, 5, 6, 11, 14, 19, 22],
-                info_list: info_list.clone(),
+                info_list,
             },
         );
     }
@@ -0,0 +1,139 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_event::scene::{ScenePointOperateEvent, ScenePointOperation};
+use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::ScenePointUnlockNotify;
+use nod_krai_gi_proto::server_only::SceneBin;
+
+pub struct ScenePointState {
+    pub unlocked_point_list: Vec<u32>,
+    pub locked_point_list: Vec<u32>,
+    pub hide_point_list: Vec<u32>,
+    pub unhide_point_list: Vec<u32>,
+}
+
+// every configured point starts unlocked and visible, persisted locks and hides override that
+pub fn get_scene_point_state(
+    players: &Players,
+    uid: u32,
+    scene_id: u32,
+    config_point_list: &[u32],
+) -> ScenePointState {
+    let scene_bin = players
+        .get(uid)
+        .and_then(|player_info| player_info.scene_bin.as_ref())
+        .and_then(|player_scene_bin| player_scene_bin.world.as_ref())
+        .and_then(|world_bin| world_bin.scene_map.get(&scene_id));
+
+    let Some(scene_bin) = scene_bin else {
+        return ScenePointState {
+            unlocked_point_list: config_point_list.to_vec(),
+            locked_point_list: vec![],
+            hide_point_list: vec![],
+            unhide_point_list: config_point_list.to_vec(),
+        };
+    };
+
+    let mut unlocked_point_list: Vec<u32> = config_point_list
+        .iter()
+        .filter(|point_id| !scene_bin.locked_point_list.contains(point_id))
+        .copied()
+        .collect();
+    for point_id in scene_bin.unlocked_point_list.iter() {
+        if !unlocked_point_list.contains(point_id) {
+            unlocked_point_list.push(*point_id);
+        }
+    }
+
+    let mut unhide_point_list: Vec<u32> = config_point_list
+        .iter()
+        .filter(|point_id| !scene_bin.hide_point_list.contains(point_id))
+        .copied()
+        .collect();
+    for point_id in scene_bin.unhide_point_list.iter() {
+        if !unhide_point_list.contains(point_id) {
+            unhide_point_list.push(*point_id);
+        }
+    }
+
+    ScenePointState {
+        unlocked_point_list,
+        locked_point_list: scene_bin.locked_point_list.clone(),
+        hide_point_list: scene_bin.hide_point_list.clone(),
+        unhide_point_list,
+    }
+}
+
+pub fn handle_scene_point_operate(
+    mut events: MessageReader<ScenePointOperateEvent>,
+    mut players: ResMut<Players>,
+    message_output: Res<MessageOutput>,
+) {
+    for event in events.read() {
+        tracing::debug!(
+            "scene point {:?} uid={} scene={} point={}",
+            event.operation,
+            event.uid,
+            event.scene_id,
+            event.point_id
+        );
+
+        let Some(player_scene_bin) = players
+            .get_mut(event.uid)
+            .and_then(|player_info| player_info.scene_bin.as_mut())
+        else {
+            continue;
+        };
+        let scene_bin = player_scene_bin
+            .world
+            .get_or_insert_default()
+            .scene_map
+            .entry(event.scene_id)
+            .or_default();
+
+        apply_scene_point_operation(scene_bin, event.point_id, event.operation);
+
+        let point_list = vec![event.point_id];
+        let mut notify = ScenePointUnlockNotify {
+            scene_id: event.scene_id,
+            ..Default::default()
+        };
+        match event.operation {
+            ScenePointOperation::Unlock => notify.point_list = point_list,
+            ScenePointOperation::Lock => notify.locked_point_list = point_list,
+            ScenePointOperation::Hide => notify.hide_point_list = point_list,
+            ScenePointOperation::Unhide => notify.unhide_point_list = point_list,
+        }
+        message_output.send(event.uid, "ScenePointUnlockNotify", notify);
+    }
+}
+
+// keeps the unlocked/locked and hide/unhide pairs mutually exclusive
+fn apply_scene_point_operation(
+    scene_bin: &mut SceneBin,
+    point_id: u32,
+    operation: ScenePointOperation,
+) {
+    let (add_list, remove_list) = match operation {
+        ScenePointOperation::Unlock => (
+            &mut scene_bin.unlocked_point_list,
+            &mut scene_bin.locked_point_list,
+        ),
+        ScenePointOperation::Lock => (
+            &mut scene_bin.locked_point_list,
+            &mut scene_bin.unlocked_point_list,
+        ),
+        ScenePointOperation::Hide => (
+            &mut scene_bin.hide_point_list,
+            &mut scene_bin.unhide_point_list,
+        ),
+        ScenePointOperation::Unhide => (
+            &mut scene_bin.unhide_point_list,
+            &mut scene_bin.hide_point_list,
+        ),
+    };
+    remove_list.retain(|id| *id != point_id);
+    if !add_list.contains(&point_id) {
+        add_list.push(point_id);
+    }
+}
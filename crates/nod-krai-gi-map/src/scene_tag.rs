@@ -0,0 +1,167 @@
+use bevy_ecs::prelude::*;
+use common::time_util;
+use nod_krai_gi_data::excel::{
+    level_tag_excel_config_collection, SceneTagConfig, SceneTagConfigKeyed,
+};
+use nod_krai_gi_event::scene::{LevelTagChangeEvent, SceneTagOperateEvent};
+use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::SceneDataNotify;
+use nod_krai_gi_proto::server_only::{LevelTagChangeBin, SceneTagOp, SceneTagOpType};
+
+// every configured tag starts enabled, persisted ops add or remove tags on top of that
+pub fn get_scene_tag_id_list(players: &Players, uid: u32, scene_id: u32) -> Vec<u32> {
+    let mut scene_tag_id_list: Vec<u32> = SceneTagConfig::get_scene_tag_entries()
+        .get(&scene_id)
+        .map(|scene_tag_list| scene_tag_list.iter().map(|item| item.id).collect())
+        .unwrap_or_default();
+
+    let scene_tag_op_list = players
+        .get(uid)
+        .and_then(|player_info| player_info.scene_bin.as_ref())
+        .and_then(|player_scene_bin| player_scene_bin.world.as_ref())
+        .and_then(|world_bin| world_bin.scene_map.get(&scene_id))
+        .map(|scene_bin| scene_bin.scene_tag_op_list.as_slice())
+        .unwrap_or_default();
+
+    for scene_tag_op in scene_tag_op_list {
+        if scene_tag_op.op_type == SceneTagOpType::SceneTagOpAdd as i32 {
+            if !scene_tag_id_list.contains(&scene_tag_op.id) {
+                scene_tag_id_list.push(scene_tag_op.id);
+            }
+        } else if scene_tag_op.op_type == SceneTagOpType::SceneTagOpDel as i32 {
+            scene_tag_id_list.retain(|id| *id != scene_tag_op.id);
+        }
+    }
+
+    scene_tag_id_list
+}
+
+pub fn get_level_tag_name_list(players: &Players, uid: u32, scene_id: u32) -> Vec<String> {
+    let level_tag_config_collection = level_tag_excel_config_collection::get();
+
+    players
+        .get(uid)
+        .and_then(|player_info| player_info.scene_bin.as_ref())
+        .and_then(|player_scene_bin| player_scene_bin.level_tag_bin.as_ref())
+        .map(|level_tag_bin| {
+            level_tag_bin
+                .level_tag_change_list
+                .iter()
+                .filter_map(|level_tag_change| {
+                    level_tag_config_collection.get(&level_tag_change.level_tag_id)
+                })
+                .filter(|level_tag_config| level_tag_config.scene_id == scene_id)
+                .map(|level_tag_config| level_tag_config.level_tag_name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn handle_scene_tag_operate(
+    mut events: MessageReader<SceneTagOperateEvent>,
+    mut players: ResMut<Players>,
+    message_output: Res<MessageOutput>,
+) {
+    for event in events.read() {
+        tracing::debug!(
+            "scene tag {} uid={} scene={} tag={}",
+            if event.is_add { "add" } else { "del" },
+            event.uid,
+            event.scene_id,
+            event.scene_tag_id
+        );
+
+        let Some(player_scene_bin) = players
+            .get_mut(event.uid)
+            .and_then(|player_info| player_info.scene_bin.as_mut())
+        else {
+            continue;
+        };
+        let scene_bin = player_scene_bin
+            .world
+            .get_or_insert_default()
+            .scene_map
+            .entry(event.scene_id)
+            .or_default();
+
+        // only the latest op of a tag matters
+        scene_bin
+            .scene_tag_op_list
+            .retain(|scene_tag_op| scene_tag_op.id != event.scene_tag_id);
+        scene_bin.scene_tag_op_list.push(SceneTagOp {
+            id: event.scene_tag_id,
+            op_type: if event.is_add {
+                SceneTagOpType::SceneTagOpAdd
+            } else {
+                SceneTagOpType::SceneTagOpDel
+            } as i32,
+        });
+
+        send_scene_data_notify(&players, &message_output, event.uid, event.scene_id);
+    }
+}
+
+pub fn handle_level_tag_change(
+    mut events: MessageReader<LevelTagChangeEvent>,
+    mut players: ResMut<Players>,
+    message_output: Res<MessageOutput>,
+) {
+    let level_tag_config_collection = level_tag_excel_config_collection::get();
+
+    for event in events.read() {
+        let Some(level_tag_config) = level_tag_config_collection.get(&event.level_tag_id) else {
+            tracing::debug!("level tag {} config not found", event.level_tag_id);
+            continue;
+        };
+        let scene_id = level_tag_config.scene_id;
+
+        tracing::debug!(
+            "level tag change uid={} scene={} tag={}",
+            event.uid,
+            scene_id,
+            event.level_tag_id
+        );
+
+        let Some(player_scene_bin) = players
+            .get_mut(event.uid)
+            .and_then(|player_info| player_info.scene_bin.as_mut())
+        else {
+            continue;
+        };
+
+        // a scene has one level tag at a time, the new one replaces the old
+        let level_tag_bin = player_scene_bin.level_tag_bin.get_or_insert_default();
+        level_tag_bin
+            .level_tag_change_list
+            .retain(|level_tag_change| {
+                level_tag_config_collection
+                    .get(&level_tag_change.level_tag_id)
+                    .is_none_or(|config| config.scene_id != scene_id)
+            });
+        level_tag_bin.level_tag_change_list.push(LevelTagChangeBin {
+            level_tag_id: event.level_tag_id,
+            change_time: time_util::unix_timestamp() as u32,
+        });
+
+        send_scene_data_notify(&players, &message_output, event.uid, scene_id);
+    }
+}
+
+fn send_scene_data_notify(
+    players: &Players,
+    message_output: &MessageOutput,
+    uid: u32,
+    scene_id: u32,
+) {
+    message_output.send(
+        uid,
+        "SceneDataNotify",
+        SceneDataNotify {
+            scene_id,
+            scene_tag_id_list: get_scene_tag_id_list(players, uid, scene_id),
+            level_config_name_list: get_level_tag_name_list(players, uid, scene_id),
+            ..Default::default()
+        },
+    );
+}
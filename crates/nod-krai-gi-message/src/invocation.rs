@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::output::MessageOutput;
+use crate::peer::ScenePeerManager;
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemParam;
+use nod_krai_gi_proto::normal::{
+    AbilityInvocationsNotify, AbilityInvokeEntry, ClientAbilityInitFinishNotify,
+    CombatInvocationsNotify, CombatInvokeEntry, ForwardType,
+};
+
+pub struct InvocationRouterPlugin;
+
+impl Plugin for InvocationRouterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InvocationRouter>()
+            .init_resource::<ScenePeerManager>()
+            .add_systems(Last, flush_invocations);
+    }
+}
+
+// invokes queued per recipient uid, sent once per tick by flush_invocations
+#[derive(Resource, Default)]
+pub struct InvocationRouter {
+    ability_invokes: HashMap<u32, Vec<AbilityInvokeEntry>>,
+    // keyed by (recipient uid, entity id), the notify carries a single entity
+    init_finish_invokes: HashMap<(u32, u32), Vec<AbilityInvokeEntry>>,
+    combat_invokes: HashMap<u32, Vec<CombatInvokeEntry>>,
+    combat_sequence_id: u32,
+}
+
+#[derive(SystemParam)]
+pub struct InvocationSender<'w> {
+    router: ResMut<'w, InvocationRouter>,
+    message_output: Res<'w, MessageOutput>,
+    peer_manager: Res<'w, ScenePeerManager>,
+}
+
+impl InvocationSender<'_> {
+    pub fn forward_ability_invoke(&mut self, sender_uid: u32, invoke: AbilityInvokeEntry) {
+        let recipients = self.recipients(sender_uid, invoke.forward_type(), invoke.forward_peer);
+        for uid in recipients {
+            self.router
+                .ability_invokes
+                .entry(uid)
+                .or_default()
+                .push(invoke.clone());
+        }
+    }
+
+    pub fn forward_ability_init_finish_invoke(
+        &mut self,
+        sender_uid: u32,
+        entity_id: u32,
+        invoke: AbilityInvokeEntry,
+    ) {
+        let recipients = self.recipients(sender_uid, invoke.forward_type(), invoke.forward_peer);
+        for uid in recipients {
+            self.router
+                .init_finish_invokes
+                .entry((uid, entity_id))
+                .or_default()
+                .push(invoke.clone());
+        }
+    }
+
+    // the combat entry carries no named peer field in this proto, ForwardToPeer finds no target
+    pub fn forward_combat_invoke(&mut self, sender_uid: u32, invoke: CombatInvokeEntry) {
+        let recipients = self.recipients(sender_uid, invoke.forward_type(), 0);
+        for uid in recipients {
+            self.router
+                .combat_invokes
+                .entry(uid)
+                .or_default()
+                .push(invoke.clone());
+        }
+    }
+
+    // sender_uid 0 stands for the server itself, it is never excluded as the current peer
+    fn recipients(
+        &self,
+        sender_uid: u32,
+        forward_type: ForwardType,
+        forward_peer: u32,
+    ) -> Vec<u32> {
+        let host_uid = self.peer_manager.host_uid();
+        let player_uids = self.message_output.player_uids();
+
+        match forward_type {
+            ForwardType::ForwardLocal | ForwardType::ForwardOnlyServer => Vec::new(),
+            ForwardType::ForwardToAll => player_uids.collect(),
+            ForwardType::ForwardToAllExceptCur
+            | ForwardType::ForwardToAllExistExceptCur
+            | ForwardType::ForwardToPeers => player_uids.filter(|uid| *uid != sender_uid).collect(),
+            // before the first scene enter there is no host peer yet, the world owner is the sender
+            ForwardType::ForwardToHost => host_uid.or(Some(sender_uid)).into_iter().collect(),
+            ForwardType::ForwardToAllGuest => {
+                player_uids.filter(|uid| Some(*uid) != host_uid).collect()
+            }
+            ForwardType::ForwardToPeer => {
+                let Some(uid) = self.peer_manager.get_uid_by_peer_id(forward_peer) else {
+                    tracing::debug!("invocation forward_peer {} not found", forward_peer);
+                    return Vec::new();
+                };
+                vec![uid]
+            }
+        }
+    }
+}
+
+pub fn flush_invocations(mut router: ResMut<InvocationRouter>, message_output: Res<MessageOutput>) {
+    for (uid, invokes) in router.ability_invokes.drain() {
+        message_output.send(
+            uid,
+            "AbilityInvocationsNotify",
+            AbilityInvocationsNotify { invokes },
+        );
+    }
+
+    for ((uid, entity_id), invokes) in router.init_finish_invokes.drain() {
+        message_output.send(
+            uid,
+            "ClientAbilityInitFinishNotify",
+            ClientAbilityInitFinishNotify { entity_id, invokes },
+        );
+    }
+
+    let combat_invokes: Vec<_> = router.combat_invokes.drain().collect();
+    for (uid, invoke_list) in combat_invokes {
+        router.combat_sequence_id += 1;
+        message_output.send(
+            uid,
+            "CombatInvocationsNotify",
+            CombatInvocationsNotify {
+                invoke_list,
+                client_sequence_id: router.combat_sequence_id,
+            },
+        );
+    }
+}
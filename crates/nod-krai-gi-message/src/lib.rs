@@ -1,5 +1,7 @@
 pub mod event;
+pub mod invocation;
 pub mod output;
+pub mod peer;
 
 pub static PLAYER_VERSION: std::sync::OnceLock<
     std::sync::Arc<dashmap::DashMap<u32, String>>,
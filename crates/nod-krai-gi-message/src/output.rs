@@ -20,6 +20,10 @@ impl MessageOutput {
         Self(client_map)
     }
 
+    pub fn player_uids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.keys().copied()
+    }
+
     pub fn send<T>(&self, player_uid: u32, message_name: &str, message: T)
     where
         T: Sized + Serialize + Protobuf,
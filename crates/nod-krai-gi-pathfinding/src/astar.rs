@@ -0,0 +1,304 @@
+use crate::nav_grid::{Cell, NavGrid};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+pub const MAX_EXPANDED_NODES: usize = 200_000;
+
+const NEIGHBORS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+pub struct GridPath {
+    pub cells: Vec<Cell>,
+    pub reached: bool,
+}
+
+fn heuristic(from: Cell, to: Cell) -> u32 {
+    let dx = from.0.abs_diff(to.0);
+    let dz = from.1.abs_diff(to.1);
+    STRAIGHT_COST * dx.max(dz) + (DIAGONAL_COST - STRAIGHT_COST) * dx.min(dz)
+}
+
+fn offset(grid: &NavGrid, cell: Cell, dx: i32, dz: i32) -> Option<Cell> {
+    let x = cell.0 as i64 + dx as i64;
+    let z = cell.1 as i64 + dz as i64;
+    if x < 0 || z < 0 || x >= grid.width as i64 || z >= grid.depth as i64 {
+        return None;
+    }
+    Some((x as u32, z as u32))
+}
+
+pub fn find_nearest_walkable(grid: &NavGrid, cell: Cell, radius: u32) -> Option<Cell> {
+    if grid.is_walkable(cell) {
+        return Some(cell);
+    }
+
+    let radius = radius as i32;
+    let mut best: Option<(u32, Cell)> = None;
+    for dz in -radius..=radius {
+        for dx in -radius..=radius {
+            let Some(candidate) = offset(grid, cell, dx, dz) else {
+                continue;
+            };
+            if !grid.is_walkable(candidate) {
+                continue;
+            }
+            let dist = (dx * dx + dz * dz) as u32;
+            if best.is_none_or(|(best_dist, _)| dist < best_dist) {
+                best = Some((dist, candidate));
+            }
+        }
+    }
+    best.map(|(_, cell)| cell)
+}
+
+// returns None only when start itself isn't walkable, otherwise falls back to the
+// explored cell closest to the goal with reached = false
+pub fn find_path(grid: &NavGrid, start: Cell, goal: Cell) -> Option<GridPath> {
+    let start_index = grid.index(start)?;
+    if !grid.is_walkable(start) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<usize, Cell> = HashMap::new();
+    let mut cost: HashMap<usize, u32> = HashMap::new();
+
+    cost.insert(start_index, 0);
+    open.push(Reverse((heuristic(start, goal), 0u32, start)));
+
+    let mut closest = (heuristic(start, goal), start);
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, cur_cost, cur))) = open.pop() {
+        if cur == goal {
+            return Some(GridPath {
+                cells: reconstruct(&came_from, grid, cur),
+                reached: true,
+            });
+        }
+
+        let cur_index = grid.index(cur)?;
+        if cost.get(&cur_index).is_some_and(|best| cur_cost > *best) {
+            continue;
+        }
+
+        expanded += 1;
+        if expanded > MAX_EXPANDED_NODES {
+            break;
+        }
+
+        let h = heuristic(cur, goal);
+        if h < closest.0 {
+            closest = (h, cur);
+        }
+
+        for (dx, dz) in NEIGHBORS {
+            let Some(next) = offset(grid, cur, dx, dz) else {
+                continue;
+            };
+            if !grid.can_step(cur, next) {
+                continue;
+            }
+
+            let step_cost = if dx != 0 && dz != 0 {
+                // don't cut corners around blocked cells
+                let side_x = offset(grid, cur, dx, 0);
+                let side_z = offset(grid, cur, 0, dz);
+                match (side_x, side_z) {
+                    (Some(side_x), Some(side_z))
+                        if grid.can_step(cur, side_x) && grid.can_step(cur, side_z) => {}
+                    _ => continue,
+                }
+                DIAGONAL_COST
+            } else {
+                STRAIGHT_COST
+            };
+
+            let next_cost = cur_cost + step_cost;
+            let Some(next_index) = grid.index(next) else {
+                continue;
+            };
+            if cost.get(&next_index).is_some_and(|best| next_cost >= *best) {
+                continue;
+            }
+
+            cost.insert(next_index, next_cost);
+            came_from.insert(next_index, cur);
+            open.push(Reverse((
+                next_cost + heuristic(next, goal),
+                next_cost,
+                next,
+            )));
+        }
+    }
+
+    Some(GridPath {
+        cells: reconstruct(&came_from, grid, closest.1),
+        reached: false,
+    })
+}
+
+fn reconstruct(came_from: &HashMap<usize, Cell>, grid: &NavGrid, end: Cell) -> Vec<Cell> {
+    let mut cells = vec![end];
+    let mut cur = end;
+    while let Some(prev) = grid.index(cur).and_then(|index| came_from.get(&index)) {
+        cur = *prev;
+        cells.push(cur);
+    }
+    cells.reverse();
+    cells
+}
+
+fn has_line_of_sight(grid: &NavGrid, from: Cell, to: Cell) -> bool {
+    let (mut x, mut z) = (from.0 as i64, from.1 as i64);
+    let (tx, tz) = (to.0 as i64, to.1 as i64);
+    let dx = (tx - x).abs();
+    let dz = (tz - z).abs();
+    let sx = if tx > x { 1 } else { -1 };
+    let sz = if tz > z { 1 } else { -1 };
+    let mut err = dx - dz;
+    let mut prev = from;
+
+    while (x, z) != (tx, tz) {
+        let e2 = err * 2;
+        if e2 > -dz {
+            err -= dz;
+            x += sx;
+        }
+        if e2 < dx {
+            err += dx;
+            z += sz;
+        }
+        let cur = (x as u32, z as u32);
+        if !grid.can_step(prev, cur) {
+            return false;
+        }
+        if prev.0 != cur.0 && prev.1 != cur.1 {
+            let side_x = (cur.0, prev.1);
+            let side_z = (prev.0, cur.1);
+            if !grid.can_step(prev, side_x) || !grid.can_step(prev, side_z) {
+                return false;
+            }
+        }
+        prev = cur;
+    }
+    true
+}
+
+// drops intermediate cells that can be skipped with a straight walk
+pub fn smooth_path(grid: &NavGrid, cells: &[Cell]) -> Vec<Cell> {
+    if cells.len() <= 2 {
+        return cells.to_vec();
+    }
+
+    let mut corners = vec![cells[0]];
+    let mut anchor = 0;
+    let mut i = 2;
+    while i < cells.len() {
+        if !has_line_of_sight(grid, cells[anchor], cells[i]) {
+            anchor = i - 1;
+            corners.push(cells[anchor]);
+        }
+        i += 1;
+    }
+    corners.push(cells[cells.len() - 1]);
+    corners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_grid(width: u32, depth: u32) -> NavGrid {
+        let mut grid = NavGrid::new(0.0, 0.0, 1.0, 1.0, width, depth);
+        for z in 0..depth {
+            for x in 0..width {
+                grid.set_height((x, z), Some(0.0));
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn straight_line_on_open_grid() {
+        let grid = flat_grid(10, 10);
+        let path = find_path(&grid, (0, 0), (9, 0)).unwrap();
+        assert!(path.reached);
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        assert_eq!(path.cells.last(), Some(&(9, 0)));
+        assert_eq!(path.cells.len(), 10);
+        assert_eq!(smooth_path(&grid, &path.cells), vec![(0, 0), (9, 0)]);
+    }
+
+    #[test]
+    fn walks_around_a_wall() {
+        let mut grid = flat_grid(10, 10);
+        for z in 0..9 {
+            grid.set_height((5, z), None);
+        }
+        let path = find_path(&grid, (0, 0), (9, 0)).unwrap();
+        assert!(path.reached);
+        assert!(path.cells.contains(&(5, 9)));
+        assert!(path.cells.iter().all(|cell| grid.is_walkable(*cell)));
+
+        let corners = smooth_path(&grid, &path.cells);
+        assert!(corners.len() > 2 && corners.len() < path.cells.len());
+        for pair in corners.windows(2) {
+            assert!(has_line_of_sight(&grid, pair[0], pair[1]));
+        }
+    }
+
+    #[test]
+    fn climb_limit_blocks_steps() {
+        let mut grid = flat_grid(3, 1);
+        grid.set_height((1, 0), Some(5.0));
+        let path = find_path(&grid, (0, 0), (2, 0)).unwrap();
+        assert!(!path.reached);
+        assert_eq!(path.cells, vec![(0, 0)]);
+    }
+
+    #[test]
+    fn unreachable_goal_returns_closest_cell() {
+        let mut grid = flat_grid(10, 1);
+        grid.set_height((6, 0), None);
+        let path = find_path(&grid, (0, 0), (9, 0)).unwrap();
+        assert!(!path.reached);
+        assert_eq!(path.cells.last(), Some(&(5, 0)));
+    }
+
+    #[test]
+    fn blocked_start_has_no_path() {
+        let mut grid = flat_grid(3, 3);
+        grid.set_height((0, 0), None);
+        assert!(find_path(&grid, (0, 0), (2, 2)).is_none());
+        assert_eq!(find_nearest_walkable(&grid, (0, 0), 1), Some((1, 0)));
+    }
+
+    #[test]
+    fn smoothing_keeps_corner_cells() {
+        let mut grid = flat_grid(5, 5);
+        for x in 0..4 {
+            grid.set_height((x, 1), None);
+        }
+        let path = find_path(&grid, (0, 0), (0, 2)).unwrap();
+        assert!(path.reached);
+        let corners = smooth_path(&grid, &path.cells);
+        assert_eq!(corners.first(), Some(&(0, 0)));
+        assert_eq!(corners.last(), Some(&(0, 2)));
+        assert!(corners.len() > 2);
+        for pair in corners.windows(2) {
+            assert!(has_line_of_sight(&grid, pair[0], pair[1]));
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Bakes a coarse walkable height grid per scene out of the scene scripts.
+//!
+//! There is no collision data on the server side, so every known spawn point
+//! (monsters, gadgets, regions, scene points, born pos) is treated as ground and
+//! the cells around it are marked walkable. Cells without samples are left
+//! without data and cells below the scene's die_y are marked blocked, neither
+//! can be walked through.
+//!
+//! This is not a navmesh. Walls, cliffs and buildings between samples are
+//! invisible to the bake, so a path only keeps to sampled ground, stays above
+//! die_y and avoids climbs steeper than --max-climb. Real obstacle avoidance
+//! needs the client's collision meshes, which the server doesn't have.
+//!
+//! usage: bake_nav_grid <scene_id>... [--lua-root assets/lua] [--bin-root assets/BinOutput]
+//!        [--out assets/navgrid] [--cell-size 2] [--radius 24] [--max-climb 4]
+
+use nod_krai_gi_data::scene::scene_point_config::{
+    get_scene_point_config_collection, load_scene_point_configs_from_bin,
+};
+use nod_krai_gi_data::scene::script_cache::{
+    get_scene_config_collection, init_scene_static_templates, load_lua_vm,
+    load_scene_group_from_cache, SCENE_BLOCK_COLLECTION, SCENE_LUA_VM,
+};
+use nod_krai_gi_data::scene::Position;
+use nod_krai_gi_pathfinding::nav_grid::NavGrid;
+use std::path::Path;
+use std::sync::Arc;
+
+struct BakeOptions {
+    scene_id_list: Vec<u32>,
+    lua_root: String,
+    bin_root: String,
+    out_root: String,
+    cell_size: f32,
+    radius: f32,
+    max_climb: f32,
+}
+
+fn parse_args() -> Option<BakeOptions> {
+    let mut options = BakeOptions {
+        scene_id_list: vec![],
+        lua_root: "assets/lua".to_string(),
+        bin_root: "assets/BinOutput".to_string(),
+        out_root: "assets/navgrid".to_string(),
+        cell_size: 2.0,
+        radius: 24.0,
+        max_climb: 4.0,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lua-root" => options.lua_root = args.next()?,
+            "--bin-root" => options.bin_root = args.next()?,
+            "--out" => options.out_root = args.next()?,
+            "--cell-size" => options.cell_size = args.next()?.parse().ok()?,
+            "--radius" => options.radius = args.next()?.parse().ok()?,
+            "--max-climb" => options.max_climb = args.next()?.parse().ok()?,
+            _ => options.scene_id_list.push(arg.parse().ok()?),
+        }
+    }
+
+    if options.scene_id_list.is_empty() || options.cell_size <= 0.0 {
+        return None;
+    }
+    Some(options)
+}
+
+fn collect_samples(options: &BakeOptions, scene_id: u32) -> Vec<Position> {
+    let mut samples = vec![];
+
+    let scene_config_collection_clone = get_scene_config_collection();
+    if let Some(scene_config) = scene_config_collection_clone.get(&scene_id) {
+        samples.push(scene_config.scene_config.born_pos.clone());
+    }
+
+    let lua = SCENE_LUA_VM.get().unwrap().clone();
+    let scene_block_collection_clone = Arc::clone(SCENE_BLOCK_COLLECTION.get().unwrap());
+    for entry in scene_block_collection_clone.iter() {
+        let (block_scene_id, block_id) = *entry.key();
+        if block_scene_id != scene_id {
+            continue;
+        }
+        for block_group in entry.value().groups.iter() {
+            let Some(group) = load_scene_group_from_cache(
+                &options.lua_root,
+                &lua,
+                scene_id,
+                block_id,
+                block_group.id,
+            ) else {
+                continue;
+            };
+            samples.extend(group.monsters.iter().map(|monster| monster.pos.clone()));
+            samples.extend(group.gadgets.iter().map(|gadget| gadget.pos.clone()));
+            samples.extend(group.regions.iter().map(|region| region.pos.clone()));
+        }
+    }
+
+    let scene_point_config_collection_clone = get_scene_point_config_collection();
+    if let Some(point_config) = scene_point_config_collection_clone.get(&scene_id) {
+        for point in point_config.points.values() {
+            samples.push(point.pos.clone());
+            samples.push(point.tran_pos.clone());
+        }
+    }
+
+    // points with no position at all are left at the origin by serde default
+    samples.retain(|pos| pos.x != 0.0 || pos.y != 0.0 || pos.z != 0.0);
+    samples
+}
+
+fn bake_scene(options: &BakeOptions, scene_id: u32) -> Option<NavGrid> {
+    let samples = collect_samples(options, scene_id);
+    if samples.is_empty() {
+        println!("scene {} has no samples", scene_id);
+        return None;
+    }
+
+    let (mut min_x, mut min_z) = (f32::MAX, f32::MAX);
+    let (mut max_x, mut max_z) = (f32::MIN, f32::MIN);
+    for pos in samples.iter() {
+        min_x = min_x.min(pos.x - options.radius);
+        min_z = min_z.min(pos.z - options.radius);
+        max_x = max_x.max(pos.x + options.radius);
+        max_z = max_z.max(pos.z + options.radius);
+    }
+
+    let scene_config_collection_clone = get_scene_config_collection();
+    let scene_config = scene_config_collection_clone
+        .get(&scene_id)
+        .map(|template| template.scene_config.clone());
+    if let Some(ref scene_config) = scene_config {
+        if scene_config.size.x > 0.0 && scene_config.size.z > 0.0 {
+            min_x = min_x.max(scene_config.begin_pos.x);
+            min_z = min_z.max(scene_config.begin_pos.z);
+            max_x = max_x.min(scene_config.begin_pos.x + scene_config.size.x);
+            max_z = max_z.min(scene_config.begin_pos.z + scene_config.size.z);
+        }
+    }
+    if max_x <= min_x || max_z <= min_z {
+        return None;
+    }
+
+    let width = ((max_x - min_x) / options.cell_size).ceil() as u32;
+    let depth = ((max_z - min_z) / options.cell_size).ceil() as u32;
+    let mut grid = NavGrid::new(
+        min_x,
+        min_z,
+        options.cell_size,
+        options.max_climb,
+        width,
+        depth,
+    );
+
+    // inverse distance weighted height of every sample in range
+    let mut weight_sum = vec![0f32; width as usize * depth as usize];
+    let mut height_sum = vec![0f32; width as usize * depth as usize];
+    let cell_radius = (options.radius / options.cell_size).ceil() as i64;
+
+    for pos in samples.iter() {
+        let Some(center) = grid.cell_at(pos.x, pos.z) else {
+            continue;
+        };
+        for dz in -cell_radius..=cell_radius {
+            for dx in -cell_radius..=cell_radius {
+                let x = center.0 as i64 + dx;
+                let z = center.1 as i64 + dz;
+                if x < 0 || z < 0 || x >= width as i64 || z >= depth as i64 {
+                    continue;
+                }
+                let cell = (x as u32, z as u32);
+                let (cx, cz) = grid.cell_center(cell);
+                let dist = ((cx - pos.x).powi(2) + (cz - pos.z).powi(2)).sqrt();
+                if dist > options.radius {
+                    continue;
+                }
+                let weight = 1.0 / (dist + 1.0).powi(2);
+                let index = grid.index(cell).unwrap();
+                weight_sum[index] += weight;
+                height_sum[index] += pos.y * weight;
+            }
+        }
+    }
+
+    let die_y = scene_config.and_then(|scene_config| scene_config.die_y);
+    for z in 0..depth {
+        for x in 0..width {
+            let index = grid.index((x, z)).unwrap();
+            if weight_sum[index] <= 0.0 {
+                continue;
+            }
+            let height = height_sum[index] / weight_sum[index];
+            if die_y.is_some_and(|die_y| height <= die_y) {
+                grid.set_height((x, z), None);
+                continue;
+            }
+            grid.set_height((x, z), Some(height));
+        }
+    }
+
+    Some(grid)
+}
+
+fn main() {
+    let Some(options) = parse_args() else {
+        println!(
+            "usage: bake_nav_grid <scene_id>... [--lua-root dir] [--bin-root dir] [--out dir] \
+             [--cell-size m] [--radius m] [--max-climb m]"
+        );
+        return;
+    };
+
+    load_lua_vm(format!("{}/common", options.lua_root).as_str());
+    init_scene_static_templates(format!("{}/scene", options.lua_root).as_str());
+    if Path::new(&format!("{}/Scene/Point", options.bin_root)).exists() {
+        load_scene_point_configs_from_bin(&options.bin_root);
+    }
+
+    for scene_id in options.scene_id_list.iter() {
+        let Some(grid) = bake_scene(&options, *scene_id) else {
+            println!("scene {} skipped", scene_id);
+            continue;
+        };
+
+        let path = format!("{}/scene_{}.navgrid", options.out_root, scene_id);
+        match grid.save(&path) {
+            Ok(()) => println!(
+                "scene {} baked {}x{} cells ({} walkable) -> {}",
+                scene_id,
+                grid.width,
+                grid.depth,
+                grid.walkable_count(),
+                path
+            ),
+            Err(err) => println!("scene {} failed to write {}: {}", scene_id, path, err),
+        }
+    }
+}
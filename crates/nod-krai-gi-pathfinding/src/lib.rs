@@ -1,9 +1,17 @@
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use nod_krai_gi_message::{event::ClientMessageEvent, output::MessageOutput};
-use nod_krai_gi_proto::normal::{query_path_rsp::PathStatusType, QueryPathReq, QueryPathRsp};
+use nod_krai_gi_proto::normal::{
+    query_path_rsp::PathStatusType, QueryPathReq, QueryPathRsp, Vector, Vector3Int,
+};
 use nod_krai_gi_proto::retcode::Retcode;
 
+pub mod astar;
+pub mod nav_grid;
+
+const NAV_GRID_ROOT: &str = "./assets/navgrid";
+const DEFAULT_SNAP_RADIUS: u32 = 2;
+
 pub struct PathfindingPlugin;
 
 impl Plugin for PathfindingPlugin {
@@ -36,22 +44,14 @@ fn pathfinding_packet_processor(
                 if let Some(request) = message.decode::<QueryPathReq>() {
                     // tracing::debug!("QueryPath: {request:?}");
 
-                    let mut corners = Vec::with_capacity(2);
-
-                    if let Some(source_pos) = request.source_pos {
-                        corners.push(source_pos);
-                    }
-
-                    if let Some(destination) = request.destination_pos.first() {
-                        corners.push(*destination);
-                    }
+                    let (query_status, corners) = query_path(&request);
 
                     message_output.send(
                         message.sender_uid(),
                         "QueryPathRsp",
                         QueryPathRsp {
                             retcode: Retcode::RetSucc.into(),
-                            query_status: PathStatusType::StatusSucc.into(),
+                            query_status: query_status.into(),
                             query_id: request.query_id,
                             corners,
                         },
@@ -62,3 +62,117 @@ fn pathfinding_packet_processor(
         }
     }
 }
+
+// where the grid can't say anything the path is left to the client the same way as
+// before there were grids
+fn straight_path(source_pos: Vector, destination: Option<&Vector>) -> Vec<Vector> {
+    let mut corners = vec![source_pos];
+    if let Some(destination) = destination {
+        corners.push(*destination);
+    }
+    corners
+}
+
+// source_pos followed by the centers of the smoothed cells after the start cell
+fn path_corners(
+    grid: &nav_grid::NavGrid,
+    source_pos: Vector,
+    cells: &[nav_grid::Cell],
+) -> Vec<Vector> {
+    let cells = astar::smooth_path(grid, cells);
+    let mut corners = Vec::with_capacity(cells.len());
+    corners.push(source_pos);
+    for cell in cells.iter().skip(1) {
+        let (x, z) = grid.cell_center(*cell);
+        let y = grid.height(*cell).unwrap_or(source_pos.y);
+        corners.push(Vector { x, y, z });
+    }
+    corners
+}
+
+fn query_path(request: &QueryPathReq) -> (PathStatusType, Vec<Vector>) {
+    let Some(source_pos) = request.source_pos else {
+        return (PathStatusType::StatusFail, vec![]);
+    };
+
+    // a scene with no grid (or one still loading) has nothing to route on
+    let Some(grid) = nav_grid::get_or_request_nav_grid(request.scene_id, NAV_GRID_ROOT) else {
+        return (
+            PathStatusType::StatusSucc,
+            straight_path(source_pos, request.destination_pos.first()),
+        );
+    };
+
+    let Some(start) = grid
+        .cell_at(source_pos.x, source_pos.z)
+        .and_then(|cell| nav_grid_snap(&grid, cell, request.source_extend.as_ref()))
+    else {
+        tracing::debug!(
+            "query path source {:?} has no nav grid coverage in scene {}",
+            source_pos,
+            request.scene_id
+        );
+        return (
+            PathStatusType::StatusSucc,
+            straight_path(source_pos, request.destination_pos.first()),
+        );
+    };
+
+    // (distance left to the destination, corners) of the best path that fell short
+    let mut partial: Option<(f32, Vec<Vector>)> = None;
+
+    for destination in request.destination_pos.iter() {
+        let Some(goal_cell) = grid.cell_at(destination.x, destination.z) else {
+            continue;
+        };
+        // a goal off the walkable cells can't be reached but still gets a partial path
+        let goal = nav_grid_snap(&grid, goal_cell, request.destination_extend.as_ref())
+            .unwrap_or(goal_cell);
+
+        let Some(path) = astar::find_path(&grid, start, goal) else {
+            continue;
+        };
+
+        if path.reached {
+            let mut corners = path_corners(&grid, source_pos, &path.cells);
+            if corners.len() > 1 {
+                corners.pop();
+            }
+            corners.push(*destination);
+            return (PathStatusType::StatusSucc, corners);
+        }
+
+        // the closest cell is the start itself, the path gets nowhere nearer
+        let Some(closest) = path.cells.last().filter(|_| path.cells.len() > 1) else {
+            continue;
+        };
+        let (x, z) = grid.cell_center(*closest);
+        let remaining = ((destination.x - x).powi(2) + (destination.z - z).powi(2)).sqrt();
+        if partial
+            .as_ref()
+            .is_none_or(|(best_remaining, _)| remaining < *best_remaining)
+        {
+            partial = Some((remaining, path_corners(&grid, source_pos, &path.cells)));
+        }
+    }
+
+    match partial {
+        Some((_, corners)) => (PathStatusType::StatusPartial, corners),
+        None => (PathStatusType::StatusFail, vec![]),
+    }
+}
+
+fn nav_grid_snap(
+    grid: &nav_grid::NavGrid,
+    cell: nav_grid::Cell,
+    extend: Option<&Vector3Int>,
+) -> Option<nav_grid::Cell> {
+    let radius = extend
+        .map(|extend| {
+            let extend = extend.x.max(extend.z).max(0) as f32;
+            (extend / grid.cell_size).ceil() as u32
+        })
+        .filter(|radius| *radius > 0)
+        .unwrap_or(DEFAULT_SNAP_RADIUS);
+    astar::find_nearest_walkable(grid, cell, radius)
+}
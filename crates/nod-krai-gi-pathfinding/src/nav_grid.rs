@@ -0,0 +1,284 @@
+use dashmap::DashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+pub const NAV_GRID_MAGIC: &[u8; 4] = b"NKNG";
+pub const NAV_GRID_VERSION: u16 = 2;
+
+// heights are stored in decimeters, these two mark a cell known not to be walkable
+// and a cell the baker had no samples for
+pub const BLOCKED_HEIGHT: i16 = i16::MIN;
+pub const NO_DATA_HEIGHT: i16 = i16::MIN + 1;
+
+const HEADER_SIZE: usize = 4 + 2 + 4 * 4 + 4 * 2;
+
+// a scene without a grid file is looked up again after this long
+const MISSING_RETRY_SECS: u64 = 60;
+
+pub enum NavGridState {
+    Loading,
+    Loaded(Arc<NavGrid>),
+    Missing(Instant),
+}
+
+pub static NAV_GRID_COLLECTION: OnceLock<Arc<DashMap<u32, NavGridState>>> = OnceLock::new();
+
+// never blocks on disk, the first query for a scene kicks off the load on a
+// background thread and gets None until the grid is in
+pub fn get_or_request_nav_grid(scene_id: u32, grid_root: &str) -> Option<Arc<NavGrid>> {
+    let grid_collection = NAV_GRID_COLLECTION.get_or_init(|| Arc::new(DashMap::new()));
+
+    match grid_collection.get(&scene_id).as_deref() {
+        Some(NavGridState::Loaded(grid)) => return Some(grid.clone()),
+        Some(NavGridState::Loading) => return None,
+        Some(NavGridState::Missing(since))
+            if since.elapsed() < Duration::from_secs(MISSING_RETRY_SECS) =>
+        {
+            return None
+        }
+        _ => {}
+    }
+
+    grid_collection.insert(scene_id, NavGridState::Loading);
+
+    let grid_collection = Arc::clone(grid_collection);
+    let grid_path = format!("{}/scene_{}.navgrid", grid_root, scene_id);
+    std::thread::spawn(move || {
+        let state = match NavGrid::load(&grid_path) {
+            Some(grid) => NavGridState::Loaded(Arc::new(grid)),
+            None => {
+                tracing::debug!("nav grid for scene {} not found at {}", scene_id, grid_path);
+                NavGridState::Missing(Instant::now())
+            }
+        };
+        grid_collection.insert(scene_id, state);
+    });
+
+    None
+}
+
+pub type Cell = (u32, u32);
+
+#[derive(Debug, Clone)]
+pub struct NavGrid {
+    pub origin_x: f32,
+    pub origin_z: f32,
+    pub cell_size: f32,
+    pub max_climb: f32,
+    pub width: u32,
+    pub depth: u32,
+    pub heights: Vec<i16>,
+}
+
+impl NavGrid {
+    pub fn new(
+        origin_x: f32,
+        origin_z: f32,
+        cell_size: f32,
+        max_climb: f32,
+        width: u32,
+        depth: u32,
+    ) -> Self {
+        Self {
+            origin_x,
+            origin_z,
+            cell_size,
+            max_climb,
+            width,
+            depth,
+            heights: vec![NO_DATA_HEIGHT; width as usize * depth as usize],
+        }
+    }
+
+    pub fn index(&self, cell: Cell) -> Option<usize> {
+        if cell.0 >= self.width || cell.1 >= self.depth {
+            return None;
+        }
+        Some(cell.1 as usize * self.width as usize + cell.0 as usize)
+    }
+
+    pub fn cell_at(&self, x: f32, z: f32) -> Option<Cell> {
+        let cx = ((x - self.origin_x) / self.cell_size).floor();
+        let cz = ((z - self.origin_z) / self.cell_size).floor();
+        if cx < 0.0 || cz < 0.0 || cx >= self.width as f32 || cz >= self.depth as f32 {
+            return None;
+        }
+        Some((cx as u32, cz as u32))
+    }
+
+    pub fn cell_center(&self, cell: Cell) -> (f32, f32) {
+        (
+            self.origin_x + (cell.0 as f32 + 0.5) * self.cell_size,
+            self.origin_z + (cell.1 as f32 + 0.5) * self.cell_size,
+        )
+    }
+
+    pub fn height(&self, cell: Cell) -> Option<f32> {
+        let height = *self.heights.get(self.index(cell)?)?;
+        if height == BLOCKED_HEIGHT || height == NO_DATA_HEIGHT {
+            return None;
+        }
+        Some(height as f32 / 10.0)
+    }
+
+    // false for cells the baker never sampled, true for ground and known blocked cells
+    pub fn has_data(&self, cell: Cell) -> bool {
+        self.index(cell)
+            .and_then(|index| self.heights.get(index))
+            .is_some_and(|height| *height != NO_DATA_HEIGHT)
+    }
+
+    pub fn set_height(&mut self, cell: Cell, height: Option<f32>) {
+        let Some(index) = self.index(cell) else {
+            return;
+        };
+        self.heights[index] = match height {
+            None => BLOCKED_HEIGHT,
+            Some(height) => (height * 10.0)
+                .round()
+                .clamp(NO_DATA_HEIGHT as f32 + 1.0, i16::MAX as f32)
+                as i16,
+        };
+    }
+
+    pub fn is_walkable(&self, cell: Cell) -> bool {
+        self.height(cell).is_some()
+    }
+
+    pub fn can_step(&self, from: Cell, to: Cell) -> bool {
+        match (self.height(from), self.height(to)) {
+            (Some(from_height), Some(to_height)) => {
+                (from_height - to_height).abs() <= self.max_climb
+            }
+            _ => false,
+        }
+    }
+
+    pub fn walkable_count(&self) -> usize {
+        self.heights
+            .iter()
+            .filter(|height| **height != BLOCKED_HEIGHT && **height != NO_DATA_HEIGHT)
+            .count()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_SIZE || &bytes[0..4] != NAV_GRID_MAGIC {
+            return None;
+        }
+
+        let read_u16 = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        let read_f32 = |offset: usize| f32::from_bits(read_u32(offset));
+
+        if read_u16(4) != NAV_GRID_VERSION {
+            return None;
+        }
+
+        let origin_x = read_f32(6);
+        let origin_z = read_f32(10);
+        let cell_size = read_f32(14);
+        let max_climb = read_f32(18);
+        let width = read_u32(22);
+        let depth = read_u32(26);
+
+        if !cell_size.is_finite()
+            || cell_size <= 0.0
+            || !max_climb.is_finite()
+            || !origin_x.is_finite()
+            || !origin_z.is_finite()
+        {
+            return None;
+        }
+
+        let cell_count = width as usize * depth as usize;
+        if bytes.len() != HEADER_SIZE + cell_count * 2 {
+            return None;
+        }
+
+        let heights = bytes[HEADER_SIZE..]
+            .chunks_exact(2)
+            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect();
+
+        Some(Self {
+            origin_x,
+            origin_z,
+            cell_size,
+            max_climb,
+            width,
+            depth,
+            heights,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.heights.len() * 2);
+        bytes.extend_from_slice(NAV_GRID_MAGIC);
+        bytes.extend_from_slice(&NAV_GRID_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.origin_x.to_le_bytes());
+        bytes.extend_from_slice(&self.origin_z.to_le_bytes());
+        bytes.extend_from_slice(&self.cell_size.to_le_bytes());
+        bytes.extend_from_slice(&self.max_climb.to_le_bytes());
+        bytes.extend_from_slice(&self.width.to_le_bytes());
+        bytes.extend_from_slice(&self.depth.to_le_bytes());
+        for height in &self.heights {
+            bytes.extend_from_slice(&height.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn load(path: &str) -> Option<Self> {
+        let bytes = fs::read(path).ok()?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut grid = NavGrid::new(-10.0, 20.0, 2.0, 4.0, 3, 2);
+        grid.set_height((1, 1), Some(12.34));
+        let loaded = NavGrid::from_bytes(&grid.to_bytes()).unwrap();
+        assert_eq!(loaded.heights, grid.heights);
+        assert_eq!(loaded.height((1, 1)), Some(12.3));
+        assert!(!loaded.is_walkable((0, 0)));
+    }
+
+    #[test]
+    fn blocked_is_not_missing_data() {
+        let mut grid = NavGrid::new(0.0, 0.0, 1.0, 1.0, 2, 1);
+        grid.set_height((1, 0), None);
+        assert!(!grid.has_data((0, 0)));
+        assert!(grid.has_data((1, 0)));
+        assert!(!grid.is_walkable((0, 0)) && !grid.is_walkable((1, 0)));
+        assert_eq!(grid.walkable_count(), 0);
+    }
+
+    #[test]
+    fn rejects_bad_cell_size() {
+        for cell_size in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let mut grid = NavGrid::new(0.0, 0.0, 1.0, 1.0, 1, 1);
+            grid.cell_size = cell_size;
+            assert!(NavGrid::from_bytes(&grid.to_bytes()).is_none());
+        }
+    }
+}
@@ -10563,6 +10563,14 @@ pub struct BackupGroupBin {
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(default)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupLogicStateBin {
+    #[prost(map = "string, int32", tag = "1")]
+    #[serde(skip_serializing_if = "crate::is_default")]
+    pub value_map: ::std::collections::HashMap<::prost::alloc::string::String, i32>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SceneBlockGroupCompBin {
     #[prost(uint32, repeated, tag = "1")]
     #[serde(skip_serializing_if = "crate::is_default")]
@@ -10573,6 +10581,9 @@ pub struct SceneBlockGroupCompBin {
     #[prost(bool, tag = "3")]
     #[serde(skip_serializing_if = "crate::is_default")]
     pub is_scene_group_all_dead: bool,
+    #[prost(map = "uint32, message", tag = "4")]
+    #[serde(skip_serializing_if = "crate::is_default")]
+    pub logic_state_map: ::std::collections::HashMap<u32, GroupLogicStateBin>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(default)]
@@ -12260,6 +12271,9 @@ pub struct PlayerBasicCompBin {
     #[prost(string, tag = "48")]
     #[serde(skip_serializing_if = "crate::is_default")]
     pub ip_region_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "49")]
+    #[serde(skip_serializing_if = "crate::is_default")]
+    pub world_level: u32,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(default)]
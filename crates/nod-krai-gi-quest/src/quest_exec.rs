@@ -2,8 +2,12 @@ use bevy_ecs::prelude::{MessageReader, MessageWriter, Res, ResMut};
 use common::string_util::InternString;
 use nod_krai_gi_data::quest;
 use nod_krai_gi_data::quest::quest_config::{QuestContent, QuestExec};
+use nod_krai_gi_event::combat::AvatarEnergyAddEvent;
 use nod_krai_gi_event::lua::{LuaTriggerEvent, SpawnGroupEntityEvent};
 use nod_krai_gi_event::quest::*;
+use nod_krai_gi_event::scene::{
+    LevelTagChangeEvent, ScenePointOperateEvent, ScenePointOperation, SceneTagOperateEvent,
+};
 use nod_krai_gi_message::output::MessageOutput;
 use nod_krai_gi_persistence::Players;
 use nod_krai_gi_proto::normal::QuestUpdateQuestVarNotify;
@@ -37,6 +41,10 @@ pub fn quest_exec(
     mut quest_accept_events: MessageWriter<QuestAcceptEvent>,
     mut spawn_group_entity_events: MessageWriter<SpawnGroupEntityEvent>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+    mut scene_point_events: MessageWriter<ScenePointOperateEvent>,
+    mut scene_tag_events: MessageWriter<SceneTagOperateEvent>,
+    mut level_tag_events: MessageWriter<LevelTagChangeEvent>,
+    mut avatar_energy_add_events: MessageWriter<AvatarEnergyAddEvent>,
 ) {
     let sub_quest_config_collection = quest::quest_config::get_sub_quest_config_collection();
 
@@ -310,6 +318,12 @@ pub fn quest_exec(
                         param_u32(params, 0),
                         param_u32(params, 1)
                     );
+                    scene_point_events.write(ScenePointOperateEvent {
+                        uid: event.player_uid,
+                        scene_id: param_u32(params, 0),
+                        point_id: param_u32(params, 1),
+                        operation: ScenePointOperation::Unlock,
+                    });
                 }
                 QuestExec::UnlockArea => {
                     tracing::debug!(
@@ -337,7 +351,10 @@ pub fn quest_exec(
                     );
                 }
                 QuestExec::AddCurAvatarEnergy => {
-                    tracing::debug!("[QuestExec] AddCurAvatarEnergy");
+                    // without an amount the current avatar is charged up fully
+                    let energy = params.first().and_then(|s| s.as_str().parse::<f32>().ok());
+                    tracing::debug!("[QuestExec] AddCurAvatarEnergy energy={:?}", energy);
+                    avatar_energy_add_events.write(AvatarEnergyAddEvent(event.player_uid, energy));
                 }
                 QuestExec::SetIsFlyable => {
                     tracing::debug!(
@@ -416,6 +433,12 @@ pub fn quest_exec(
                         param_u32(params, 0),
                         param_u32(params, 1)
                     );
+                    scene_tag_events.write(SceneTagOperateEvent {
+                        uid: event.player_uid,
+                        scene_id: param_u32(params, 0),
+                        scene_tag_id: param_u32(params, 1),
+                        is_add: true,
+                    });
                 }
                 QuestExec::DelSceneTag => {
                     tracing::debug!(
@@ -423,6 +446,22 @@ pub fn quest_exec(
                         param_u32(params, 0),
                         param_u32(params, 1)
                     );
+                    scene_tag_events.write(SceneTagOperateEvent {
+                        uid: event.player_uid,
+                        scene_id: param_u32(params, 0),
+                        scene_tag_id: param_u32(params, 1),
+                        is_add: false,
+                    });
+                }
+                QuestExec::ChangeSceneLevelTag => {
+                    tracing::debug!(
+                        "[QuestExec] ChangeSceneLevelTag tag={}",
+                        param_u32(params, 0)
+                    );
+                    level_tag_events.write(LevelTagChangeEvent {
+                        uid: event.player_uid,
+                        level_tag_id: param_u32(params, 0),
+                    });
                 }
                 _ => {
                     tracing::debug!(
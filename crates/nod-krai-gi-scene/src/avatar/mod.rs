@@ -1,5 +1,4 @@
 use bevy_ecs::prelude::*;
-use nod_krai_gi_data::prop_type::FightPropType;
 use nod_krai_gi_entity::avatar::{AvatarQueryReadOnly, CurrentPlayerAvatarMarker};
 use nod_krai_gi_entity::avatar::{CurrentTeam, ReplaceCurrentPlayerAvatarMarker};
 use nod_krai_gi_entity::common::{FightProperties, LifeState, Visible};
@@ -17,7 +16,8 @@ use nod_krai_gi_proto::normal::{
 use nod_krai_gi_proto::retcode::Retcode;
 use std::collections::HashSet;
 use tracing::{debug, instrument};
-use nod_krai_gi_event::entity::{EntityDisappearEvent, EntityPropertySeparateUpdateEvent};
+use nod_krai_gi_event::entity::EntityDisappearEvent;
+use crate::revive::PlayerDeathStates;
 
 pub fn change_avatar(
     mut client_messages: MessageReader<ClientMessageEvent>,
@@ -31,8 +31,8 @@ pub fn change_avatar(
         Option<&CurrentPlayerAvatarMarker>,
     )>,
     message_output: Res<MessageOutput>,
-    mut update_separate_property_entity_events: MessageWriter<EntityPropertySeparateUpdateEvent>,
     mut disappear_events: MessageWriter<EntityDisappearEvent>,
+    mut death_states: ResMut<PlayerDeathStates>,
 ) {
     for message in client_messages.read() {
         match message.message_name() {
@@ -104,40 +104,36 @@ pub fn change_avatar(
                             continue;
                         };
 
-                    let mut all_dead = true;
-                    let mut first_alive_avatar_guid = None;
-                    let mut first_alive_avatar_entity = None;
-                    let mut first_alive_avatar_transform = None;
-
-                    for (avatar_entity, _, life_state, avatar_data, _) in
-                        avatars.iter().filter(|(_, _, _, a, _)| {
-                            a.owner_player_uid.0 == message.sender_uid()
-                                && avatar_guid_list.contains(&a.guid.0)
-                        })
-                    {
-                        if *life_state == LifeState::Alive && first_alive_avatar_guid.is_none() {
-                            all_dead = false;
-                            first_alive_avatar_guid = Some(avatar_data.guid.0);
-                            first_alive_avatar_entity = Some(avatar_entity);
-                            first_alive_avatar_transform = Some(avatar_data.transform.clone());
-                        }
-                    }
+                    // the next alive avatar in team order after the one that died takes over
+                    let die_index = avatar_guid_list
+                        .iter()
+                        .position(|guid| *guid == request.die_guid)
+                        .unwrap_or(0);
+                    let next_alive_avatar = avatar_guid_list
+                        .iter()
+                        .cycle()
+                        .skip(die_index + 1)
+                        .take(avatar_guid_list.len())
+                        .find_map(|guid| {
+                            avatars.iter().find(|(_, _, life_state, a, _)| {
+                                a.owner_player_uid.0 == message.sender_uid()
+                                    && a.guid.0 == *guid
+                                    && **life_state == LifeState::Alive
+                            })
+                        });
 
-                    if let (Some(guid), Some(entity), Some(transform)) = (
-                        first_alive_avatar_guid,
-                        first_alive_avatar_entity,
-                        first_alive_avatar_transform,
-                    ) {
+                    debug!("all_dead:{}", next_alive_avatar.is_none());
+                    if let Some((entity, _, _, avatar_data, _)) = next_alive_avatar {
                         if let Some(ref mut player_avatar_bin) = player_info.avatar_bin {
-                            player_avatar_bin.cur_avatar_guid = guid;
+                            player_avatar_bin.cur_avatar_guid = avatar_data.guid.0;
                         }
 
                         let transform = match request.reborn_pos {
                             Some(move_pos) => Transform {
                                 position: move_pos.into(),
-                                rotation: transform.rotation,
+                                rotation: avatar_data.transform.rotation,
                             },
-                            _ => transform,
+                            _ => avatar_data.transform.clone(),
                         };
 
                         debug!("transform:{}", transform);
@@ -148,48 +144,15 @@ pub fn change_avatar(
                             .insert(Visible)
                             .insert(ReplaceCurrentPlayerAvatarMarker(0))
                             .insert(transform.clone());
+                    } else if !death_states.is_wiped(message.sender_uid()) {
+                        // the client answers with WorldPlayerReviveReq once the player confirms
+                        message_output.send(
+                            message.sender_uid(),
+                            "WorldPlayerDieNotify",
+                            death_states.wipe(message.sender_uid()),
+                        );
                     }
 
-                    debug!("all_dead:{}", all_dead);
-                    if all_dead {
-                        let Some(ref player_avatar_bin) = player_info.avatar_bin else {
-                            continue;
-                        };
-                        for (avatar_entity, fight_props, _, avatar_data, _) in
-                            avatars.iter().filter(|(_, _, _, a, _)| {
-                                a.owner_player_uid.0 == message.sender_uid()
-                                    && player_avatar_bin.cur_avatar_guid_list.contains(&a.guid.0)
-                            })
-                        {
-                            let max_hp = fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP);
-                            update_separate_property_entity_events.write(
-                                EntityPropertySeparateUpdateEvent(
-                                    avatar_entity,
-                                    FightPropType::FIGHT_PROP_CUR_HP,
-                                    max_hp,
-                                ),
-                            );
-                            if player_avatar_bin.cur_avatar_guid == avatar_data.guid.0 {
-                                let transform = match request.reborn_pos {
-                                    Some(move_pos) => Transform {
-                                        position: move_pos.into(),
-                                        rotation: avatar_data.transform.rotation,
-                                    },
-                                    _ => avatar_data.transform.clone(),
-                                };
-
-                                debug!("transform:{}", transform);
-
-                                commands
-                                    .entity(avatar_entity)
-                                    .insert(CurrentPlayerAvatarMarker)
-                                    .insert(Visible)
-                                    .insert(ReplaceCurrentPlayerAvatarMarker(0))
-                                    .insert(transform.clone());
-                            }
-                        }
-                    };
-
                     message_output.send(
                         message.sender_uid(),
                         "AvatarDieAnimationEndRsp",
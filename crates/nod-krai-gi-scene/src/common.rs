@@ -50,46 +50,3 @@ impl Default for PlayerSceneState {
         Self::new()
     }
 }
-
-#[derive(Resource, Default)]
-pub struct ScenePeerManager {
-    host_peer_id: u32,
-    peer_map: HashMap<u32, u32>,
-}
-
-impl ScenePeerManager {
-    pub fn get_or_add_peer(&mut self, player_uid: u32) -> u32 {
-        if let Some((peer_id, _)) = self.peer_map.iter().find(|(_, uid)| **uid == player_uid) {
-            return *peer_id;
-        }
-
-        let mut peer_id = 1;
-        self.peer_map.keys().for_each(|id| {
-            if peer_id == *id {
-                peer_id += 1;
-            }
-        });
-
-        self.peer_map.insert(peer_id, player_uid);
-        peer_id
-    }
-
-    pub fn peer_count(&self) -> usize {
-        self.peer_map.len()
-    }
-
-    pub fn make_host(&mut self, peer_id: u32) {
-        self.host_peer_id = peer_id;
-    }
-
-    pub fn host_peer_id(&self) -> u32 {
-        self.host_peer_id
-    }
-
-    pub fn get_peer_id_by_uid(&self, player_uid: u32) -> u32 {
-        let Some(map) = self.peer_map.iter().find(|(_, uid)| **uid == player_uid) else {
-            return 0;
-        };
-        *map.0
-    }
-}
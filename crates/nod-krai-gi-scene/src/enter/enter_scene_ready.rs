@@ -1,7 +1,8 @@
-use crate::common::{PlayerSceneStates, ScenePeerManager};
+use crate::common::PlayerSceneStates;
 use bevy_ecs::prelude::*;
 use nod_krai_gi_event::scene::*;
 use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_message::peer::ScenePeerManager;
 use nod_krai_gi_persistence::Players;
 use nod_krai_gi_proto::dy_parser::{replace_out_i32, replace_out_u32};
 use nod_krai_gi_proto::retcode::Retcode;
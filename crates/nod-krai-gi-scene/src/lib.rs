@@ -3,7 +3,7 @@ use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use ::common::player_cache::cache_set_is_tp;
 use ::common::time_util::unix_timestamp_ms;
-use common::{PlayerSceneState, PlayerSceneStates, ScenePeerManager};
+use common::{PlayerSceneState, PlayerSceneStates};
 use enter::EnterSceneStateSystems;
 use nod_krai_gi_data::excel::{SceneTagConfig, SceneTagConfigKeyed};
 use nod_krai_gi_entity::avatar::{CurrentPlayerAvatarMarker, CurrentTeam};
@@ -19,6 +19,7 @@ use nod_krai_gi_entity::{
 };
 use nod_krai_gi_event::scene::*;
 use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_message::peer::ScenePeerManager;
 use nod_krai_gi_persistence::Players;
 use nod_krai_gi_proto::dy_parser::{replace_out_u32, replace_out_u64};
 use nod_krai_gi_proto::normal::{EnterType, ProtEntityType, VisionType};
@@ -29,7 +30,9 @@ mod avatar;
 mod enter;
 mod player_join_team;
 mod player_jump;
+mod revive;
 mod scene_team_update;
+mod statue;
 mod sync_enter_info;
 
 pub mod common;
@@ -41,10 +44,16 @@ impl Plugin for ScenePlugin {
         app.init_resource::<EnterSceneStateSystems>()
             .insert_resource(PlayerSceneStates::default())
             .insert_resource(ScenePeerManager::default())
+            .init_resource::<revive::PlayerDeathStates>()
+            .init_resource::<statue::StatueRegions>()
             .add_systems(PostStartup, init_scene)
             .add_systems(PreUpdate, enter::handle_enter_scene_state_change)
             .add_systems(PreUpdate, (set_up_avatar_team, replace_avatar_team).chain())
             .add_systems(PreUpdate, change_avatar)
+            .add_systems(PreUpdate, statue::handle_trans_point_region)
+            .add_systems(Update, revive::record_player_die_cause)
+            .add_systems(Update, revive::handle_world_player_revive)
+            .add_systems(Update, statue::statue_heal_system)
             .add_systems(Update, player_join_team::player_join_team)
             .add_systems(Update, player_jump::player_jump)
             .add_systems(Update, player_jump::player_jump_by_point)
@@ -198,6 +207,7 @@ fn notify_player_enter_scene(
     message_output: Res<MessageOutput>,
     player_scene_states: Res<PlayerSceneStates>,
     world_version_config: Res<WorldVersionConfig>,
+    world_level: Res<WorldLevel>,
 ) {
     for event in events.read() {
         let Some(player_scene_state) = player_scene_states.get(&event.uid) else {
@@ -255,7 +265,7 @@ fn notify_player_enter_scene(
                 world_level: replace_out_u32(
                     world_version_config.protocol_version.as_str(),
                     "PlayerEnterSceneNotify.world_level",
-                    9,
+                    world_level.0,
                 ),
                 enter_reason: replace_out_u32(
                     world_version_config.protocol_version.as_str(),
@@ -1,4 +1,3 @@
-use crate::common::ScenePeerManager;
 use bevy_ecs::prelude::*;
 use nod_krai_gi_entity::avatar::{
     spawn_avatar_entity, AvatarQueryReadOnly, ControlPeer, CurrentTeam,
@@ -9,6 +8,7 @@ use nod_krai_gi_entity::{
     transform::Transform,
 };
 use nod_krai_gi_event::scene::*;
+use nod_krai_gi_message::peer::ScenePeerManager;
 use nod_krai_gi_persistence::Players;
 
 pub fn player_join_team(
@@ -0,0 +1,200 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::prop_type::FightPropType;
+use nod_krai_gi_data::scene::scene_point_config::get_scene_point_config_collection;
+use nod_krai_gi_entity::avatar::AvatarQueryReadOnly;
+use nod_krai_gi_entity::common::{EntityById, FightProperties};
+use nod_krai_gi_entity::gadget::GadgetID;
+use nod_krai_gi_entity::monster::MonsterID;
+use nod_krai_gi_event::combat::PlayerDieCauseEvent;
+use nod_krai_gi_event::entity::EntityPropertyUpdateEvent;
+use nod_krai_gi_event::scene::*;
+use nod_krai_gi_message::{event::ClientMessageEvent, output::MessageOutput};
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::{
+    world_player_die_notify, PlayerDieType, WorldPlayerDieNotify, WorldPlayerReviveRsp,
+};
+use nod_krai_gi_proto::retcode::Retcode;
+use nod_krai_gi_proto::server_only::SceneBin;
+use std::collections::HashMap;
+
+// points a wiped team can be sent back to
+const REVIVE_POINT_TYPES: [&str; 2] = ["SceneTransPoint", "SceneBuildingPoint"];
+
+#[derive(Default)]
+pub struct PlayerDeathState {
+    die_type: PlayerDieType,
+    murderer_entity_id: u32,
+    murderer: Option<world_player_die_notify::Entity>,
+    // set once the whole team is dead, cleared by WorldPlayerReviveReq
+    wiped: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct PlayerDeathStates(HashMap<u32, PlayerDeathState>);
+
+impl PlayerDeathStates {
+    pub fn is_wiped(&self, uid: u32) -> bool {
+        self.0.get(&uid).is_some_and(|state| state.wiped)
+    }
+
+    // marks the team as wiped and builds the notify from the last recorded cause
+    pub fn wipe(&mut self, uid: u32) -> WorldPlayerDieNotify {
+        let state = self.0.entry(uid).or_default();
+        state.wiped = true;
+        WorldPlayerDieNotify {
+            die_type: state.die_type.into(),
+            murderer_entity_id: state.murderer_entity_id,
+            entity: state.murderer,
+            ..Default::default()
+        }
+    }
+}
+
+pub fn record_player_die_cause(
+    index: Res<EntityById>,
+    mut events: MessageReader<PlayerDieCauseEvent>,
+    murderers: Query<(Option<&MonsterID>, Option<&GadgetID>)>,
+    mut death_states: ResMut<PlayerDeathStates>,
+) {
+    for PlayerDieCauseEvent(uid, die_type, murderer_entity_id) in events.read() {
+        let murderer = index
+            .0
+            .get(murderer_entity_id)
+            .and_then(|entity| murderers.get(*entity).ok())
+            .and_then(|(monster_id, gadget_id)| match (monster_id, gadget_id) {
+                (Some(monster_id), _) => {
+                    Some(world_player_die_notify::Entity::MonsterId(monster_id.0))
+                }
+                (_, Some(gadget_id)) => {
+                    Some(world_player_die_notify::Entity::GadgetId(gadget_id.0))
+                }
+                _ => None,
+            });
+
+        let state = death_states.0.entry(*uid).or_default();
+        state.die_type = *die_type;
+        state.murderer_entity_id = *murderer_entity_id;
+        state.murderer = murderer;
+    }
+}
+
+pub fn handle_world_player_revive(
+    mut client_messages: MessageReader<ClientMessageEvent>,
+    players: Res<Players>,
+    avatars: Query<(Entity, &FightProperties, AvatarQueryReadOnly)>,
+    mut death_states: ResMut<PlayerDeathStates>,
+    mut update_property_entity_events: MessageWriter<EntityPropertyUpdateEvent>,
+    mut jump_events: MessageWriter<ScenePlayerJumpEvent>,
+    message_output: Res<MessageOutput>,
+) {
+    for message in client_messages.read() {
+        if message.message_name() != "WorldPlayerReviveReq" {
+            continue;
+        }
+        let uid = message.sender_uid();
+
+        if !death_states.is_wiped(uid) {
+            tracing::debug!("uid {} asked to revive with an alive avatar", uid);
+            message_output.send(
+                uid,
+                "WorldPlayerReviveRsp",
+                WorldPlayerReviveRsp {
+                    retcode: Retcode::RetAvatarNotDead.into(),
+                },
+            );
+            continue;
+        }
+
+        let Some(player_info) = players.get(uid) else {
+            continue;
+        };
+        let (Some(player_scene_bin), Some(player_avatar_bin)) =
+            (&player_info.scene_bin, &player_info.avatar_bin)
+        else {
+            continue;
+        };
+
+        for (avatar_entity, fight_props, _) in avatars.iter().filter(|(_, _, avatar_data)| {
+            avatar_data.owner_player_uid.0 == uid
+                && player_avatar_bin
+                    .cur_avatar_guid_list
+                    .contains(&avatar_data.guid.0)
+        }) {
+            update_property_entity_events.write(EntityPropertyUpdateEvent(
+                avatar_entity,
+                FightPropType::FIGHT_PROP_CUR_HP,
+                fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP),
+            ));
+        }
+
+        let scene_id = player_scene_bin.my_cur_scene_id;
+        let cur_pos = player_scene_bin.my_cur_scene_pos.unwrap_or_default();
+        let scene_bin = player_scene_bin
+            .world
+            .as_ref()
+            .and_then(|world_bin| world_bin.scene_map.get(&scene_id));
+
+        // without an unlocked point nearby the team gets back up where it fell
+        let destination =
+            closest_revive_point(scene_id, scene_bin, (cur_pos.x, cur_pos.y, cur_pos.z))
+                .unwrap_or((cur_pos.x, cur_pos.y, cur_pos.z));
+        tracing::debug!(
+            "uid {} revives in scene {} at {:?}",
+            uid,
+            scene_id,
+            destination
+        );
+
+        if let Some(state) = death_states.0.get_mut(&uid) {
+            *state = PlayerDeathState::default();
+        }
+
+        jump_events.write(ScenePlayerJumpEvent(
+            uid,
+            scene_id,
+            EnterReason::Revival,
+            destination,
+        ));
+
+        message_output.send(
+            uid,
+            "WorldPlayerReviveRsp",
+            WorldPlayerReviveRsp {
+                retcode: Retcode::RetSucc.into(),
+            },
+        );
+    }
+}
+
+// same rule as the map's scene point state: points are unlocked unless explicitly locked
+fn is_point_unlocked(scene_bin: Option<&SceneBin>, point_id: u32) -> bool {
+    scene_bin.is_none_or(|scene_bin| {
+        scene_bin.unlocked_point_list.contains(&point_id)
+            || !scene_bin.locked_point_list.contains(&point_id)
+    })
+}
+
+fn closest_revive_point(
+    scene_id: u32,
+    scene_bin: Option<&SceneBin>,
+    (x, y, z): (f32, f32, f32),
+) -> Option<(f32, f32, f32)> {
+    let scene_point_config_collection = get_scene_point_config_collection();
+    let scene_point_config = scene_point_config_collection.get(&scene_id)?;
+
+    scene_point_config
+        .points
+        .iter()
+        .filter(|(point_id, point_data)| {
+            REVIVE_POINT_TYPES.contains(&point_data.point_type.as_str())
+                && (point_data.tran_scene_id == 0 || point_data.tran_scene_id == scene_id)
+                && is_point_unlocked(scene_bin, **point_id)
+        })
+        .map(|(_, point_data)| {
+            let pos = &point_data.pos;
+            let distance = (pos.x - x).powi(2) + (pos.y - y).powi(2) + (pos.z - z).powi(2);
+            (distance, &point_data.tran_pos)
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, tran_pos)| (tran_pos.x, tran_pos.y, tran_pos.z))
+}
@@ -0,0 +1,183 @@
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::prop_type::{FightPropType, PROP_CUR_SPRING_VOLUME, PROP_MAX_SPRING_VOLUME};
+use nod_krai_gi_data::scene::scene_point_config::get_scene_point_config_collection;
+use nod_krai_gi_entity::avatar::CurrentTeam;
+use nod_krai_gi_entity::common::{FightProperties, OwnerPlayerUID};
+use nod_krai_gi_event::entity::EntityPropertyUpdateEvent;
+use nod_krai_gi_message::{event::ClientMessageEvent, output::MessageOutput};
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::normal::{
+    prop_value, EnterTransPointRegionNotify, ExitTransPointRegionNotify, PlayerPropNotify,
+    PropValue,
+};
+use nod_krai_gi_proto::server_only::SceneBin;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// the pool is kept in hundredths of hp, like the spring volume props
+const MAX_SPRING_VOLUME: f32 = 8500000.0;
+const SPRING_VOLUME_PER_HP: f32 = 100.0;
+// an empty pool fills up again in ten minutes
+const SPRING_RECOVER_PER_MS: f32 = MAX_SPRING_VOLUME / 600000.0;
+const HEAL_INTERVAL: Duration = Duration::from_secs(1);
+
+const STATUE_POINT_TYPE: &str = "SceneBuildingPoint";
+
+pub struct StatueRegion {
+    scene_id: u32,
+    point_id: u32,
+    next_heal_at: Instant,
+    // last volume sent to the client, none until the first heal tick
+    synced_volume: Option<i64>,
+}
+
+// players standing next to a statue, keyed by uid
+#[derive(Resource, Default)]
+pub struct StatueRegions(HashMap<u32, StatueRegion>);
+
+pub fn handle_trans_point_region(
+    mut client_messages: MessageReader<ClientMessageEvent>,
+    mut statue_regions: ResMut<StatueRegions>,
+) {
+    for message in client_messages.read() {
+        match message.message_name() {
+            "EnterTransPointRegionNotify" => {
+                let Some(notify) = message.decode::<EnterTransPointRegionNotify>() else {
+                    continue;
+                };
+                let is_statue = get_scene_point_config_collection()
+                    .get(&notify.scene_id)
+                    .and_then(|scene_config| scene_config.points.get(&notify.point_id))
+                    .is_some_and(|point_data| point_data.point_type == STATUE_POINT_TYPE);
+                if !is_statue {
+                    continue;
+                }
+
+                tracing::debug!(
+                    "uid {} entered statue region {} in scene {}",
+                    message.sender_uid(),
+                    notify.point_id,
+                    notify.scene_id
+                );
+                statue_regions.0.insert(
+                    message.sender_uid(),
+                    StatueRegion {
+                        scene_id: notify.scene_id,
+                        point_id: notify.point_id,
+                        next_heal_at: Instant::now(),
+                        synced_volume: None,
+                    },
+                );
+            }
+            "ExitTransPointRegionNotify" => {
+                let Some(notify) = message.decode::<ExitTransPointRegionNotify>() else {
+                    continue;
+                };
+                if statue_regions
+                    .0
+                    .get(&message.sender_uid())
+                    .is_some_and(|region| {
+                        region.scene_id == notify.scene_id && region.point_id == notify.point_id
+                    })
+                {
+                    statue_regions.0.remove(&message.sender_uid());
+                }
+            }
+            &_ => {}
+        }
+    }
+}
+
+pub fn statue_heal_system(
+    mut statue_regions: ResMut<StatueRegions>,
+    mut players: ResMut<Players>,
+    avatars: Query<(Entity, &FightProperties, &OwnerPlayerUID), With<CurrentTeam>>,
+    mut update_property_entity_events: MessageWriter<EntityPropertyUpdateEvent>,
+    message_output: Res<MessageOutput>,
+) {
+    let now = Instant::now();
+    let now_ms = ::common::time_util::unix_timestamp_ms();
+
+    for (uid, region) in statue_regions.0.iter_mut() {
+        if now < region.next_heal_at {
+            continue;
+        }
+        region.next_heal_at = now + HEAL_INTERVAL;
+
+        let Some(player_scene_bin) = players
+            .get_mut(*uid)
+            .and_then(|player_info| player_info.scene_bin.as_mut())
+        else {
+            continue;
+        };
+        if player_scene_bin.my_cur_scene_id != region.scene_id {
+            continue;
+        }
+        let scene_bin = player_scene_bin
+            .world
+            .get_or_insert_default()
+            .scene_map
+            .entry(region.scene_id)
+            .or_default();
+
+        recover_spring_volume(scene_bin, now_ms);
+
+        // dead avatars sit at zero hp, healing them brings them back
+        for (avatar_entity, fight_props, _) in avatars
+            .iter()
+            .filter(|(_, _, owner_uid)| owner_uid.0 == *uid)
+        {
+            let cur_hp = fight_props
+                .get_property(FightPropType::FIGHT_PROP_CUR_HP)
+                .max(0.0);
+            let max_hp = fight_props.get_property(FightPropType::FIGHT_PROP_MAX_HP);
+            let heal = (max_hp - cur_hp).min(scene_bin.cur_spring_volume / SPRING_VOLUME_PER_HP);
+            if heal <= 0.0 {
+                continue;
+            }
+
+            scene_bin.cur_spring_volume -= heal * SPRING_VOLUME_PER_HP;
+            update_property_entity_events.write(EntityPropertyUpdateEvent(
+                avatar_entity,
+                FightPropType::FIGHT_PROP_CUR_HP,
+                cur_hp + heal,
+            ));
+        }
+
+        let cur_volume = scene_bin.cur_spring_volume as i64;
+        if region.synced_volume == Some(cur_volume) {
+            continue;
+        }
+        region.synced_volume = Some(cur_volume);
+        let prop_map = [
+            (PROP_MAX_SPRING_VOLUME, MAX_SPRING_VOLUME as i64),
+            (PROP_CUR_SPRING_VOLUME, cur_volume),
+        ]
+        .into_iter()
+        .map(|(prop_type, value)| {
+            (
+                prop_type,
+                PropValue {
+                    r#type: prop_type,
+                    val: value,
+                    value: Some(prop_value::Value::Ival(value)),
+                },
+            )
+        })
+        .collect();
+        message_output.send(*uid, "PlayerPropNotify", PlayerPropNotify { prop_map });
+    }
+}
+
+// refills the pool for the time passed since the last recovery, a fresh scene starts full
+fn recover_spring_volume(scene_bin: &mut SceneBin, now_ms: u64) {
+    if scene_bin.last_spring_recover_time_ms == 0 {
+        scene_bin.cur_spring_volume = MAX_SPRING_VOLUME;
+    } else {
+        let elapsed_ms = now_ms.saturating_sub(scene_bin.last_spring_recover_time_ms);
+        scene_bin.cur_spring_volume = (scene_bin.cur_spring_volume
+            + elapsed_ms as f32 * SPRING_RECOVER_PER_MS)
+            .min(MAX_SPRING_VOLUME);
+    }
+    scene_bin.last_spring_recover_time_ms = now_ms;
+}
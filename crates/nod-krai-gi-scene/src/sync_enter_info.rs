@@ -10,9 +10,10 @@ use nod_krai_gi_entity::{
 };
 use nod_krai_gi_event::scene::*;
 use nod_krai_gi_message::output::MessageOutput;
+use nod_krai_gi_message::peer::ScenePeerManager;
 use nod_krai_gi_persistence::Players;
 
-use crate::common::{PlayerSceneStates, ScenePeerManager};
+use crate::common::PlayerSceneStates;
 
 pub fn sync_enter_info(
     mut scene_init_events: MessageReader<SceneInitFinishEvent>,
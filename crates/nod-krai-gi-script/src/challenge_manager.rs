@@ -106,6 +106,17 @@ impl ChallengeManager {
         }
     }
 
+    pub fn pause_challenge(&mut self, challenge_index: u32) {
+        if let Some(challenge) = self.active_challenges.get_mut(&challenge_index) {
+            challenge.pause();
+            tracing::debug!(
+                "[ChallengeManager] Paused challenge {} with index {}",
+                challenge.challenge_id,
+                challenge_index
+            );
+        }
+    }
+
     pub fn stop_challenge(
         &mut self,
         challenge_index: u32,
@@ -0,0 +1,124 @@
+use crate::script_load::{GroupLoadState, SceneGroupRuntime};
+use crate::script_lua_vm::LuaRuntime;
+use crate::{SceneGroupRegistry, SharedVariableStore};
+use bevy_ecs::prelude::*;
+use common::gm_util::{Command, GroupAction};
+use nod_krai_gi_data::scene::script_cache::reload_scene_group;
+use nod_krai_gi_entity::common::{GroupId, ProtocolEntityID, ToBeRemovedMarker};
+use nod_krai_gi_event::command::*;
+use nod_krai_gi_event::entity::EntityDisappearEvent;
+use nod_krai_gi_event::lua::SpawnSuiteEntitiesEvent;
+use nod_krai_gi_proto::normal::VisionType;
+use std::collections::HashMap;
+
+// runs before spawn_suite_entities so the old entities are already marked when the
+// suites come back
+pub fn group_command_handler(
+    mut commands: Commands,
+    mut events: MessageReader<GmCommandEvent>,
+    mut registry: NonSendMut<SceneGroupRegistry>,
+    variable_store: Res<SharedVariableStore>,
+    mut lua_vm: ResMut<LuaRuntime>,
+    entities: Query<(Entity, &ProtocolEntityID, &GroupId), Without<ToBeRemovedMarker>>,
+    mut disappear_events: MessageWriter<EntityDisappearEvent>,
+    mut spawn_suite_entities_events: MessageWriter<SpawnSuiteEntitiesEvent>,
+    mut gm_notify_events: MessageWriter<ConsoleChatNotifyEvent>,
+) {
+    for GmCommandEvent(player_uid, command) in events.read() {
+        let Command::Group(GroupAction::Reload { id }) = command else {
+            continue;
+        };
+        let result = reload_live_group(*id, &mut registry, &variable_store, &mut lua_vm);
+
+        // the entities still carry the old script's config, bring the group back from scratch
+        if let (Ok(()), Some(GroupLoadState::Loaded(rt))) = (&result, registry.groups.get(id)) {
+            entities
+                .iter()
+                .filter(|(_, _, group_id)| group_id.0 == *id)
+                .for_each(|(entity, entity_id, _)| {
+                    disappear_events.write(EntityDisappearEvent(
+                        entity_id.0,
+                        VisionType::VisionMiss.into(),
+                    ));
+                    commands.entity(entity).insert(ToBeRemovedMarker);
+                });
+            for suite_id in rt.active_suites.iter().chain(rt.flow_suites.iter()) {
+                spawn_suite_entities_events.write(SpawnSuiteEntitiesEvent {
+                    group_id: *id,
+                    block_id: rt.data.base_info.block_id,
+                    suite_id: *suite_id,
+                });
+            }
+        }
+
+        gm_notify_events.write(ConsoleChatNotifyEvent(
+            *player_uid,
+            match result {
+                Ok(()) => format!("group {} reloaded", id),
+                Err(err) => format!("group {} reload fail: {}", id, err),
+            },
+        ));
+    }
+}
+
+// re-instantiates a loaded group from the script on disk, keeping its variables and suites
+fn reload_live_group(
+    group_id: u32,
+    registry: &mut SceneGroupRegistry,
+    variable_store: &SharedVariableStore,
+    lua_vm: &mut ResMut<LuaRuntime>,
+) -> Result<(), String> {
+    let Some(GroupLoadState::Loaded(old_rt)) = registry.groups.get(&group_id) else {
+        return Err("group is not loaded".to_string());
+    };
+    let scene_id = old_rt.data.base_info.scene_id;
+    let block_id = old_rt.data.base_info.block_id;
+    let uid = old_rt.context.uid;
+    let active_suites = old_rt.active_suites.clone();
+    let flow_suites = old_rt.flow_suites.clone();
+    let suite_history = old_rt.suite_history.clone();
+    let extra_trigger_names = old_rt.extra_trigger_names.clone();
+    let trigger_fire_counts = old_rt.trigger_fire_counts.clone();
+
+    reload_scene_group("./assets/lua", scene_id, group_id)?;
+
+    let Some(mut rt) = SceneGroupRuntime::new(scene_id, block_id, group_id, lua_vm) else {
+        return Err("group runtime init fail".to_string());
+    };
+
+    // new variables start at their defaults, removed ones are dropped
+    let cur_variables = variable_store.0.get_group_variables(group_id);
+    let variables: HashMap<String, i32> = rt
+        .variables
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.clone(),
+                cur_variables.get(name).copied().unwrap_or(*value),
+            )
+        })
+        .collect();
+    variable_store
+        .0
+        .init_group_variables(group_id, variables.clone());
+    rt.variables = variables;
+
+    let suite_count = rt.data.suites.len();
+    let is_valid_suite = |suite_id: &u32| (*suite_id as usize) <= suite_count;
+    rt.active_suites = active_suites.into_iter().filter(is_valid_suite).collect();
+    if rt.active_suites.is_empty() {
+        rt.active_suites.push(1);
+    }
+    rt.flow_suites = flow_suites.into_iter().filter(is_valid_suite).collect();
+    rt.suite_history = suite_history;
+    rt.context.uid = uid;
+    rt.extra_trigger_names = extra_trigger_names
+        .into_iter()
+        .filter(|name| rt.data.triggers.iter().any(|trigger| trigger.name == *name))
+        .collect();
+    rt.recompute_active_triggers();
+    rt.trigger_fire_counts = trigger_fire_counts;
+
+    registry.groups.insert(group_id, GroupLoadState::Loaded(rt));
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use crate::script_group_manager::GroupLoadManager;
+use crate::{ScriptCommandQueue, SharedVariableStore};
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION;
+use nod_krai_gi_data::scene::ScriptCommand;
+use nod_krai_gi_event::lua::ScriptCommandEvent;
+use nod_krai_gi_event::scene::WorldOwnerUID;
+use nod_krai_gi_persistence::Players;
+use nod_krai_gi_proto::server_only::SceneBlockGroupCompBin;
+
+// loads dead groups and logic state values of the world owner into the variable store
+pub fn restore_group_state(
+    players: Res<Players>,
+    world_owner_uid: Res<WorldOwnerUID>,
+    variable_store: Res<SharedVariableStore>,
+) {
+    let Some(world_bin) = players
+        .get(world_owner_uid.0)
+        .and_then(|player_info| player_info.scene_bin.as_ref())
+        .and_then(|player_scene_bin| player_scene_bin.world.as_ref())
+    else {
+        return;
+    };
+
+    for block_group_bin in world_bin
+        .scene_map
+        .values()
+        .filter_map(|scene_bin| scene_bin.block_group_bin.as_ref())
+    {
+        for group_id in block_group_bin.dead_group_list.iter() {
+            variable_store.0.set_group_dead(*group_id, true);
+        }
+        for (group_id, logic_state_bin) in block_group_bin.logic_state_map.iter() {
+            variable_store
+                .0
+                .init_logic_state_values(*group_id, logic_state_bin.value_map.clone());
+        }
+    }
+}
+
+pub fn handle_script_command_group_state_event(
+    mut ev_reader: MessageReader<ScriptCommandEvent>,
+    mut players: ResMut<Players>,
+    world_owner_uid: Res<WorldOwnerUID>,
+    mut group_load_manager: ResMut<GroupLoadManager>,
+    queue: Res<ScriptCommandQueue>,
+) {
+    for event in ev_reader.read() {
+        match &event.command {
+            ScriptCommand::SetGroupLogicStateValue {
+                group_id,
+                name,
+                value,
+            } => {
+                tracing::debug!(
+                    "[Script] [SetGroupLogicStateValue] group={} name={} val={}",
+                    group_id,
+                    name,
+                    value
+                );
+                let Some(block_group_bin) =
+                    get_block_group_bin_mut(&mut players, world_owner_uid.0, *group_id)
+                else {
+                    continue;
+                };
+                block_group_bin
+                    .logic_state_map
+                    .entry(*group_id)
+                    .or_default()
+                    .value_map
+                    .insert(name.clone(), *value);
+            }
+
+            ScriptCommand::SetGroupDead { group_id } => {
+                tracing::debug!("[Script] [SetGroupDead] group={}", group_id);
+                // a dead group is never loaded again, drop it now instead of on the next move
+                if group_load_manager.groups.remove(group_id) {
+                    group_load_manager
+                        .player_groups
+                        .values_mut()
+                        .for_each(|player_group_set| {
+                            player_group_set.remove(group_id);
+                        });
+                    queue.0.push(ScriptCommand::UnloadGroup {
+                        group_id: *group_id,
+                    });
+                }
+                let Some(block_group_bin) =
+                    get_block_group_bin_mut(&mut players, world_owner_uid.0, *group_id)
+                else {
+                    continue;
+                };
+                if !block_group_bin.dead_group_list.contains(group_id) {
+                    block_group_bin.dead_group_list.push(*group_id);
+                }
+            }
+
+            ScriptCommand::RecoverDeadGroup { group_id } => {
+                tracing::debug!("[Script] [RecoverDeadGroup] group={}", group_id);
+                let Some(block_group_bin) =
+                    get_block_group_bin_mut(&mut players, world_owner_uid.0, *group_id)
+                else {
+                    continue;
+                };
+                block_group_bin.dead_group_list.retain(|id| id != group_id);
+            }
+
+            _ => {}
+        }
+    }
+}
+
+fn get_block_group_bin_mut(
+    players: &mut Players,
+    uid: u32,
+    group_id: u32,
+) -> Option<&mut SceneBlockGroupCompBin> {
+    let player_scene_bin = players.get_mut(uid)?.scene_bin.as_mut()?;
+
+    let scene_id = SCENE_GROUP_COLLECTION
+        .get()
+        .and_then(|collection| {
+            collection
+                .get(&group_id)
+                .and_then(|template| template.value().as_ref().map(|t| t.base_info.scene_id))
+        })
+        .unwrap_or(player_scene_bin.my_cur_scene_id);
+
+    Some(
+        player_scene_bin
+            .world
+            .get_or_insert_default()
+            .scene_map
+            .entry(scene_id)
+            .or_default()
+            .block_group_bin
+            .get_or_insert_default(),
+    )
+}
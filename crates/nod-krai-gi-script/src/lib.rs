@@ -1,11 +1,16 @@
 mod challenge_manager;
 mod challenge_notify;
+mod gm_command;
+mod group_state;
+mod monster_tide;
 mod script_entity;
 mod script_group_manager;
+pub mod script_hot_reload;
 mod script_lib;
 mod script_lib_handle;
 mod script_load;
 mod script_lua_vm;
+mod time_axis;
 
 use crate::challenge_manager::{
     challenge_timer_system, ChallengeManager, ChallengeTimer, ChallengeUpdateResult,
@@ -13,43 +18,60 @@ use crate::challenge_manager::{
 use crate::challenge_notify::{
     handle_challenge_finish_event, handle_challenge_progress_event, handle_challenge_start_event,
 };
+use crate::group_state::{handle_script_command_group_state_event, restore_group_state};
+use crate::monster_tide::{
+    handle_monster_kill_tide_event, handle_monster_tide_command, monster_tide_spawn_system,
+    MonsterTideManager,
+};
 use crate::script_entity::{
-    despawn_group_entity, despawn_suite_entities, refresh_group_entity, spawn_group_entity,
-    spawn_suite_entities,
+    clear_pending_group_spawns, despawn_group_entity, despawn_suite_entities, refresh_group_entity,
+    spawn_group_entity, spawn_suite_entities, PendingGroupSpawns,
 };
 use crate::script_group_manager::{
-    handle_player_move_for_group_loading, handle_player_move_for_region_entry, GroupLoadManager,
+    handle_level_tag_change_for_group_loading, handle_player_move_for_group_loading,
+    handle_player_move_for_region_entry, handle_script_command_group_vision_event,
+    GroupLoadManager,
 };
 use crate::script_lib::{
     call_lua_on_be_hurt, call_lua_on_client_execute_req, call_lua_trigger_action, call_lua_trigger_condition,
     BevyScriptLib, GroupVariableStore,
 };
 use crate::script_load::{GroupLoadState, SceneGroupRuntime};
-use crate::script_lua_vm::LuaRuntime;
+use crate::script_lua_vm::{reset_lua_instruction_budget, sync_gadget_lua_scripts, LuaRuntime};
+use crate::time_axis::{handle_time_axis_command, time_axis_tick_system, TimeAxisManager};
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use common::player_cache::cache_get_scene_level;
+use common::time_util;
 use crossbeam_queue::SegQueue;
-use mlua::Function;
+use mlua::{Function, IntoLua, Lua, Value};
+use nod_krai_gi_data::excel::common::EntityType;
+use nod_krai_gi_data::prop_type::FightPropType;
 use nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache;
-use nod_krai_gi_data::scene::{EventType, GadgetState, LuaEvt, ScriptCommand};
+use nod_krai_gi_data::scene::{EventType, GadgetState, LuaEvt, Position, ScriptCommand};
 use nod_krai_gi_entity::common::{
-    BlockId, ConfigId, EntityCounter, GroupId, ProtocolEntityID, Visible,
+    BlockId, ConfigId, EntityCounter, FightProperties, GlobalAbilityValues, GroupId, OneOff,
+    ProtocolEntityID, ToBeRemovedMarker, Visible,
 };
 use nod_krai_gi_entity::gadget::{spawn_gadget_entity, State};
-use nod_krai_gi_entity::monster::spawn_monster_entity;
-use nod_krai_gi_event::entity::{GadgetStateChangeEvent, SetWorktopOptionsEvent};
+use nod_krai_gi_entity::monster::{spawn_monster_entity, MonsterHpLock, MonsterID};
+use nod_krai_gi_event::entity::{
+    EntityDisappearEvent, GadgetStateChangeEvent, SetWorktopOptionsEvent,
+};
 use nod_krai_gi_event::lua::{
     ChallengeFinishEvent, ChallengeProgressEvent, ChallengeStartEvent, DespawnGroupEntityEvent,
-    DespawnSuiteEntitiesEvent, LuaTriggerEvent, MonsterKillEvent, OnBeHurtEvent,
-    OnClientExecuteReqEvent, RefreshGroupEntityEvent, ScriptCommandEvent, SpawnGroupEntityEvent,
-    SpawnSuiteEntitiesEvent,
+    DespawnSuiteEntitiesEvent, LuaSourceTriggerEvent, LuaTriggerEvent, MonsterKillEvent,
+    OnBeHurtEvent, OnClientExecuteReqEvent, RefreshGroupEntityEvent, ScriptCommandEvent,
+    SpawnGroupEntityEvent, SpawnSuiteEntitiesEvent,
 };
 use nod_krai_gi_event::quest::QuestContentProgressEvent;
-use nod_krai_gi_event::scene::{WorldOwnerUID, WorldVersionConfig};
+use nod_krai_gi_event::scene::{
+    LevelTagChangeEvent, ScenePointOperateEvent, ScenePointOperation, SceneTagOperateEvent,
+    WorldOwnerUID, WorldVersionConfig,
+};
 use nod_krai_gi_message::output::MessageOutput;
 use nod_krai_gi_proto::normal::scene_gadget_info::Content;
-use nod_krai_gi_proto::normal::GatherGadgetInfo;
+use nod_krai_gi_proto::normal::{GatherGadgetInfo, ProtEntityType, VisionType};
 use nod_krai_gi_proto::server_only::VectorBin;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -94,19 +116,37 @@ impl Plugin for ScriptPlugin {
             .insert_resource(GroupLoadManager::default())
             .insert_resource(ChallengeManager::new())
             .insert_resource(ChallengeTimer::default())
+            .insert_resource(MonsterTideManager::default())
+            .insert_resource(TimeAxisManager::default())
+            .insert_resource(PendingGroupSpawns::default())
+            .add_systems(Startup, restore_group_state)
+            .add_systems(PreUpdate, reset_lua_instruction_budget)
+            .add_systems(PreUpdate, sync_gadget_lua_scripts)
+            .add_systems(PreUpdate, clear_pending_group_spawns)
             .add_systems(Update, script_command_system)
             .add_systems(Update, handle_script_command_group_event)
             .add_systems(Update, handle_script_command_entity_event)
             .add_systems(Update, handle_script_command_notify_event)
             .add_systems(Update, handle_script_command_challenge_event)
             .add_systems(Update, handle_script_command_quest_event)
+            .add_systems(Update, handle_script_command_scene_point_event)
+            .add_systems(Update, handle_script_command_scene_tag_event)
+            .add_systems(Update, handle_script_command_group_state_event)
+            .add_systems(Update, handle_monster_tide_command)
+            .add_systems(Update, monster_tide_spawn_system)
+            .add_systems(Update, handle_monster_kill_tide_event)
+            .add_systems(Update, handle_time_axis_command)
+            .add_systems(Update, time_axis_tick_system)
             .add_systems(Update, lua_trigger_event_system)
             .add_systems(Update, gadget_lua_on_client_execute_req)
             .add_systems(Update, gadget_lua_on_be_hurt)
             .add_systems(Update, set_worktop_options_event)
             .add_systems(Update, set_gadget_state_event)
+            .add_systems(Update, set_entity_hp_event)
             .add_systems(Update, handle_player_move_for_group_loading)
             .add_systems(Update, handle_player_move_for_region_entry)
+            .add_systems(Update, handle_script_command_group_vision_event)
+            .add_systems(Update, handle_level_tag_change_for_group_loading)
             .add_systems(Update, spawn_group_entity)
             .add_systems(Update, despawn_group_entity)
             .add_systems(Update, spawn_suite_entities)
@@ -119,7 +159,11 @@ impl Plugin for ScriptPlugin {
             .add_systems(Update, handle_monster_kill_challenge_event)
             .add_systems(Update, handle_monster_kill_quest_event)
             .add_systems(Update, handle_gadget_state_change_quest_event)
-            .add_systems(Update, handle_gadget_interact_quest_event);
+            .add_systems(Update, handle_gadget_interact_quest_event)
+            .add_systems(
+                Update,
+                gm_command::group_command_handler.before(spawn_suite_entities),
+            );
     }
 }
 
@@ -140,6 +184,8 @@ pub fn handle_script_command_entity_event(
     world_version_config: Res<WorldVersionConfig>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
     registry: NonSendMut<SceneGroupRegistry>,
+    entities: Query<(Entity, &ProtocolEntityID, &GroupId, &ConfigId), Without<ToBeRemovedMarker>>,
+    mut disappear_events: MessageWriter<EntityDisappearEvent>,
 ) {
     let scene_group_collection = std::sync::Arc::clone(
         nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION
@@ -155,6 +201,9 @@ pub fn handle_script_command_entity_event(
             ScriptCommand::CreateGadget {
                 group_id,
                 config_id,
+                pos,
+                rot,
+                server_global_values,
             } => {
                 tracing::debug!(
                     "[Script] [CreateGadget] group={} config={}",
@@ -182,6 +231,15 @@ pub fn handle_script_command_entity_event(
                     continue;
                 };
 
+                if get_group_entity_state_cache().is_gadget_one_off(
+                    world_owner_uid.0,
+                    *group_id,
+                    gadget.config_id,
+                    time_util::unix_timestamp() as u32,
+                ) {
+                    continue;
+                }
+
                 let mut gadget_id = gadget.gadget_id;
                 let mut is_interactive = false;
                 let mut gadget_content = None;
@@ -207,16 +265,8 @@ pub fn handle_script_command_entity_event(
                     world_version_config.protocol_version.clone(),
                     &mut commands,
                     &mut entity_counter,
-                    VectorBin {
-                        x: gadget.pos.x,
-                        y: gadget.pos.y,
-                        z: gadget.pos.z,
-                    },
-                    VectorBin {
-                        x: gadget.rot.x,
-                        y: gadget.rot.y,
-                        z: gadget.rot.z,
-                    },
+                    to_vector_bin(*pos, &gadget.pos),
+                    to_vector_bin(*rot, &gadget.rot),
                     gadget_id,
                     gadget.level.unwrap_or(90),
                     gadget.is_enable_interact.unwrap_or(is_interactive),
@@ -224,6 +274,7 @@ pub fn handle_script_command_entity_event(
                     gadget.drop_tag.clone(),
                     gadget.chest_drop_id.unwrap_or(0),
                     gadget.state.unwrap_or(GadgetState::Default) as u32,
+                    to_global_ability_values(server_global_values),
                 ) else {
                     continue;
                 };
@@ -234,6 +285,9 @@ pub fn handle_script_command_entity_event(
                     .insert(GroupId(*group_id))
                     .insert(ConfigId(gadget.config_id))
                     .insert(Visible);
+                if gadget.is_one_off.unwrap_or(false) {
+                    commands.entity(gadget_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_gadget_spawn(
                     world_owner_uid.0,
@@ -268,6 +322,9 @@ pub fn handle_script_command_entity_event(
             ScriptCommand::CreateMonster {
                 group_id,
                 config_id,
+                pos,
+                rot,
+                server_global_values,
             } => {
                 tracing::debug!(
                     "[Script] [CreateMonster] group={} config={}",
@@ -295,6 +352,15 @@ pub fn handle_script_command_entity_event(
                     continue;
                 };
 
+                if get_group_entity_state_cache().is_monster_one_off(
+                    world_owner_uid.0,
+                    *group_id,
+                    monster.config_id,
+                    time_util::unix_timestamp() as u32,
+                ) {
+                    continue;
+                }
+
                 let show_level = cache_get_scene_level(
                     world_owner_uid.0,
                     scene_group_template.base_info.scene_id,
@@ -308,16 +374,8 @@ pub fn handle_script_command_entity_event(
                     world_version_config.protocol_version.clone(),
                     &mut commands,
                     &mut entity_counter,
-                    VectorBin {
-                        x: monster.pos.x,
-                        y: monster.pos.y,
-                        z: monster.pos.z,
-                    },
-                    VectorBin {
-                        x: monster.rot.x,
-                        y: monster.rot.y,
-                        z: monster.rot.z,
-                    },
+                    to_vector_bin(*pos, &monster.pos),
+                    to_vector_bin(*rot, &monster.rot),
                     monster.monster_id,
                     level,
                     monster.pose_id.unwrap_or(0),
@@ -325,6 +383,7 @@ pub fn handle_script_command_entity_event(
                     monster.special_name_id.unwrap_or(0),
                     monster.drop_tag.clone(),
                     monster.chest_drop_id.unwrap_or(0),
+                    to_global_ability_values(server_global_values),
                 ) else {
                     continue;
                 };
@@ -335,6 +394,9 @@ pub fn handle_script_command_entity_event(
                     .insert(GroupId(*group_id))
                     .insert(ConfigId(monster.config_id))
                     .insert(Visible);
+                if monster.is_one_off.unwrap_or(false) {
+                    commands.entity(monster_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_monster_spawn(
                     world_owner_uid.0,
@@ -376,32 +438,50 @@ pub fn handle_script_command_entity_event(
                 );
             }
 
-            ScriptCommand::KillGroupEntity {
+            ScriptCommand::RemoveEntityByConfigId {
                 group_id,
-                kill_policy,
+                entity_type,
+                config_id,
             } => {
                 tracing::debug!(
-                    "[Script] [KillGroupEntity] group={} policy={}",
+                    "[Script] [RemoveEntityByConfigId] group={} type={} config={}",
                     group_id,
-                    kill_policy
+                    entity_type,
+                    config_id
                 );
-                // KillPolicy: 0=none, 1=all, 2=monster, 3=gadget
+                let remove_monster = *entity_type == EntityType::Monster as u32;
+                let cache = get_group_entity_state_cache();
+                for (entity, entity_id, e_group_id, e_config_id) in entities.iter() {
+                    let is_monster = entity_id.0 >> world_version_config.ty_value
+                        == ProtEntityType::ProtEntityMonster as u32;
+                    if e_group_id.0 != *group_id
+                        || e_config_id.0 != *config_id
+                        || is_monster != remove_monster
+                    {
+                        continue;
+                    }
+                    // removed, not killed: no die triggers and the one-off state is untouched
+                    disappear_events
+                        .write(EntityDisappearEvent(entity_id.0, VisionType::VisionRemove));
+                    commands.entity(entity).insert(ToBeRemovedMarker);
+                    if is_monster {
+                        cache.on_monster_remove(world_owner_uid.0, *group_id, *config_id);
+                    } else {
+                        cache.on_gadget_remove(world_owner_uid.0, *group_id, *config_id);
+                    }
+                }
             }
 
-            ScriptCommand::AutoMonsterTide {
+            ScriptCommand::KillGroupEntity {
                 group_id,
-                source_id,
-                orders_config_id: _,
-                tide_count,
-                scene_limit,
+                kill_policy,
             } => {
                 tracing::debug!(
-                    "[Script] [AutoMonsterTide] group={} source={} tide={} limit={}",
+                    "[Script] [KillGroupEntity] group={} policy={}",
                     group_id,
-                    source_id,
-                    tide_count,
-                    scene_limit
+                    kill_policy
                 );
+                // KillPolicy: 0=none, 1=all, 2=monster, 3=gadget
             }
 
             ScriptCommand::SetEntityServerGlobalValue {
@@ -424,6 +504,20 @@ pub fn handle_script_command_entity_event(
     }
 }
 
+fn to_vector_bin(value: Option<(f32, f32, f32)>, default: &Position) -> VectorBin {
+    let (x, y, z) = value.unwrap_or((default.x, default.y, default.z));
+    VectorBin { x, y, z }
+}
+
+fn to_global_ability_values(values: &[(String, f32)]) -> GlobalAbilityValues {
+    GlobalAbilityValues(
+        values
+            .iter()
+            .map(|(key, value)| (key.as_str().into(), *value))
+            .collect(),
+    )
+}
+
 pub fn handle_script_command_notify_event(
     mut ev_reader: MessageReader<ScriptCommandEvent>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
@@ -634,27 +728,6 @@ pub fn handle_script_command_notify_event(
                 );
             }
 
-            ScriptCommand::InitTimeAxis {
-                identifier,
-                delays,
-                should_loop,
-            } => {
-                tracing::debug!(
-                    "[Script] [InitTimeAxis] id={} delays={:?} loop={}",
-                    identifier,
-                    delays,
-                    should_loop
-                );
-            }
-
-            ScriptCommand::EndTimeAxis { identifier } => {
-                tracing::debug!("[Script] [EndTimeAxis] id={}", identifier);
-            }
-
-            ScriptCommand::EndAllTimeAxis => {
-                tracing::debug!("[Script] [EndAllTimeAxis]");
-            }
-
             _ => {}
         }
     }
@@ -664,6 +737,7 @@ fn handle_script_command_group_event(
     mut ev_reader: MessageReader<ScriptCommandEvent>,
     mut registry: NonSendMut<SceneGroupRegistry>,
     variable_store: Res<SharedVariableStore>,
+    world_owner_uid: Res<WorldOwnerUID>,
     mut lua_vm: ResMut<LuaRuntime>,
     mut spawn_group_entity_event: MessageWriter<SpawnGroupEntityEvent>,
     mut despawn_group_entity_event: MessageWriter<DespawnGroupEntityEvent>,
@@ -725,6 +799,7 @@ fn handle_script_command_group_event(
                     despawn_suite_entities_event.write(DespawnSuiteEntitiesEvent {
                         group_id: *group_id,
                         suite_id: *suite_id,
+                        kill: false,
                     });
 
                     rt.active_suites.retain(|s| *s != *suite_id);
@@ -752,7 +827,9 @@ fn handle_script_command_group_event(
                         SceneGroupRuntime::new(*scene_id, *block_id, *group_id, &mut lua_vm);
 
                     match runtime {
-                        Some(rt) => {
+                        Some(mut rt) => {
+                            // the entity state cache read by ScriptLib is keyed by the world owner
+                            rt.context.uid = world_owner_uid.0;
                             let vars = rt.variables.clone();
                             variable_store.0.init_group_variables(*group_id, vars);
                             registry
@@ -833,6 +910,10 @@ fn handle_script_command_group_event(
                     });
 
                     rt.active_suites = vec![*suite_id];
+                    rt.flow_suites.clear();
+                    rt.suite_history.clear();
+                    rt.extra_trigger_names.clear();
+                    rt.trigger_fire_counts.clear();
                     rt.recompute_active_triggers();
 
                     let initial_vars: HashMap<String, i32> = rt
@@ -855,11 +936,213 @@ fn handle_script_command_group_event(
                     suite_id
                 );
                 if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(group_id) {
-                    rt.active_suites = vec![*suite_id];
+                    rt.push_suite_history();
+                    let flow_suites = rt.flow_suites.clone();
+                    let suite_diff = rt.switch_suites(vec![*suite_id], flow_suites);
+                    write_suite_switch_events(
+                        *group_id,
+                        rt.data.base_info.block_id,
+                        suite_diff,
+                        false,
+                        &mut spawn_suite_entities_event,
+                        &mut despawn_suite_entities_event,
+                    );
+                }
+            }
+
+            ScriptCommand::GoBackGroupSuite { group_id } => {
+                tracing::debug!("[Script] [GoBackGroupSuite] group={}", group_id);
+                if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(group_id) {
+                    let Some(snapshot) = rt.suite_history.pop() else {
+                        tracing::debug!(
+                            "[Script] [GoBackGroupSuite] group {} has no suite history",
+                            group_id
+                        );
+                        continue;
+                    };
+                    let suite_diff = rt.switch_suites(snapshot.active_suites, snapshot.flow_suites);
+                    write_suite_switch_events(
+                        *group_id,
+                        rt.data.base_info.block_id,
+                        suite_diff,
+                        false,
+                        &mut spawn_suite_entities_event,
+                        &mut despawn_suite_entities_event,
+                    );
+                }
+            }
+
+            ScriptCommand::CreateGroupTrigger {
+                group_id,
+                trigger_name,
+            } => {
+                tracing::debug!(
+                    "[Script] [CreateGroupTrigger] group={} trigger={}",
+                    group_id,
+                    trigger_name
+                );
+                if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(group_id) {
+                    let exists = rt
+                        .data
+                        .triggers
+                        .iter()
+                        .any(|trigger| trigger.name == *trigger_name);
+                    if !exists {
+                        tracing::debug!(
+                            "[Script] [CreateGroupTrigger] group {} has no trigger {}",
+                            group_id,
+                            trigger_name
+                        );
+                        continue;
+                    }
+                    if !rt.extra_trigger_names.contains(trigger_name) {
+                        rt.extra_trigger_names.push(trigger_name.clone());
+                    }
+                    rt.trigger_fire_counts.remove(trigger_name);
                     rt.recompute_active_triggers();
                 }
             }
 
+            ScriptCommand::KillExtraGroupSuite { group_id, suite_id } => {
+                tracing::debug!(
+                    "[Script] [KillExtraGroupSuite] group={} suite={}",
+                    group_id,
+                    suite_id
+                );
+                if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(group_id) {
+                    if !rt.active_suites.contains(suite_id) {
+                        continue;
+                    }
+                    let active_suites = rt
+                        .active_suites
+                        .iter()
+                        .filter(|s| **s != *suite_id)
+                        .copied()
+                        .collect();
+                    let flow_suites = rt.flow_suites.clone();
+                    let suite_diff = rt.switch_suites(active_suites, flow_suites);
+                    write_suite_switch_events(
+                        *group_id,
+                        rt.data.base_info.block_id,
+                        suite_diff,
+                        true,
+                        &mut spawn_suite_entities_event,
+                        &mut despawn_suite_entities_event,
+                    );
+                }
+            }
+
+            ScriptCommand::SetFlowSuite { group_id, suite_id }
+            | ScriptCommand::GoToFlowSuite { group_id, suite_id } => {
+                tracing::debug!(
+                    "[Script] [GoToFlowSuite] group={} suite={}",
+                    group_id,
+                    suite_id
+                );
+                if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(group_id) {
+                    // only GoToFlowSuite can be undone with GoBackGroupSuite
+                    if matches!(event.command, ScriptCommand::GoToFlowSuite { .. }) {
+                        rt.push_suite_history();
+                    }
+                    let active_suites = rt.active_suites.clone();
+                    let suite_diff = rt.switch_suites(active_suites, vec![*suite_id]);
+                    write_suite_switch_events(
+                        *group_id,
+                        rt.data.base_info.block_id,
+                        suite_diff,
+                        false,
+                        &mut spawn_suite_entities_event,
+                        &mut despawn_suite_entities_event,
+                    );
+                }
+            }
+
+            ScriptCommand::AddExtraFlowSuite {
+                group_id,
+                suite_id,
+                policy,
+            } => {
+                tracing::debug!(
+                    "[Script] [AddExtraFlowSuite] group={} suite={} policy={}",
+                    group_id,
+                    suite_id,
+                    policy
+                );
+                // FlowSuiteOperatePolicy: 0=default, 1=complete
+                if *policy > 1 {
+                    tracing::debug!("[Script] [AddExtraFlowSuite] unsupported policy {}", policy);
+                    continue;
+                }
+                if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(group_id) {
+                    if rt.flow_suites.contains(suite_id) {
+                        // complete brings back whatever of the suite is gone, live ones are kept
+                        if *policy == 1 {
+                            spawn_suite_entities_event.write(SpawnSuiteEntitiesEvent {
+                                group_id: *group_id,
+                                block_id: rt.data.base_info.block_id,
+                                suite_id: *suite_id,
+                            });
+                        }
+                        continue;
+                    }
+                    let active_suites = rt.active_suites.clone();
+                    let mut flow_suites = rt.flow_suites.clone();
+                    flow_suites.push(*suite_id);
+                    let suite_diff = rt.switch_suites(active_suites, flow_suites);
+                    write_suite_switch_events(
+                        *group_id,
+                        rt.data.base_info.block_id,
+                        suite_diff,
+                        false,
+                        &mut spawn_suite_entities_event,
+                        &mut despawn_suite_entities_event,
+                    );
+                }
+            }
+
+            ScriptCommand::RemoveExtraFlowSuite {
+                group_id,
+                suite_id,
+                policy,
+            }
+            | ScriptCommand::KillExtraFlowSuite {
+                group_id,
+                suite_id,
+                policy,
+            } => {
+                // FlowSuiteOperatePolicy: 0=default, 1=complete
+                let kill = matches!(event.command, ScriptCommand::KillExtraFlowSuite { .. })
+                    || *policy == 1;
+                tracing::debug!(
+                    "[Script] [RemoveExtraFlowSuite] group={} suite={} policy={} kill={}",
+                    group_id,
+                    suite_id,
+                    policy,
+                    kill
+                );
+                if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(group_id) {
+                    if !rt.flow_suites.contains(suite_id) {
+                        continue;
+                    }
+                    let active_suites = rt.active_suites.clone();
+                    let flow_suites = rt
+                        .flow_suites
+                        .iter()
+                        .filter(|s| **s != *suite_id)
+                        .copied()
+                        .collect();
+                    let suite_diff = rt.switch_suites(active_suites, flow_suites);
+                    write_suite_switch_events(
+                        *group_id,
+                        rt.data.base_info.block_id,
+                        suite_diff,
+                        kill,
+                        &mut spawn_suite_entities_event,
+                        &mut despawn_suite_entities_event,
+                    );
+                }
+            }
+
             ScriptCommand::NotifyGroupLua {
                 group_id,
                 event_type,
@@ -921,6 +1204,30 @@ fn handle_script_command_group_event(
     }
 }
 
+fn write_suite_switch_events(
+    group_id: u32,
+    block_id: u32,
+    (removed_suites, added_suites): (Vec<u32>, Vec<u32>),
+    kill: bool,
+    spawn_suite_entities_event: &mut MessageWriter<SpawnSuiteEntitiesEvent>,
+    despawn_suite_entities_event: &mut MessageWriter<DespawnSuiteEntitiesEvent>,
+) {
+    for suite_id in removed_suites {
+        despawn_suite_entities_event.write(DespawnSuiteEntitiesEvent {
+            group_id,
+            suite_id,
+            kill,
+        });
+    }
+    for suite_id in added_suites {
+        spawn_suite_entities_event.write(SpawnSuiteEntitiesEvent {
+            group_id,
+            block_id,
+            suite_id,
+        });
+    }
+}
+
 fn handle_script_command_challenge_event(
     mut ev_reader: MessageReader<ScriptCommandEvent>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
@@ -977,6 +1284,18 @@ fn handle_script_command_challenge_event(
                 challenge_start_events.write(start_event);
             }
 
+            ScriptCommand::PauseChallenge {
+                group_id,
+                challenge_index,
+            } => {
+                tracing::debug!(
+                    "[Script] [PauseChallenge] group={} index={}",
+                    group_id,
+                    challenge_index
+                );
+                challenge_manager.pause_challenge(*challenge_index);
+            }
+
             ScriptCommand::StopChallenge {
                 group_id,
                 challenge_index,
@@ -1053,6 +1372,94 @@ fn handle_script_command_quest_event(
     }
 }
 
+fn handle_script_command_scene_point_event(
+    mut ev_reader: MessageReader<ScriptCommandEvent>,
+    world_owner_uid: Res<WorldOwnerUID>,
+    mut scene_point_events: MessageWriter<ScenePointOperateEvent>,
+) {
+    for event in ev_reader.read() {
+        let (scene_id, point_id, operation) = match &event.command {
+            ScriptCommand::UnlockScenePoint { scene_id, point_id } => {
+                (*scene_id, *point_id, ScenePointOperation::Unlock)
+            }
+            ScriptCommand::LockScenePoint { scene_id, point_id } => {
+                (*scene_id, *point_id, ScenePointOperation::Lock)
+            }
+            ScriptCommand::HideScenePoint { scene_id, point_id } => {
+                (*scene_id, *point_id, ScenePointOperation::Hide)
+            }
+            ScriptCommand::UnhideScenePoint { scene_id, point_id } => {
+                (*scene_id, *point_id, ScenePointOperation::Unhide)
+            }
+            _ => continue,
+        };
+        tracing::debug!(
+            "[Script] [{:?}ScenePoint] scene={} point={}",
+            operation,
+            scene_id,
+            point_id
+        );
+        scene_point_events.write(ScenePointOperateEvent {
+            uid: world_owner_uid.0,
+            scene_id,
+            point_id,
+            operation,
+        });
+    }
+}
+
+fn handle_script_command_scene_tag_event(
+    mut ev_reader: MessageReader<ScriptCommandEvent>,
+    world_owner_uid: Res<WorldOwnerUID>,
+    mut scene_tag_events: MessageWriter<SceneTagOperateEvent>,
+    mut level_tag_events: MessageWriter<LevelTagChangeEvent>,
+) {
+    for event in ev_reader.read() {
+        match &event.command {
+            ScriptCommand::AddSceneTag {
+                scene_id,
+                scene_tag_id,
+            } => {
+                tracing::debug!(
+                    "[Script] [AddSceneTag] scene={} tag={}",
+                    scene_id,
+                    scene_tag_id
+                );
+                scene_tag_events.write(SceneTagOperateEvent {
+                    uid: world_owner_uid.0,
+                    scene_id: *scene_id,
+                    scene_tag_id: *scene_tag_id,
+                    is_add: true,
+                });
+            }
+            ScriptCommand::DelSceneTag {
+                scene_id,
+                scene_tag_id,
+            } => {
+                tracing::debug!(
+                    "[Script] [DelSceneTag] scene={} tag={}",
+                    scene_id,
+                    scene_tag_id
+                );
+                scene_tag_events.write(SceneTagOperateEvent {
+                    uid: world_owner_uid.0,
+                    scene_id: *scene_id,
+                    scene_tag_id: *scene_tag_id,
+                    is_add: false,
+                });
+            }
+            ScriptCommand::ChangeToTargetLevelTag { level_tag_id } => {
+                tracing::debug!("[Script] [ChangeToTargetLevelTag] tag={}", level_tag_id);
+                level_tag_events.write(LevelTagChangeEvent {
+                    uid: world_owner_uid.0,
+                    level_tag_id: *level_tag_id,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
 fn handle_monster_kill_challenge_event(
     mut ev_reader: MessageReader<MonsterKillEvent>,
     mut challenge_manager: ResMut<ChallengeManager>,
@@ -1143,51 +1550,85 @@ fn handle_monster_kill_challenge_event(
 
 fn lua_trigger_event_system(
     mut ev_reader: MessageReader<LuaTriggerEvent>,
+    mut source_ev_reader: MessageReader<LuaSourceTriggerEvent>,
     mut registry: NonSendMut<SceneGroupRegistry>,
+    lua_vm: Res<LuaRuntime>,
 ) {
-    use std::collections::hash_map::Entry;
-
     for event in ev_reader.read() {
-        let group_id = event.group_id;
+        let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(&event.group_id) else {
+            continue;
+        };
+        fire_group_triggers(&lua_vm.lua, rt, &event.event_type, event.evt, None);
+    }
 
-        match registry.groups.entry(group_id) {
-            Entry::Vacant(_v) => {
-                //...
-            }
+    for event in source_ev_reader.read() {
+        let Some(GroupLoadState::Loaded(rt)) = registry.groups.get_mut(&event.group_id) else {
+            continue;
+        };
+        fire_group_triggers(
+            &lua_vm.lua,
+            rt,
+            &event.event_type,
+            event.evt,
+            Some(&event.source_name),
+        );
+    }
+}
 
-            Entry::Occupied(mut o) => match o.get_mut() {
-                GroupLoadState::Loading => continue,
-                GroupLoadState::Failed => continue,
+// runs the active triggers of a group listening to the event, fires are counted against trigger_count
+fn fire_group_triggers(
+    lua: &Lua,
+    rt: &mut SceneGroupRuntime,
+    event_type: &EventType,
+    evt: LuaEvt,
+    source_name: Option<&str>,
+) {
+    let Some(all_triggers) = rt.triggers_by_event.get(event_type) else {
+        return;
+    };
+
+    tracing::debug!("[LuaTrigger] all_triggers:{}", all_triggers.len());
+
+    let (Ok(Value::Table(context)), Ok(Value::Table(evt))) =
+        (rt.context.into_lua(lua), evt.into_lua(lua))
+    else {
+        return;
+    };
+    if let Some(source_name) = source_name {
+        let _ = evt.set("source_name", source_name);
+    }
 
-                GroupLoadState::Loaded(rt) => {
-                    let Some(all_triggers) = rt.triggers_by_event.get(&event.event_type) else {
-                        continue;
-                    };
+    for trig in all_triggers {
+        if !rt.active_trigger_names.contains(&trig.name) {
+            continue;
+        }
+        if let Some(source_name) = source_name
+            && !trig.source.is_empty()
+            && trig.source != source_name
+        {
+            continue;
+        }
 
-                    tracing::debug!("[LuaTrigger] all_triggers:{}", all_triggers.len());
+        let fired = rt.trigger_fire_counts.get(&trig.name).copied().unwrap_or(0);
+        if trig.trigger_count > 0 && fired >= trig.trigger_count {
+            continue;
+        }
 
-                    for trig in all_triggers {
-                        if !rt.active_trigger_names.contains(&trig.name) {
-                            continue;
-                        }
+        tracing::debug!("[LuaTrigger] trigger event:{:#?}", trig.name);
 
-                        tracing::debug!("[LuaTrigger] trigger event:{:#?}", trig.name);
-
-                        let should_execute = match &trig.condition {
-                            Some(cond) => call_lua_trigger_condition(cond, rt.context, event.evt)
-                                .unwrap_or(false),
-                            None => true,
-                        };
-                        if should_execute {
-                            if let Some(action) = &trig.action {
-                                let _ = call_lua_trigger_action(action, rt.context, event.evt);
-                            }
-                        }
-                    }
-                }
+        let _ = context.set("trigger_count", fired);
+        let should_execute = match &trig.condition {
+            Some(cond) => call_lua_trigger_condition(cond, &context, &evt).unwrap_or(false),
+            None => true,
+        };
+        if !should_execute {
+            continue;
+        }
 
-                GroupLoadState::Unloaded => unreachable!(),
-            },
+        rt.trigger_fire_counts.insert(trig.name.clone(), fired + 1);
+        let _ = context.set("trigger_count", fired + 1);
+        if let Some(action) = &trig.action {
+            let _ = call_lua_trigger_action(action, &context, &evt);
         }
     }
 }
@@ -1240,6 +1681,7 @@ fn gadget_lua_on_be_hurt(
                     event.element_type,
                     event.strike_type,
                     event.is_host,
+                    event.reaction_type,
                 );
             }
         }
@@ -1393,6 +1835,94 @@ fn set_gadget_state_event(
     }
 }
 
+fn set_entity_hp_event(
+    mut commands: Commands,
+    mut ev_reader: MessageReader<ScriptCommandEvent>,
+    mut entities: Query<(
+        Entity,
+        &GroupId,
+        &ConfigId,
+        &mut FightProperties,
+        Has<MonsterID>,
+    )>,
+    hp_locks: Query<&MonsterHpLock>,
+) {
+    for event in ev_reader.read() {
+        match &event.command {
+            ScriptCommand::SetMonsterHp {
+                group_id,
+                config_id,
+                hp_percent,
+            }
+            | ScriptCommand::SetGadgetHp {
+                group_id,
+                config_id,
+                hp_percent,
+            } => {
+                let is_monster = matches!(event.command, ScriptCommand::SetMonsterHp { .. });
+                tracing::debug!(
+                    "[Script] [SetEntityHp] group={} config={} hp_percent={} monster={}",
+                    group_id,
+                    config_id,
+                    hp_percent,
+                    is_monster
+                );
+                for (entity, e_group_id, e_config_id, mut props, has_monster) in entities.iter_mut()
+                {
+                    if e_group_id.0 != *group_id || e_config_id.0 != *config_id {
+                        continue;
+                    }
+                    if is_monster != has_monster {
+                        continue;
+                    }
+                    let max_hp = props.get_property(FightPropType::FIGHT_PROP_MAX_HP);
+                    let hp = max_hp * hp_percent.clamp(0.0, 100.0) / 100.0;
+                    props.set_property(FightPropType::FIGHT_PROP_CUR_HP, hp);
+                    // a locked monster stays locked at the hp the script just set
+                    if hp_locks.contains(entity) {
+                        commands.entity(entity).insert(MonsterHpLock { min_hp: hp });
+                    }
+                }
+            }
+
+            ScriptCommand::LockMonsterHp {
+                group_id,
+                config_id,
+            } => {
+                tracing::debug!(
+                    "[Script] [LockMonsterHp] group={} config={}",
+                    group_id,
+                    config_id
+                );
+                for (entity, e_group_id, e_config_id, props, has_monster) in entities.iter() {
+                    if e_group_id.0 == *group_id && e_config_id.0 == *config_id && has_monster {
+                        commands.entity(entity).insert(MonsterHpLock {
+                            min_hp: props.get_property(FightPropType::FIGHT_PROP_CUR_HP),
+                        });
+                    }
+                }
+            }
+
+            ScriptCommand::UnlockMonsterHp {
+                group_id,
+                config_id,
+            } => {
+                tracing::debug!(
+                    "[Script] [UnlockMonsterHp] group={} config={}",
+                    group_id,
+                    config_id
+                );
+                for (entity, e_group_id, e_config_id, _, _) in entities.iter() {
+                    if e_group_id.0 == *group_id && e_config_id.0 == *config_id {
+                        commands.entity(entity).remove::<MonsterHpLock>();
+                    }
+                }
+            }
+            _ => {}
+        };
+    }
+}
+
 fn handle_monster_kill_quest_event(
     mut ev_reader: MessageReader<MonsterKillEvent>,
     world_owner_uid: Res<WorldOwnerUID>,
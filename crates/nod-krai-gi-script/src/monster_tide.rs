@@ -0,0 +1,597 @@
+use crate::script_load::GroupLoadState;
+use crate::SceneGroupRegistry;
+use bevy_ecs::prelude::*;
+use common::player_cache::cache_get_scene_level;
+use nod_krai_gi_data::custom::MonsterPool;
+use nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache;
+use nod_krai_gi_data::scene::scene_group_template::{Monster, SceneGroupTemplate};
+use nod_krai_gi_data::scene::{EventType, LuaEvt, Position, ScriptCommand};
+use nod_krai_gi_entity::common::{
+    BlockId, ConfigId, EntityCounter, GlobalAbilityValues, GroupId, ProtocolEntityID,
+    ToBeRemovedMarker, Visible,
+};
+use nod_krai_gi_entity::monster::spawn_monster_entity;
+use nod_krai_gi_event::entity::EntityDisappearEvent;
+use nod_krai_gi_event::lua::{LuaTriggerEvent, MonsterKillEvent, ScriptCommandEvent};
+use nod_krai_gi_event::scene::{WorldOwnerUID, WorldVersionConfig};
+use nod_krai_gi_proto::normal::VisionType;
+use nod_krai_gi_proto::server_only::VectorBin;
+use std::collections::HashMap;
+
+// pool monsters have no config in the group script, give them ids far away from real ones
+const POOL_MONSTER_CONFIG_ID_BASE: u32 = 10_000_000;
+const POOL_MONSTER_CONFIG_ID_STRIDE: u32 = 100_000;
+
+enum MonsterTideSource {
+    // AutoMonsterTide, spawns the group's own monsters in order
+    Orders {
+        config_id_list: Vec<u32>,
+        next_index: usize,
+    },
+    // AutoPoolMonsterTide, spawns random monsters from MonsterPool at the given points
+    Pool {
+        pool_id_list: Vec<u32>,
+        point_config_id_list: Vec<u32>,
+        tag: String,
+    },
+}
+
+struct MonsterTide {
+    source: MonsterTideSource,
+    total_count: u32,
+    min_count: u32,
+    max_count: u32,
+    spawned_count: u32,
+    killed_count: u32,
+    alive_config_ids: Vec<u32>,
+    paused: bool,
+    // set by CreateMonstersFromMonsterPool, fills up to max_count without waiting for min_count
+    force_fill: bool,
+}
+
+impl MonsterTide {
+    fn is_pool(&self) -> bool {
+        matches!(self.source, MonsterTideSource::Pool { .. })
+    }
+
+    // a total_count of 0 keeps the tide going until the script ends it
+    fn is_endless(&self) -> bool {
+        self.total_count == 0
+    }
+
+    fn is_over(&self) -> bool {
+        !self.is_endless() && self.killed_count >= self.total_count
+    }
+
+    // how many monsters should be spawned right now
+    fn fill_count(&self) -> u32 {
+        let alive = self.alive_config_ids.len() as u32;
+        let remaining = if self.is_endless() {
+            u32::MAX
+        } else {
+            self.total_count.saturating_sub(self.spawned_count)
+        };
+        if remaining == 0 || alive >= self.max_count {
+            return 0;
+        }
+        // orders tides keep topping up, pool tides wait until the wave drops below min_count
+        if self.is_pool()
+            && !self.force_fill
+            && alive >= self.min_count.max(1)
+            && self.spawned_count > 0
+        {
+            return 0;
+        }
+        (self.max_count - alive).min(remaining)
+    }
+
+    fn event_types(&self) -> (EventType, EventType, EventType) {
+        if self.is_pool() {
+            (
+                EventType::EventPoolMonsterTideCreate,
+                EventType::EventPoolMonsterTideDie,
+                EventType::EventPoolMonsterTideOver,
+            )
+        } else {
+            (
+                EventType::EventMonsterTideCreate,
+                EventType::EventMonsterTideDie,
+                EventType::EventMonsterTideOver,
+            )
+        }
+    }
+}
+
+// key: (group_id, source_id)
+#[derive(Resource, Default)]
+pub struct MonsterTideManager {
+    tides: HashMap<(u32, u32), MonsterTide>,
+}
+
+pub fn handle_monster_tide_command(
+    mut ev_reader: MessageReader<ScriptCommandEvent>,
+    mut commands: Commands,
+    mut manager: ResMut<MonsterTideManager>,
+    entities: Query<(Entity, &ProtocolEntityID, &GroupId, &ConfigId)>,
+    mut disappear_events: MessageWriter<EntityDisappearEvent>,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+    world_owner_uid: Res<WorldOwnerUID>,
+) {
+    for event in ev_reader.read() {
+        match &event.command {
+            ScriptCommand::AutoMonsterTide {
+                group_id,
+                source_id,
+                orders_config_id,
+                tide_count,
+                scene_limit,
+            } => {
+                tracing::debug!(
+                    "[Script] [AutoMonsterTide] group={} source={} tide={} limit={}",
+                    group_id,
+                    source_id,
+                    tide_count,
+                    scene_limit
+                );
+                manager.tides.insert(
+                    (*group_id, *source_id),
+                    MonsterTide {
+                        source: MonsterTideSource::Orders {
+                            config_id_list: orders_config_id.clone(),
+                            next_index: 0,
+                        },
+                        total_count: *tide_count,
+                        min_count: *scene_limit,
+                        max_count: (*scene_limit).max(1),
+                        spawned_count: 0,
+                        killed_count: 0,
+                        alive_config_ids: vec![],
+                        paused: false,
+                        force_fill: false,
+                    },
+                );
+            }
+
+            ScriptCommand::AutoPoolMonsterTide {
+                group_id,
+                source_id,
+                pool_id_list,
+                point_config_id_list,
+                total_count,
+                min_count,
+                max_count,
+                tag,
+            } => {
+                tracing::debug!(
+                    "[Script] [AutoPoolMonsterTide] group={} source={} pools={:?} total={} min={} max={}",
+                    group_id,
+                    source_id,
+                    pool_id_list,
+                    total_count,
+                    min_count,
+                    max_count
+                );
+                manager.tides.insert(
+                    (*group_id, *source_id),
+                    MonsterTide {
+                        source: MonsterTideSource::Pool {
+                            pool_id_list: pool_id_list.clone(),
+                            point_config_id_list: point_config_id_list.clone(),
+                            tag: tag.clone(),
+                        },
+                        total_count: *total_count,
+                        min_count: *min_count,
+                        max_count: (*max_count).max(*min_count).max(1),
+                        spawned_count: 0,
+                        killed_count: 0,
+                        alive_config_ids: vec![],
+                        paused: false,
+                        force_fill: false,
+                    },
+                );
+            }
+
+            ScriptCommand::EndMonsterTide {
+                group_id,
+                source_id,
+                end_type,
+            } => {
+                tracing::debug!(
+                    "[Script] [EndMonsterTide] group={} source={} end_type={}",
+                    group_id,
+                    source_id,
+                    end_type
+                );
+                // alive monsters stay in the scene, only the refill stops
+                let Some(tide) = manager.tides.remove(&(*group_id, *source_id)) else {
+                    continue;
+                };
+                let (_, _, over_event_type) = tide.event_types();
+                lua_trigger_events.write(LuaTriggerEvent {
+                    group_id: *group_id,
+                    event_type: over_event_type,
+                    evt: LuaEvt {
+                        param1: tide.killed_count,
+                        param2: *end_type,
+                        param3: *source_id,
+                        source_eid: 0,
+                        target_eid: 0,
+                    },
+                });
+            }
+
+            ScriptCommand::KillMonsterTide {
+                group_id,
+                source_id,
+            } => {
+                tracing::debug!(
+                    "[Script] [KillMonsterTide] group={} source={}",
+                    group_id,
+                    source_id
+                );
+                let Some(tide) = manager.tides.remove(&(*group_id, *source_id)) else {
+                    continue;
+                };
+
+                entities
+                    .iter()
+                    .filter(|(_, _, entity_group_id, config_id)| {
+                        entity_group_id.0 == *group_id
+                            && tide.alive_config_ids.contains(&config_id.0)
+                    })
+                    .for_each(|(entity, entity_id, _, config_id)| {
+                        disappear_events
+                            .write(EntityDisappearEvent(entity_id.0, VisionType::VisionDie));
+                        commands.entity(entity).insert(ToBeRemovedMarker);
+                        get_group_entity_state_cache().on_monster_remove(
+                            world_owner_uid.0,
+                            *group_id,
+                            config_id.0,
+                        );
+                    });
+            }
+
+            ScriptCommand::PauseMonsterTide {
+                group_id,
+                source_id,
+            } => {
+                if let Some(tide) = manager.tides.get_mut(&(*group_id, *source_id)) {
+                    tide.paused = true;
+                }
+            }
+
+            ScriptCommand::ContinueMonsterTide {
+                group_id,
+                source_id,
+            } => {
+                if let Some(tide) = manager.tides.get_mut(&(*group_id, *source_id)) {
+                    tide.paused = false;
+                }
+            }
+
+            ScriptCommand::CreateMonstersFromMonsterPool { group_id, tag } => {
+                tracing::debug!(
+                    "[Script] [CreateMonstersFromMonsterPool] group={} tag={}",
+                    group_id,
+                    tag
+                );
+                // the spawn system tops the matching pool tides up to max_count
+                manager
+                    .tides
+                    .iter_mut()
+                    .filter(|((tide_group_id, _), _)| tide_group_id == group_id)
+                    .for_each(|(_, tide)| {
+                        if let MonsterTideSource::Pool { tag: tide_tag, .. } = &tide.source
+                            && (tag.is_empty() || tide_tag == tag)
+                        {
+                            tide.force_fill = true;
+                        }
+                    });
+            }
+
+            ScriptCommand::UnloadGroup { group_id } => {
+                manager
+                    .tides
+                    .retain(|(tide_group_id, _), _| tide_group_id != group_id);
+            }
+
+            _ => {}
+        }
+    }
+}
+
+pub fn monster_tide_spawn_system(
+    mut commands: Commands,
+    mut manager: ResMut<MonsterTideManager>,
+    mut entity_counter: ResMut<EntityCounter>,
+    world_owner_uid: Res<WorldOwnerUID>,
+    world_version_config: Res<WorldVersionConfig>,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+    registry: NonSend<SceneGroupRegistry>,
+) {
+    if manager.tides.is_empty() {
+        return;
+    }
+
+    let scene_group_collection = std::sync::Arc::clone(
+        nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION
+            .get()
+            .unwrap(),
+    );
+
+    for ((group_id, source_id), tide) in manager.tides.iter_mut() {
+        if tide.paused {
+            continue;
+        }
+        let fill_count = tide.fill_count();
+        tide.force_fill = false;
+        if fill_count == 0 {
+            continue;
+        }
+
+        let Some(GroupLoadState::Loaded(rt)) = registry.groups.get(group_id) else {
+            continue;
+        };
+        let block_id = rt.data.base_info.block_id;
+
+        let Some(scene_group_template) = scene_group_collection.get(group_id) else {
+            continue;
+        };
+        let Some(scene_group_template) = scene_group_template.value() else {
+            continue;
+        };
+
+        let (create_event_type, _, _) = tide.event_types();
+
+        for _ in 0..fill_count {
+            let Some(monster) = next_tide_monster(*source_id, tide, scene_group_template) else {
+                break;
+            };
+
+            let show_level =
+                cache_get_scene_level(world_owner_uid.0, scene_group_template.base_info.scene_id)
+                    .unwrap_or(1);
+
+            let mut level = monster.level.unwrap_or(103) + 67;
+            level += show_level - 1;
+
+            let Some((entity_id, monster_entity, cur_hp, max_hp)) = spawn_monster_entity(
+                world_version_config.protocol_version.clone(),
+                &mut commands,
+                &mut entity_counter,
+                VectorBin {
+                    x: monster.pos.x,
+                    y: monster.pos.y,
+                    z: monster.pos.z,
+                },
+                VectorBin {
+                    x: monster.rot.x,
+                    y: monster.rot.y,
+                    z: monster.rot.z,
+                },
+                monster.monster_id,
+                level,
+                monster.pose_id.unwrap_or(0),
+                monster.title_id.unwrap_or(0),
+                monster.special_name_id.unwrap_or(0),
+                monster.drop_tag.clone(),
+                monster.chest_drop_id.unwrap_or(0),
+                GlobalAbilityValues::default(),
+            ) else {
+                break;
+            };
+
+            commands
+                .entity(monster_entity)
+                .insert(BlockId(block_id))
+                .insert(GroupId(*group_id))
+                .insert(ConfigId(monster.config_id))
+                .insert(Visible);
+
+            get_group_entity_state_cache().on_monster_spawn(
+                world_owner_uid.0,
+                *group_id,
+                monster.config_id,
+                entity_id,
+                cur_hp,
+                max_hp,
+            );
+
+            tide.spawned_count += 1;
+            tide.alive_config_ids.push(monster.config_id);
+
+            lua_trigger_events.write(LuaTriggerEvent {
+                group_id: *group_id,
+                event_type: EventType::EventAnyMonsterLive,
+                evt: LuaEvt {
+                    param1: monster.config_id,
+                    param2: monster.config_id,
+                    param3: 0,
+                    source_eid: entity_id,
+                    target_eid: entity_id,
+                },
+            });
+
+            lua_trigger_events.write(LuaTriggerEvent {
+                group_id: *group_id,
+                event_type: create_event_type,
+                evt: LuaEvt {
+                    param1: monster.config_id,
+                    param2: tide.spawned_count,
+                    param3: *source_id,
+                    source_eid: entity_id,
+                    target_eid: entity_id,
+                },
+            });
+
+            tracing::debug!(
+                "[MonsterTide] spawned group_id {} source {} config_id {} entity_id {} ({}/{})",
+                group_id,
+                source_id,
+                monster.config_id,
+                entity_id,
+                tide.spawned_count,
+                tide.total_count
+            );
+        }
+    }
+}
+
+fn next_tide_monster(
+    source_id: u32,
+    tide: &mut MonsterTide,
+    scene_group_template: &SceneGroupTemplate,
+) -> Option<Monster> {
+    match &mut tide.source {
+        MonsterTideSource::Orders {
+            config_id_list,
+            next_index,
+        } => {
+            // the same config can't be alive twice, skip the ones still standing
+            for _ in 0..config_id_list.len() {
+                let config_id = config_id_list[*next_index % config_id_list.len()];
+                *next_index += 1;
+                if tide.alive_config_ids.contains(&config_id) {
+                    continue;
+                }
+                return scene_group_template
+                    .monsters
+                    .iter()
+                    .find(|m| m.config_id == config_id)
+                    .cloned();
+            }
+            None
+        }
+        MonsterTideSource::Pool {
+            pool_id_list,
+            point_config_id_list,
+            tag,
+        } => {
+            let pool_id =
+                *pool_id_list.get(tide.spawned_count as usize % pool_id_list.len().max(1))?;
+            let monster_id = MonsterPool::get_pool(pool_id)?.random_monster_id()?;
+
+            let (pos, rot) = tide_spawn_point(
+                point_config_id_list,
+                tide.spawned_count as usize,
+                scene_group_template,
+            )?;
+
+            Some(Monster {
+                config_id: POOL_MONSTER_CONFIG_ID_BASE
+                    + source_id * POOL_MONSTER_CONFIG_ID_STRIDE
+                    + tide.spawned_count,
+                monster_id,
+                pos,
+                rot,
+                level: scene_group_template.monsters.first().and_then(|m| m.level),
+                title_id: None,
+                special_name_id: None,
+                drop_tag: (!tag.is_empty()).then(|| tag.clone()),
+                chest_drop_id: None,
+                disable_wander: None,
+                pose_id: None,
+                area_id: None,
+                is_one_off: None,
+            })
+        }
+    }
+}
+
+// pool tides spawn at the listed points, otherwise at the group's own monster or region positions
+fn tide_spawn_point(
+    point_config_id_list: &[u32],
+    index: usize,
+    scene_group_template: &SceneGroupTemplate,
+) -> Option<(Position, Position)> {
+    let mut points: Vec<(Position, Position)> = point_config_id_list
+        .iter()
+        .filter_map(|config_id| {
+            scene_group_template
+                .gadgets
+                .iter()
+                .find(|g| g.config_id == *config_id)
+                .map(|g| (g.pos.clone(), g.rot.clone()))
+                .or_else(|| {
+                    scene_group_template
+                        .monsters
+                        .iter()
+                        .find(|m| m.config_id == *config_id)
+                        .map(|m| (m.pos.clone(), m.rot.clone()))
+                })
+        })
+        .collect();
+
+    if points.is_empty() {
+        points = scene_group_template
+            .monsters
+            .iter()
+            .map(|m| (m.pos.clone(), m.rot.clone()))
+            .collect();
+    }
+    if points.is_empty() {
+        points = scene_group_template
+            .regions
+            .iter()
+            .map(|r| (r.pos.clone(), Position::default()))
+            .collect();
+    }
+    if points.is_empty() {
+        return None;
+    }
+
+    Some(points.swap_remove(index % points.len()))
+}
+
+pub fn handle_monster_kill_tide_event(
+    mut ev_reader: MessageReader<MonsterKillEvent>,
+    mut manager: ResMut<MonsterTideManager>,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+) {
+    for event in ev_reader.read() {
+        let Some((&key, tide)) = manager.tides.iter_mut().find(|((group_id, _), tide)| {
+            *group_id == event.group_id && tide.alive_config_ids.contains(&event.config_id)
+        }) else {
+            continue;
+        };
+        let (group_id, source_id) = key;
+
+        tide.alive_config_ids.retain(|id| *id != event.config_id);
+        tide.killed_count += 1;
+
+        let (_, die_event_type, over_event_type) = tide.event_types();
+
+        lua_trigger_events.write(LuaTriggerEvent {
+            group_id,
+            event_type: die_event_type,
+            evt: LuaEvt {
+                param1: tide.killed_count,
+                param2: event.config_id,
+                param3: source_id,
+                source_eid: 0,
+                target_eid: 0,
+            },
+        });
+
+        if tide.is_over() {
+            lua_trigger_events.write(LuaTriggerEvent {
+                group_id,
+                event_type: over_event_type,
+                evt: LuaEvt {
+                    param1: tide.killed_count,
+                    param2: 0,
+                    param3: source_id,
+                    source_eid: 0,
+                    target_eid: 0,
+                },
+            });
+            manager.tides.remove(&key);
+
+            tracing::debug!(
+                "[MonsterTide] group_id {} source {} over",
+                group_id,
+                source_id
+            );
+        }
+    }
+}
@@ -3,17 +3,19 @@ use bevy_ecs::entity::Entity;
 use bevy_ecs::message::MessageReader;
 use bevy_ecs::prelude::*;
 use common::player_cache::cache_get_scene_level;
+use common::time_util;
 use nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache;
 use nod_krai_gi_data::scene::{EventType, GadgetState, LuaEvt};
 use nod_krai_gi_entity::common::{
-    BlockId, ConfigId, EntityCounter, GroupId, ProtocolEntityID, ToBeRemovedMarker, Visible,
+    BlockId, ConfigId, EntityCounter, GlobalAbilityValues, GroupId, LifeState, OneOff,
+    ProtocolEntityID, ToBeRemovedMarker, Visible,
 };
 use nod_krai_gi_entity::gadget::spawn_gadget_entity;
-use nod_krai_gi_entity::monster::spawn_monster_entity;
+use nod_krai_gi_entity::monster::{spawn_monster_entity, MonsterID};
 use nod_krai_gi_event::entity::EntityDisappearEvent;
 use nod_krai_gi_event::lua::{
-    DespawnGroupEntityEvent, DespawnSuiteEntitiesEvent, LuaTriggerEvent, RefreshGroupEntityEvent,
-    SpawnGroupEntityEvent, SpawnSuiteEntitiesEvent,
+    DespawnGroupEntityEvent, DespawnSuiteEntitiesEvent, LuaTriggerEvent, MonsterKillEvent,
+    RefreshGroupEntityEvent, SpawnGroupEntityEvent, SpawnSuiteEntitiesEvent,
 };
 use nod_krai_gi_event::scene::{WorldOwnerUID, WorldVersionConfig};
 
@@ -22,6 +24,16 @@ use crate::SceneGroupRegistry;
 use nod_krai_gi_proto::normal::scene_gadget_info::Content;
 use nod_krai_gi_proto::normal::{GatherGadgetInfo, VisionType};
 use nod_krai_gi_proto::server_only::VectorBin;
+use std::collections::HashSet;
+
+// (group_id, config_id) spawned this frame, their entities only show up in queries once
+// the commands are applied
+#[derive(Resource, Default)]
+pub struct PendingGroupSpawns(pub HashSet<(u32, u32)>);
+
+pub fn clear_pending_group_spawns(mut pending_spawns: ResMut<PendingGroupSpawns>) {
+    pending_spawns.0.clear();
+}
 
 pub fn spawn_suite_entities(
     mut spawn_suite_events: MessageReader<SpawnSuiteEntitiesEvent>,
@@ -30,6 +42,8 @@ pub fn spawn_suite_entities(
     world_owner_uid: Res<WorldOwnerUID>,
     world_version_config: Res<WorldVersionConfig>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+    existing_entities: Query<(&GroupId, &ConfigId), Without<ToBeRemovedMarker>>,
+    mut pending_spawns: ResMut<PendingGroupSpawns>,
 ) {
     let scene_group_collection = std::sync::Arc::clone(
         nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION
@@ -37,6 +51,8 @@ pub fn spawn_suite_entities(
             .unwrap(),
     );
 
+    let now = time_util::unix_timestamp() as u32;
+
     for event in spawn_suite_events.read() {
         let Some(scene_group_template) = scene_group_collection.get(&event.group_id) else {
             continue;
@@ -59,8 +75,33 @@ pub fn spawn_suite_entities(
             continue;
         };
 
+        // suites can share entities, don't spawn a second copy
+        let spawned_config_ids: HashSet<u32> = existing_entities
+            .iter()
+            .filter(|(group_id, _)| group_id.0 == event.group_id)
+            .map(|(_, config_id)| config_id.0)
+            .chain(
+                pending_spawns
+                    .0
+                    .iter()
+                    .filter(|(group_id, _)| *group_id == event.group_id)
+                    .map(|(_, config_id)| *config_id),
+            )
+            .collect();
+
         for monster in scene_group_template.monsters.iter() {
             if suite.monsters.contains(&monster.config_id) {
+                if spawned_config_ids.contains(&monster.config_id) {
+                    continue;
+                }
+                if get_group_entity_state_cache().is_monster_one_off(
+                    world_owner_uid.0,
+                    event.group_id,
+                    monster.config_id,
+                    now,
+                ) {
+                    continue;
+                }
                 let mut level = monster.level.unwrap_or(103) + 67;
                 level += show_level - 1;
                 let Some((entity_id, monster_entity, cur_hp, max_hp)) = spawn_monster_entity(
@@ -84,6 +125,7 @@ pub fn spawn_suite_entities(
                     monster.special_name_id.unwrap_or(0),
                     monster.drop_tag.clone(),
                     monster.chest_drop_id.unwrap_or(0),
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
@@ -93,6 +135,10 @@ pub fn spawn_suite_entities(
                     .insert(GroupId(event.group_id))
                     .insert(ConfigId(monster.config_id))
                     .insert(Visible);
+                pending_spawns.0.insert((event.group_id, monster.config_id));
+                if monster.is_one_off.unwrap_or(false) {
+                    commands.entity(monster_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_monster_spawn(
                     world_owner_uid.0,
@@ -127,6 +173,17 @@ pub fn spawn_suite_entities(
 
         for gadget in scene_group_template.gadgets.iter() {
             if suite.gadgets.contains(&gadget.config_id) {
+                if spawned_config_ids.contains(&gadget.config_id) {
+                    continue;
+                }
+                if get_group_entity_state_cache().is_gadget_one_off(
+                    world_owner_uid.0,
+                    event.group_id,
+                    gadget.config_id,
+                    now,
+                ) {
+                    continue;
+                }
                 let gadget_id = gadget.gadget_id;
                 let is_interactive = false;
                 let Some((entity_id, gadget_entity, cur_hp, max_hp)) = spawn_gadget_entity(
@@ -150,6 +207,7 @@ pub fn spawn_suite_entities(
                     gadget.drop_tag.clone(),
                     gadget.chest_drop_id.unwrap_or(0),
                     gadget.state.unwrap_or(GadgetState::Default) as u32,
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
@@ -159,6 +217,10 @@ pub fn spawn_suite_entities(
                     .insert(GroupId(event.group_id))
                     .insert(ConfigId(gadget.config_id))
                     .insert(Visible);
+                pending_spawns.0.insert((event.group_id, gadget.config_id));
+                if gadget.is_one_off.unwrap_or(false) {
+                    commands.entity(gadget_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_gadget_spawn(
                     world_owner_uid.0,
@@ -197,8 +259,18 @@ pub fn spawn_suite_entities(
 pub fn despawn_suite_entities(
     mut despawn_suite_events: MessageReader<DespawnSuiteEntitiesEvent>,
     mut commands: Commands,
-    mut entities: Query<(Entity, &ProtocolEntityID, &GroupId, &ConfigId)>,
+    mut entities: Query<(
+        Entity,
+        &ProtocolEntityID,
+        &GroupId,
+        &ConfigId,
+        Option<&MonsterID>,
+    )>,
     mut disappear_events: MessageWriter<EntityDisappearEvent>,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+    mut monster_kill_events: MessageWriter<MonsterKillEvent>,
+    world_owner_uid: Res<WorldOwnerUID>,
+    registry: NonSend<SceneGroupRegistry>,
 ) {
     let scene_group_collection = std::sync::Arc::clone(
         nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION
@@ -222,20 +294,89 @@ pub fn despawn_suite_entities(
         let mut config_ids = suite.monsters.clone();
         config_ids.extend(suite.gadgets.clone());
 
+        // entities shared with a suite that is still active stay in the scene
+        if let Some(GroupLoadState::Loaded(rt)) = registry.groups.get(&event.group_id) {
+            for suite_id in rt.all_active_suites() {
+                let Some(active_suite) = scene_group_template
+                    .suites
+                    .get(suite_id.saturating_sub(1) as usize)
+                else {
+                    continue;
+                };
+                config_ids.retain(|config_id| {
+                    !active_suite.monsters.contains(config_id)
+                        && !active_suite.gadgets.contains(config_id)
+                });
+            }
+        }
+
         entities
             .iter_mut()
-            .filter(|(_, _, group_id, config_id)| {
+            .filter(|(_, _, group_id, config_id, _)| {
                 group_id.0 == event.group_id && config_ids.contains(&config_id.0)
             })
-            .for_each(|(entity, entity_id, _, config_id)| {
-                disappear_events.write(EntityDisappearEvent(
-                    entity_id.0,
-                    VisionType::VisionMiss.into(),
-                ));
+            .for_each(|(entity, entity_id, _, config_id, monster_id)| {
                 commands.entity(entity).insert(ToBeRemovedMarker);
 
+                if !event.kill {
+                    disappear_events
+                        .write(EntityDisappearEvent(entity_id.0, VisionType::VisionMiss));
+                    tracing::debug!(
+                        "despawn group_id {} config_id {} suite {} entity_id {}",
+                        event.group_id,
+                        config_id.0,
+                        event.suite_id,
+                        entity_id.0
+                    );
+                    return;
+                }
+
+                // killed by script: no drops, but die triggers fire so the group can advance
+                disappear_events.write(EntityDisappearEvent(entity_id.0, VisionType::VisionDie));
+                let cache = get_group_entity_state_cache();
+                let event_type = match monster_id {
+                    Some(monster_id) => {
+                        cache.on_monster_life_state_update(
+                            world_owner_uid.0,
+                            event.group_id,
+                            config_id.0,
+                            LifeState::Dead as u32,
+                            0.0,
+                            0.0,
+                        );
+                        monster_kill_events.write(MonsterKillEvent {
+                            group_id: event.group_id,
+                            config_id: config_id.0,
+                            monster_id: monster_id.0,
+                        });
+                        EventType::EventAnyMonsterDie
+                    }
+                    None => {
+                        cache.on_gadget_life_state_update(
+                            world_owner_uid.0,
+                            event.group_id,
+                            config_id.0,
+                            LifeState::Dead as u32,
+                            0.0,
+                            0.0,
+                        );
+                        EventType::EventAnyGadgetDie
+                    }
+                };
+                lua_trigger_events.write(LuaTriggerEvent {
+                    group_id: event.group_id,
+                    event_type,
+                    evt: LuaEvt {
+                        param1: config_id.0,
+                        param2: 0,
+                        param3: 0,
+                        source_eid: entity_id.0,
+                        target_eid: entity_id.0,
+                    },
+                });
+
                 tracing::debug!(
-                    "despawn group_id {} config_id {} suite {} entity_id {}",
+                    "kill group_id {} config_id {} suite {} entity_id {}",
                     event.group_id,
                     config_id.0,
                     event.suite_id,
@@ -253,6 +394,7 @@ pub fn spawn_group_entity(
     world_version_config: Res<WorldVersionConfig>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
     mut registry: NonSendMut<SceneGroupRegistry>,
+    mut pending_spawns: ResMut<PendingGroupSpawns>,
 ) {
     let scene_group_collection_clone = std::sync::Arc::clone(
         nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION
@@ -263,6 +405,8 @@ pub fn spawn_group_entity(
     let gather_excel_config_collection_clone =
         std::sync::Arc::clone(nod_krai_gi_data::excel::gather_excel_config_collection::get());
 
+    let now = time_util::unix_timestamp() as u32;
+
     for event in spawn_group_entity_event.read() {
         // Handle suite refresh for quest RefreshGroupSuite exec
         if event.refresh_suite_id != 0 {
@@ -304,6 +448,14 @@ pub fn spawn_group_entity(
 
         for monster in scene_group_template.monsters.iter() {
             if suite.monsters.contains(&monster.config_id) {
+                if get_group_entity_state_cache().is_monster_one_off(
+                    world_owner_uid.0,
+                    event.group_id,
+                    monster.config_id,
+                    now,
+                ) {
+                    continue;
+                }
                 let mut level = monster.level.unwrap_or(103) + 67;
                 level += show_level - 1;
                 let Some((entity_id, monster_entity, cur_hp, max_hp)) = spawn_monster_entity(
@@ -327,6 +479,7 @@ pub fn spawn_group_entity(
                     monster.special_name_id.unwrap_or(0),
                     monster.drop_tag.clone(),
                     monster.chest_drop_id.unwrap_or(0),
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
@@ -336,6 +489,10 @@ pub fn spawn_group_entity(
                     .insert(GroupId(event.group_id))
                     .insert(ConfigId(monster.config_id))
                     .insert(Visible);
+                pending_spawns.0.insert((event.group_id, monster.config_id));
+                if monster.is_one_off.unwrap_or(false) {
+                    commands.entity(monster_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_monster_spawn(
                     world_owner_uid.0,
@@ -361,6 +518,14 @@ pub fn spawn_group_entity(
         }
         for gadget in scene_group_template.gadgets.iter() {
             if suite.gadgets.contains(&gadget.config_id) {
+                if get_group_entity_state_cache().is_gadget_one_off(
+                    world_owner_uid.0,
+                    event.group_id,
+                    gadget.config_id,
+                    now,
+                ) {
+                    continue;
+                }
                 let mut gadget_id = gadget.gadget_id;
                 let mut is_interactive = false;
                 let mut gadget_content = None;
@@ -402,6 +567,7 @@ pub fn spawn_group_entity(
                     gadget.drop_tag.clone(),
                     gadget.chest_drop_id.unwrap_or(0),
                     gadget.state.unwrap_or(GadgetState::Default) as u32,
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
@@ -411,6 +577,10 @@ pub fn spawn_group_entity(
                     .insert(GroupId(event.group_id))
                     .insert(ConfigId(gadget.config_id))
                     .insert(Visible);
+                pending_spawns.0.insert((event.group_id, gadget.config_id));
+                if gadget.is_one_off.unwrap_or(false) {
+                    commands.entity(gadget_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_gadget_spawn(
                     world_owner_uid.0,
@@ -479,6 +649,7 @@ pub fn refresh_group_entity(
     mut entities: Query<(Entity, &ProtocolEntityID, &GroupId)>,
     mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
     mut disappear_events: MessageWriter<EntityDisappearEvent>,
+    mut pending_spawns: ResMut<PendingGroupSpawns>,
 ) {
     let scene_group_collection = std::sync::Arc::clone(
         nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION
@@ -489,6 +660,8 @@ pub fn refresh_group_entity(
     let gather_excel_config_collection =
         std::sync::Arc::clone(nod_krai_gi_data::excel::gather_excel_config_collection::get());
 
+    let now = time_util::unix_timestamp() as u32;
+
     for event in refresh_group_events.read() {
         let group_id = event.group_id;
         let suite_id = event.suite_id;
@@ -528,6 +701,14 @@ pub fn refresh_group_entity(
 
         for monster in scene_group_template.monsters.iter() {
             if suite.monsters.contains(&monster.config_id) {
+                if get_group_entity_state_cache().is_monster_one_off(
+                    world_owner_uid.0,
+                    group_id,
+                    monster.config_id,
+                    now,
+                ) {
+                    continue;
+                }
                 let mut level = monster.level.unwrap_or(103) + 67;
                 level += show_level - 1;
                 let Some((entity_id, monster_entity, cur_hp, max_hp)) = spawn_monster_entity(
@@ -551,6 +732,7 @@ pub fn refresh_group_entity(
                     monster.special_name_id.unwrap_or(0),
                     monster.drop_tag.clone(),
                     monster.chest_drop_id.unwrap_or(0),
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
@@ -560,6 +742,10 @@ pub fn refresh_group_entity(
                     .insert(GroupId(group_id))
                     .insert(ConfigId(monster.config_id))
                     .insert(Visible);
+                pending_spawns.0.insert((group_id, monster.config_id));
+                if monster.is_one_off.unwrap_or(false) {
+                    commands.entity(monster_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_monster_spawn(
                     world_owner_uid.0,
@@ -586,6 +772,14 @@ pub fn refresh_group_entity(
 
         for gadget in scene_group_template.gadgets.iter() {
             if suite.gadgets.contains(&gadget.config_id) {
+                if get_group_entity_state_cache().is_gadget_one_off(
+                    world_owner_uid.0,
+                    group_id,
+                    gadget.config_id,
+                    now,
+                ) {
+                    continue;
+                }
                 let mut gadget_id = gadget.gadget_id;
                 let mut is_interactive = false;
                 let mut gadget_content = None;
@@ -627,6 +821,7 @@ pub fn refresh_group_entity(
                     gadget.drop_tag.clone(),
                     gadget.chest_drop_id.unwrap_or(0),
                     gadget.state.unwrap_or(GadgetState::Default) as u32,
+                    GlobalAbilityValues::default(),
                 ) else {
                     continue;
                 };
@@ -636,6 +831,10 @@ pub fn refresh_group_entity(
                     .insert(GroupId(group_id))
                     .insert(ConfigId(gadget.config_id))
                     .insert(Visible);
+                pending_spawns.0.insert((group_id, gadget.config_id));
+                if gadget.is_one_off.unwrap_or(false) {
+                    commands.entity(gadget_entity).insert(OneOff);
+                }
 
                 get_group_entity_state_cache().on_gadget_spawn(
                     world_owner_uid.0,
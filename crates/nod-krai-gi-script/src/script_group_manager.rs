@@ -1,9 +1,16 @@
+use crate::SharedVariableStore;
 use bevy_ecs::prelude::*;
+use nod_krai_gi_data::excel::{
+    level_tag_excel_config_collection, LevelTagExcelConfig, LevelTagExcelConfigKeyed,
+};
 use nod_krai_gi_data::scene::group_spatial_cache::get_or_init_spatial_cache;
+use nod_krai_gi_data::scene::scene_block_template::BlockGroup;
 use nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION;
 use nod_krai_gi_data::scene::{EventType, Position, ScriptCommand};
 use nod_krai_gi_event::combat::PlayerMoveEvent;
 use nod_krai_gi_event::lua::{LuaTriggerEvent, ScriptCommandEvent};
+use nod_krai_gi_event::scene::{LevelTagChangeEvent, WorldOwnerUID};
+use nod_krai_gi_persistence::Players;
 use std::{
     collections::{HashMap, HashSet},
     time::{Duration, Instant},
@@ -16,6 +23,9 @@ pub struct GroupLoadManager {
     pub last_load: HashMap<u32, Instant>,
     pub last_region_entry: HashMap<u32, Instant>,
     pub player_regions: HashMap<u32, HashSet<(u32, u32)>>,
+    pub player_vision_types: HashMap<u32, HashSet<u32>>,
+    pub player_region_vision_types: HashMap<u32, HashSet<u32>>,
+    pub region_vision_forbidden: HashSet<u32>,
     pub cd: Duration,
     pub region_entry_cd: Duration,
 }
@@ -28,18 +38,42 @@ impl Default for GroupLoadManager {
             last_load: HashMap::new(),
             last_region_entry: HashMap::new(),
             player_regions: HashMap::new(),
+            player_vision_types: HashMap::new(),
+            player_region_vision_types: HashMap::new(),
+            region_vision_forbidden: HashSet::new(),
             cd: Duration::from_millis(3000),
             region_entry_cd: Duration::from_millis(1000),
         }
     }
 }
 
-fn get_group_position(group_id: u32) -> (u32, Position) {
-    let none_pos = Position {
-        x: 0.0,
-        y: 0.0,
-        z: 0.0,
-    };
+impl GroupLoadManager {
+    // region vision overrides the script assigned types while the player stands in the region
+    fn effective_vision_types(&self, uid: u32) -> Option<&HashSet<u32>> {
+        if !self.region_vision_forbidden.contains(&uid)
+            && let Some(vision_types) = self.player_region_vision_types.get(&uid)
+        {
+            return Some(vision_types);
+        }
+        self.player_vision_types.get(&uid)
+    }
+
+    // players without vision types see every group, otherwise typed groups need a matching type
+    fn is_group_visible(&self, uid: u32, group_id: u32) -> bool {
+        let Some(vision_types) = self.effective_vision_types(uid) else {
+            return true;
+        };
+        if vision_types.is_empty() {
+            return true;
+        }
+        match get_block_group(group_id).and_then(|(_, block_group)| block_group.vision_type) {
+            None | Some(0) => true,
+            Some(vision_type) => vision_types.contains(&vision_type),
+        }
+    }
+}
+
+fn get_block_group(group_id: u32) -> Option<(u32, BlockGroup)> {
     let scene_group_collection_clone = std::sync::Arc::clone(SCENE_GROUP_COLLECTION.get().unwrap());
 
     let scene_block_collection_clone = std::sync::Arc::clone(
@@ -48,22 +82,61 @@ fn get_group_position(group_id: u32) -> (u32, Position) {
             .unwrap(),
     );
 
-    match scene_group_collection_clone.get(&group_id) {
-        None => (0, none_pos),
-        Some(scene_group_template) => match scene_group_template.value() {
-            None => (0, none_pos),
-            Some(scene_group_template) => {
-                let scene_id = scene_group_template.base_info.scene_id;
-                let block_id = scene_group_template.base_info.block_id;
-                let Some(block) = scene_block_collection_clone.get(&(scene_id, block_id)) else {
-                    return (0, none_pos);
-                };
-                let Some(block_group) = block.groups.iter().find(|g| g.id == group_id) else {
-                    return (0, none_pos);
-                };
-                (scene_id, block_group.pos.clone())
-            }
-        },
+    let scene_group_template = scene_group_collection_clone.get(&group_id)?;
+    let scene_group_template = scene_group_template.value().as_ref()?;
+    let scene_id = scene_group_template.base_info.scene_id;
+    let block_id = scene_group_template.base_info.block_id;
+    let block = scene_block_collection_clone.get(&(scene_id, block_id))?;
+    let block_group = block.groups.iter().find(|g| g.id == group_id)?;
+    Some((scene_id, block_group.clone()))
+}
+
+// groups bound to a level tag only load while that tag is the scene's active one,
+// scenes without an active tag keep every group loadable
+fn is_group_level_tag_active(
+    players: &Players,
+    owner_uid: u32,
+    scene_id: u32,
+    group_id: u32,
+) -> bool {
+    let Some(level_tag_id) = LevelTagExcelConfig::get_level_tag_by_dynamic_group(group_id) else {
+        return true;
+    };
+    let level_tag_config_collection = level_tag_excel_config_collection::get();
+    let Some(level_tag_bin) = players
+        .get(owner_uid)
+        .and_then(|player_info| player_info.scene_bin.as_ref())
+        .and_then(|player_scene_bin| player_scene_bin.level_tag_bin.as_ref())
+    else {
+        return true;
+    };
+
+    let mut scene_level_tags = level_tag_bin
+        .level_tag_change_list
+        .iter()
+        .filter(|level_tag_change| {
+            level_tag_config_collection
+                .get(&level_tag_change.level_tag_id)
+                .is_some_and(|config| config.scene_id == scene_id)
+        })
+        .peekable();
+    if scene_level_tags.peek().is_none() {
+        return true;
+    }
+    scene_level_tags.any(|level_tag_change| level_tag_change.level_tag_id == level_tag_id)
+}
+
+fn get_group_position(group_id: u32) -> (u32, Position) {
+    match get_block_group(group_id) {
+        None => (
+            0,
+            Position {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        ),
+        Some((scene_id, block_group)) => (scene_id, block_group.pos),
     }
 }
 
@@ -71,6 +144,9 @@ pub fn handle_player_move_for_group_loading(
     mut events: MessageReader<PlayerMoveEvent>,
     mut group_load_manager: ResMut<GroupLoadManager>,
     mut script_command_events: MessageWriter<ScriptCommandEvent>,
+    variable_store: Res<SharedVariableStore>,
+    players: Res<Players>,
+    world_owner_uid: Res<WorldOwnerUID>,
 ) {
     for ev in events.read() {
         tracing::trace!(
@@ -133,7 +209,13 @@ pub fn handle_player_move_for_group_loading(
                 if group_load_manager.groups.contains(*group_id) {
                     return false;
                 }
-                true
+                if !group_load_manager.is_group_visible(uid, **group_id) {
+                    return false;
+                }
+                if !is_group_level_tag_active(&players, world_owner_uid.0, scene_id, **group_id) {
+                    return false;
+                }
+                !variable_store.0.is_group_dead(**group_id)
             })
             .copied()
             .collect();
@@ -154,28 +236,39 @@ pub fn handle_player_move_for_group_loading(
             .groups
             .iter()
             .filter(|group_id| {
-                !other_player_groups.contains(*group_id) && {
-                    let (this_scene_id, this_position) = get_group_position(**group_id);
-                    if this_scene_id != scene_id {
-                        return true;
-                    }
+                if variable_store.0.is_group_dead(**group_id) {
+                    return true;
+                }
+                if other_player_groups.contains(*group_id) {
+                    return false;
+                }
+                if !group_load_manager.is_group_visible(uid, **group_id) {
+                    return true;
+                }
+                if !is_group_level_tag_active(&players, world_owner_uid.0, scene_id, **group_id) {
+                    return true;
+                }
 
-                    if let Some(cache) = &spatial_cache {
-                        if let Some(group_info) = cache.scene_groups.get(group_id) {
-                            let dx = group_info.center[0] - pos.x;
-                            let dy = group_info.center[1] - pos.y;
-                            let dz = group_info.center[2] - pos.z;
-                            let dist_squared = dx * dx + dy * dy + dz * dz;
-                            let unload_distance = group_info.vision_range + 100.0;
-                            let unload_distance_squared = unload_distance * unload_distance;
-                            return dist_squared > unload_distance_squared;
-                        }
-                    }
+                let (this_scene_id, this_position) = get_group_position(**group_id);
+                if this_scene_id != scene_id {
+                    return true;
+                }
 
-                    let default_unload_distance = 180.0f32; // 80 + 100
-                    this_position.distance_squared(&pos)
-                        > default_unload_distance * default_unload_distance
+                if let Some(cache) = &spatial_cache {
+                    if let Some(group_info) = cache.scene_groups.get(group_id) {
+                        let dx = group_info.center[0] - pos.x;
+                        let dy = group_info.center[1] - pos.y;
+                        let dz = group_info.center[2] - pos.z;
+                        let dist_squared = dx * dx + dy * dy + dz * dz;
+                        let unload_distance = group_info.vision_range + 100.0;
+                        let unload_distance_squared = unload_distance * unload_distance;
+                        return dist_squared > unload_distance_squared;
+                    }
                 }
+
+                let default_unload_distance = 180.0f32; // 80 + 100
+                this_position.distance_squared(&pos)
+                    > default_unload_distance * default_unload_distance
             })
             .map(|id| *id)
             .collect();
@@ -250,6 +343,7 @@ pub fn handle_player_move_for_region_entry(
 
         let player_groups = group_load_manager.player_groups.get(&uid).cloned();
         let mut current_regions: HashSet<(u32, u32)> = HashSet::new();
+        let mut region_vision_types: HashSet<u32> = HashSet::new();
 
         if let Some(player_groups) = player_groups {
             let scene_group_collection =
@@ -268,6 +362,9 @@ pub fn handle_player_move_for_region_entry(
                             let radius_squared = radius * radius;
                             if region_dist_squared <= radius_squared {
                                 current_regions.insert((*group_id, region.config_id));
+                                if let Some(vision_type_list) = &region.vision_type_list {
+                                    region_vision_types.extend(vision_type_list);
+                                }
                             }
                         }
                     }
@@ -323,5 +420,129 @@ pub fn handle_player_move_for_region_entry(
         }
 
         *player_regions = current_regions;
+
+        let cur_region_vision_types = group_load_manager.player_region_vision_types.get(&uid);
+        let region_vision_changed = if region_vision_types.is_empty() {
+            cur_region_vision_types.is_some()
+        } else {
+            cur_region_vision_types != Some(&region_vision_types)
+        };
+        if region_vision_changed {
+            tracing::debug!(
+                "Player {} region vision types {:?}",
+                uid,
+                region_vision_types
+            );
+            if region_vision_types.is_empty() {
+                group_load_manager.player_region_vision_types.remove(&uid);
+            } else {
+                group_load_manager
+                    .player_region_vision_types
+                    .insert(uid, region_vision_types);
+            }
+            // reload groups on the next move instead of waiting for the load cd
+            group_load_manager.last_load.remove(&uid);
+        }
+    }
+}
+
+pub fn handle_script_command_group_vision_event(
+    mut ev_reader: MessageReader<ScriptCommandEvent>,
+    mut group_load_manager: ResMut<GroupLoadManager>,
+) {
+    for event in ev_reader.read() {
+        match &event.command {
+            ScriptCommand::SetPlayerGroupVisionType {
+                uid_list,
+                vision_type_list,
+            } => {
+                tracing::debug!(
+                    "[Script] [SetPlayerGroupVisionType] uid_list={:?} type_list={:?}",
+                    uid_list,
+                    vision_type_list
+                );
+                for uid in uid_list.iter() {
+                    group_load_manager
+                        .player_vision_types
+                        .insert(*uid, vision_type_list.iter().copied().collect());
+                    group_load_manager.last_load.remove(uid);
+                }
+            }
+
+            ScriptCommand::AddPlayerGroupVisionType {
+                uid_list,
+                vision_type_list,
+            } => {
+                tracing::debug!(
+                    "[Script] [AddPlayerGroupVisionType] uid_list={:?} type_list={:?}",
+                    uid_list,
+                    vision_type_list
+                );
+                for uid in uid_list.iter() {
+                    group_load_manager
+                        .player_vision_types
+                        .entry(*uid)
+                        .or_default()
+                        .extend(vision_type_list);
+                    group_load_manager.last_load.remove(uid);
+                }
+            }
+
+            ScriptCommand::DelPlayerGroupVisionType {
+                uid_list,
+                vision_type_list,
+            } => {
+                tracing::debug!(
+                    "[Script] [DelPlayerGroupVisionType] uid_list={:?} type_list={:?}",
+                    uid_list,
+                    vision_type_list
+                );
+                for uid in uid_list.iter() {
+                    let Some(vision_types) = group_load_manager.player_vision_types.get_mut(uid)
+                    else {
+                        continue;
+                    };
+                    vision_types.retain(|vision_type| !vision_type_list.contains(vision_type));
+                    group_load_manager.last_load.remove(uid);
+                }
+            }
+
+            ScriptCommand::ForbidPlayerRegionVision { uid } => {
+                tracing::debug!("[Script] [ForbidPlayerRegionVision] uid={}", uid);
+                group_load_manager.region_vision_forbidden.insert(*uid);
+                group_load_manager.last_load.remove(uid);
+            }
+
+            ScriptCommand::RevertPlayerRegionVision { uid } => {
+                tracing::debug!("[Script] [RevertPlayerRegionVision] uid={}", uid);
+                group_load_manager.region_vision_forbidden.remove(uid);
+                group_load_manager.last_load.remove(uid);
+            }
+
+            _ => {}
+        }
+    }
+}
+
+// the tag itself is persisted by the map crate, this only lets the next move re-run loading
+pub fn handle_level_tag_change_for_group_loading(
+    mut events: MessageReader<LevelTagChangeEvent>,
+    mut group_load_manager: ResMut<GroupLoadManager>,
+    mut lua_trigger_events: MessageWriter<LuaTriggerEvent>,
+) {
+    for event in events.read() {
+        tracing::debug!("level tag {} changed, reloading groups", event.level_tag_id);
+        group_load_manager.last_load.clear();
+
+        for group_id in group_load_manager.groups.iter() {
+            lua_trigger_events.write(LuaTriggerEvent {
+                group_id: *group_id,
+                event_type: EventType::EventLevelTagChange,
+                evt: nod_krai_gi_data::scene::LuaEvt {
+                    param1: event.level_tag_id,
+                    ..Default::default()
+                },
+            });
+        }
     }
 }
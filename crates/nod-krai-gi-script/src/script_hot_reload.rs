@@ -0,0 +1,118 @@
+use crate::script_lua_vm::reload_gadget_script;
+use nod_krai_gi_data::scene::script_cache::{reload_scene_block, reload_scene_group};
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const LUA_ROOT: &str = "./assets/lua";
+
+enum LuaScriptKind {
+    Gadget,
+    SceneBlock { scene_id: u32, block_id: u32 },
+    SceneGroup { scene_id: u32, group_id: u32 },
+    Common,
+}
+
+// watches assets/lua and re-parses changed scripts into the script cache
+pub fn init() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!("lua hot reload watcher init fail: {}", err);
+            return;
+        }
+    };
+
+    if let Err(err) = watcher.watch(Path::new(LUA_ROOT), RecursiveMode::Recursive) {
+        tracing::error!("lua hot reload watch {} fail: {}", LUA_ROOT, err);
+        return;
+    }
+
+    let debounce_window = Duration::from_millis(300);
+    let mut pending_paths: HashSet<PathBuf> = HashSet::new();
+    let mut last_event_time = Instant::now();
+
+    loop {
+        std::thread::sleep(Duration::from_millis(30));
+
+        while let Ok(event) = rx.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            if event.kind.is_modify() || event.kind.is_create() {
+                pending_paths.extend(event.paths);
+                last_event_time = Instant::now();
+            }
+        }
+
+        if !pending_paths.is_empty() && last_event_time.elapsed() >= debounce_window {
+            for path in pending_paths.drain() {
+                handle_lua_file_changed(&path);
+            }
+        }
+    }
+}
+
+fn classify(path: &Path) -> Option<LuaScriptKind> {
+    if path.extension().and_then(|s| s.to_str()) != Some("lua") {
+        return None;
+    }
+    let file_stem = path.file_stem()?.to_str()?;
+    let parent = path.parent()?.file_name()?.to_str()?;
+
+    if parent == "gadget" {
+        return Some(LuaScriptKind::Gadget);
+    }
+
+    if let Some(rest) = file_stem.strip_prefix("scene") {
+        if let Some((scene_id, block_id)) = rest.split_once("_block") {
+            return Some(LuaScriptKind::SceneBlock {
+                scene_id: scene_id.parse().ok()?,
+                block_id: block_id.parse().ok()?,
+            });
+        }
+        if let Some((scene_id, group_id)) = rest.split_once("_group") {
+            return Some(LuaScriptKind::SceneGroup {
+                scene_id: scene_id.parse().ok()?,
+                group_id: group_id.parse().ok()?,
+            });
+        }
+    }
+
+    path.components()
+        .any(|c| c.as_os_str() == "common")
+        .then_some(LuaScriptKind::Common)
+}
+
+fn handle_lua_file_changed(path: &Path) {
+    if !path.is_file() {
+        return;
+    }
+    let Some(kind) = classify(path) else {
+        return;
+    };
+
+    let result = match kind {
+        LuaScriptKind::Gadget => reload_gadget_script(path),
+        LuaScriptKind::SceneBlock { scene_id, block_id } => {
+            reload_scene_block(&format!("{}/scene", LUA_ROOT), scene_id, block_id)
+        }
+        LuaScriptKind::SceneGroup { scene_id, group_id } => {
+            reload_scene_group(LUA_ROOT, scene_id, group_id).map(|_| ())
+        }
+        LuaScriptKind::Common => {
+            tracing::warn!(
+                "{:?} changed, common lua modules are only loaded at startup",
+                path
+            );
+            return;
+        }
+    };
+
+    match result {
+        Ok(()) => tracing::info!("lua hot reload {:?}", path),
+        Err(err) => tracing::error!("lua hot reload {:?} fail: {}", path, err),
+    }
+}
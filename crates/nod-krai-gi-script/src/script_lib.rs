@@ -1,8 +1,8 @@
 use bevy_ecs::prelude::*;
 use crossbeam_queue::SegQueue;
 use mlua::{Function, Table};
-pub(crate) use nod_krai_gi_data::scene::{LuaContext, LuaEvt, ScriptCommand};
-use std::collections::HashMap;
+pub(crate) use nod_krai_gi_data::scene::{LuaContext, ScriptCommand};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
 #[allow(unused)]
@@ -26,6 +26,22 @@ pub trait ScriptLib: Send + Sync + 'static {
 
     fn get_group_monster_count_by_config_id(&self, uid: u32, group_id: u32) -> i32;
 
+    // monster hp
+    fn get_monster_hp(&self, uid: u32, group_id: u32, config_id: u32) -> i32;
+    fn get_monster_hp_percent(&self, uid: u32, group_id: u32, config_id: u32) -> i32;
+    fn set_monster_hp(&self, group_id: u32, config_id: u32, hp_percent: f32);
+    fn set_gadget_hp(&self, group_id: u32, config_id: u32, hp_percent: f32);
+    fn lock_monster_hp(&self, group_id: u32, config_id: u32);
+    fn unlock_monster_hp(&self, group_id: u32, config_id: u32);
+    fn get_monster_id_by_entity_id(&self, uid: u32, entity_id: u32) -> i32;
+    fn get_gadget_id_by_entity_id(&self, uid: u32, entity_id: u32) -> i32;
+
+    // monster ai
+    fn get_monster_ai_state(&self, uid: u32, group_id: u32, config_id: u32) -> i32;
+    fn get_monster_aggro_target(&self, uid: u32, group_id: u32, config_id: u32) -> u32;
+    fn get_monster_aggro_list(&self, uid: u32, group_id: u32, config_id: u32) -> Vec<u32>;
+    fn get_monster_pose(&self, uid: u32, group_id: u32, config_id: u32) -> u32;
+
     // challenge methods
     fn active_challenge(
         &self,
@@ -38,6 +54,7 @@ pub trait ScriptLib: Send + Sync + 'static {
         param3: u32,
         param4: u32,
     );
+    fn pause_challenge(&self, group_id: u32, challenge_index: u32);
     fn stop_challenge(&self, group_id: u32, challenge_index: u32, is_success: bool);
     fn add_challenge_progress(&self, group_id: u32, challenge_index: u32, progress: u32);
 
@@ -47,7 +64,28 @@ pub trait ScriptLib: Send + Sync + 'static {
     // entity creation/removal
     fn create_gadget(&self, group_id: u32, config_id: u32);
     fn create_monster(&self, group_id: u32, config_id: u32);
+    fn create_gadget_with_params(&self, group_id: u32, config_id: u32, pos: Option<(f32, f32, f32)>, rot: Option<(f32, f32, f32)>, server_global_values: Vec<(String, f32)>);
+    fn create_monster_with_params(&self, group_id: u32, config_id: u32, pos: Option<(f32, f32, f32)>, rot: Option<(f32, f32, f32)>, server_global_values: Vec<(String, f32)>);
     fn kill_entity_by_config_id(&self, group_id: u32, config_id: u32);
+    fn remove_entity_by_config_id(&self, group_id: u32, entity_type: u32, config_id: u32);
+
+    // scene points
+    fn unlock_scene_point(&self, scene_id: u32, point_id: u32);
+    fn lock_scene_point(&self, scene_id: u32, point_id: u32);
+    fn hide_scene_point(&self, scene_id: u32, point_id: u32);
+    fn unhide_scene_point(&self, scene_id: u32, point_id: u32);
+
+    // scene tags
+    fn add_scene_tag(&self, scene_id: u32, scene_tag_id: u32);
+    fn del_scene_tag(&self, scene_id: u32, scene_tag_id: u32);
+    fn change_to_target_level_tag(&self, level_tag_id: u32);
+
+    // group vision
+    fn set_player_group_vision_type(&self, uid_list: Vec<u32>, vision_type_list: Vec<u32>);
+    fn add_player_group_vision_type(&self, uid_list: Vec<u32>, vision_type_list: Vec<u32>);
+    fn del_player_group_vision_type(&self, uid_list: Vec<u32>, vision_type_list: Vec<u32>);
+    fn forbid_player_region_vision(&self, uid: u32);
+    fn revert_player_region_vision(&self, uid: u32);
 
     // skill control
     fn set_is_allow_use_skill(&self, allow: bool);
@@ -89,18 +127,44 @@ pub trait ScriptLib: Send + Sync + 'static {
 
     // suite switching
     fn go_to_group_suite(&self, group_id: u32, suite_id: u32);
+    fn go_back_group_suite(&self, group_id: u32);
+    fn kill_extra_group_suite(&self, group_id: u32, suite_id: u32);
     fn get_group_suite(&self, group_id: u32) -> u32;
 
+    // flow suites
+    fn set_flow_suite(&self, group_id: u32, suite_id: u32);
+    fn go_to_flow_suite(&self, group_id: u32, suite_id: u32);
+    fn add_extra_flow_suite(&self, group_id: u32, suite_id: u32, policy: u32);
+    fn remove_extra_flow_suite(&self, group_id: u32, suite_id: u32, policy: u32);
+    fn kill_extra_flow_suite(&self, group_id: u32, suite_id: u32, policy: u32);
+
     // cross-group variable access
     fn set_group_variable_value_by_group(&self, group_id: u32, name: &str, value: i32) -> i32;
     fn change_group_variable_value_by_group(&self, group_id: u32, name: &str, delta: i32) -> i32;
 
+    // temp values live until the world is closed, logic state values are saved
+    fn get_group_temp_value(&self, group_id: u32, key: &str) -> i32;
+    fn set_group_temp_value(&self, group_id: u32, key: &str, value: i32) -> i32;
+    fn change_group_temp_value(&self, group_id: u32, key: &str, delta: i32) -> i32;
+    fn get_group_logic_state_value(&self, group_id: u32, name: &str) -> i32;
+    fn set_group_logic_state_value(&self, group_id: u32, name: &str, value: i32) -> i32;
+
+    // dead groups are never loaded until recovered
+    fn is_group_dead(&self, group_id: u32) -> bool;
+    fn set_group_dead(&self, group_id: u32);
+    fn recover_dead_group(&self, group_id: u32);
+
     // timer
     fn create_group_timer_event(&self, group_id: u32, source: &str, time: f64);
     fn cancel_group_timer_event(&self, group_id: u32, source: &str);
-    fn init_time_axis(&self, identifier: &str, delays: Vec<f64>, should_loop: bool);
-    fn end_time_axis(&self, identifier: &str);
-    fn end_all_time_axis(&self);
+    fn init_time_axis(&self, group_id: u32, identifier: &str, delays: Vec<f64>, should_loop: bool);
+    fn end_time_axis(&self, group_id: u32, identifier: &str);
+    fn end_all_time_axis(&self, group_id: u32);
+    fn pause_time_axis(&self, group_id: u32, identifier: &str);
+    fn continue_time_axis(&self, group_id: u32, identifier: &str);
+
+    // triggers
+    fn create_group_trigger(&self, group_id: u32, trigger_name: &str);
 
     // teleport
     fn trans_player_to_pos(&self, uid_list: Vec<u32>, pos: (f32, f32, f32), rot: (f32, f32, f32), scene_id: u32, radius: f32);
@@ -111,17 +175,144 @@ pub trait ScriptLib: Send + Sync + 'static {
 
     // monster tide
     fn auto_monster_tide(&self, group_id: u32, source_id: u32, orders_config_id: Vec<u32>, tide_count: u32, scene_limit: u32);
+    fn auto_pool_monster_tide(&self, group_id: u32, source_id: u32, pool_id_list: Vec<u32>, point_config_id_list: Vec<u32>, total_count: u32, min_count: u32, max_count: u32, tag: String);
+    fn end_monster_tide(&self, group_id: u32, source_id: u32, end_type: u32);
+    fn kill_monster_tide(&self, group_id: u32, source_id: u32);
+    fn pause_monster_tide(&self, group_id: u32, source_id: u32);
+    fn continue_monster_tide(&self, group_id: u32, source_id: u32);
+    fn create_monsters_from_monster_pool(&self, group_id: u32, tag: String);
 }
 
 pub struct GroupVariableStore {
     // group_id -> (variable_name -> value)
     pub variables: RwLock<HashMap<u32, HashMap<String, i32>>>,
+    // group_id -> (key -> value), kept across group unload but never saved
+    pub temp_values: RwLock<HashMap<u32, HashMap<String, i32>>>,
+    // group_id -> (name -> value), saved in the owner's scene bin
+    pub logic_state_values: RwLock<HashMap<u32, HashMap<String, i32>>>,
+    pub dead_groups: RwLock<HashSet<u32>>,
 }
 
 impl GroupVariableStore {
     pub fn new() -> Self {
         Self {
             variables: RwLock::new(HashMap::new()),
+            temp_values: RwLock::new(HashMap::new()),
+            logic_state_values: RwLock::new(HashMap::new()),
+            dead_groups: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn get_temp_value(&self, group_id: u32, key: &str) -> i32 {
+        match self.temp_values.read() {
+            Ok(store) => store
+                .get(&group_id)
+                .and_then(|values| values.get(key))
+                .copied()
+                .unwrap_or(0),
+            Err(_) => {
+                eprintln!("Failed to lock temp value store");
+                -1
+            }
+        }
+    }
+
+    pub fn set_temp_value(&self, group_id: u32, key: &str, value: i32) -> i32 {
+        match self.temp_values.write() {
+            Ok(mut store) => {
+                store
+                    .entry(group_id)
+                    .or_default()
+                    .insert(key.to_string(), value);
+                0
+            }
+            Err(_) => {
+                eprintln!("Failed to lock temp value store");
+                -1
+            }
+        }
+    }
+
+    pub fn change_temp_value(&self, group_id: u32, key: &str, delta: i32) -> i32 {
+        match self.temp_values.write() {
+            Ok(mut store) => {
+                *store
+                    .entry(group_id)
+                    .or_default()
+                    .entry(key.to_string())
+                    .or_default() += delta;
+                0
+            }
+            Err(_) => {
+                eprintln!("Failed to lock temp value store");
+                -1
+            }
+        }
+    }
+
+    pub fn init_logic_state_values(&self, group_id: u32, values: HashMap<String, i32>) {
+        match self.logic_state_values.write() {
+            Ok(mut store) => {
+                store.insert(group_id, values);
+            }
+            Err(_) => {
+                eprintln!("Failed to lock logic state store");
+            }
+        }
+    }
+
+    pub fn get_logic_state_value(&self, group_id: u32, name: &str) -> i32 {
+        match self.logic_state_values.read() {
+            Ok(store) => store
+                .get(&group_id)
+                .and_then(|values| values.get(name))
+                .copied()
+                .unwrap_or(0),
+            Err(_) => {
+                eprintln!("Failed to lock logic state store");
+                -1
+            }
+        }
+    }
+
+    pub fn set_logic_state_value(&self, group_id: u32, name: &str, value: i32) -> i32 {
+        match self.logic_state_values.write() {
+            Ok(mut store) => {
+                store
+                    .entry(group_id)
+                    .or_default()
+                    .insert(name.to_string(), value);
+                0
+            }
+            Err(_) => {
+                eprintln!("Failed to lock logic state store");
+                -1
+            }
+        }
+    }
+
+    pub fn is_group_dead(&self, group_id: u32) -> bool {
+        match self.dead_groups.read() {
+            Ok(dead_groups) => dead_groups.contains(&group_id),
+            Err(_) => {
+                eprintln!("Failed to lock dead group store");
+                false
+            }
+        }
+    }
+
+    pub fn set_group_dead(&self, group_id: u32, is_dead: bool) {
+        match self.dead_groups.write() {
+            Ok(mut dead_groups) => {
+                if is_dead {
+                    dead_groups.insert(group_id);
+                } else {
+                    dead_groups.remove(&group_id);
+                }
+            }
+            Err(_) => {
+                eprintln!("Failed to lock dead group store");
+            }
         }
     }
 
@@ -136,6 +327,16 @@ impl GroupVariableStore {
         }
     }
 
+    pub fn get_group_variables(&self, group_id: u32) -> HashMap<String, i32> {
+        match self.variables.read() {
+            Ok(store) => store.get(&group_id).cloned().unwrap_or_default(),
+            Err(_) => {
+                eprintln!("Failed to lock variables store");
+                HashMap::new()
+            }
+        }
+    }
+
     pub fn remove_group(&self, group_id: u32) {
         match self.variables.write() {
             Ok(mut store) => {
@@ -294,6 +495,116 @@ impl ScriptLib for BevyScriptLib {
             .get_alive_monster_count(uid, group_id) as i32
     }
 
+    fn get_monster_hp(&self, uid: u32, group_id: u32, config_id: u32) -> i32 {
+        nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+            .get_monster_state(uid, group_id, config_id)
+            .map(|s| s.cur_hp as i32)
+            .unwrap_or(-1)
+    }
+
+    fn get_monster_hp_percent(&self, uid: u32, group_id: u32, config_id: u32) -> i32 {
+        nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+            .get_monster_state(uid, group_id, config_id)
+            .filter(|s| s.max_hp > 0.0)
+            .map(|s| {
+                nod_krai_gi_data::scene::group_entity_state_cache::hp_percent(s.cur_hp, s.max_hp)
+                    as i32
+            })
+            .unwrap_or(-1)
+    }
+
+    fn set_monster_hp(&self, group_id: u32, config_id: u32, hp_percent: f32) {
+        self.queue.push(ScriptCommand::SetMonsterHp {
+            group_id,
+            config_id,
+            hp_percent,
+        });
+    }
+
+    fn set_gadget_hp(&self, group_id: u32, config_id: u32, hp_percent: f32) {
+        self.queue.push(ScriptCommand::SetGadgetHp {
+            group_id,
+            config_id,
+            hp_percent,
+        });
+    }
+
+    fn lock_monster_hp(&self, group_id: u32, config_id: u32) {
+        self.queue.push(ScriptCommand::LockMonsterHp {
+            group_id,
+            config_id,
+        });
+    }
+
+    fn unlock_monster_hp(&self, group_id: u32, config_id: u32) {
+        self.queue.push(ScriptCommand::UnlockMonsterHp {
+            group_id,
+            config_id,
+        });
+    }
+
+    fn get_monster_id_by_entity_id(&self, uid: u32, entity_id: u32) -> i32 {
+        let Some((group_id, state)) =
+            nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+                .find_monster_by_entity_id(uid, entity_id)
+        else {
+            return -1;
+        };
+        with_group_template(group_id, |template| {
+            template
+                .monsters
+                .iter()
+                .find(|m| m.config_id == state.config_id)
+                .map(|m| m.monster_id as i32)
+        })
+        .unwrap_or(-1)
+    }
+
+    fn get_gadget_id_by_entity_id(&self, uid: u32, entity_id: u32) -> i32 {
+        let Some((group_id, state)) =
+            nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+                .find_gadget_by_entity_id(uid, entity_id)
+        else {
+            return -1;
+        };
+        with_group_template(group_id, |template| {
+            template
+                .gadgets
+                .iter()
+                .find(|g| g.config_id == state.config_id)
+                .map(|g| g.gadget_id as i32)
+        })
+        .unwrap_or(-1)
+    }
+
+    fn get_monster_ai_state(&self, uid: u32, group_id: u32, config_id: u32) -> i32 {
+        nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+            .get_monster_state(uid, group_id, config_id)
+            .map(|s| s.ai_state as i32)
+            .unwrap_or(-1)
+    }
+
+    fn get_monster_aggro_target(&self, uid: u32, group_id: u32, config_id: u32) -> u32 {
+        nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+            .get_monster_state(uid, group_id, config_id)
+            .map(|s| s.aggro_target_uid)
+            .unwrap_or_default()
+    }
+
+    fn get_monster_aggro_list(&self, uid: u32, group_id: u32, config_id: u32) -> Vec<u32> {
+        nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+            .get_monster_state(uid, group_id, config_id)
+            .map(|s| s.aggro_uid_list)
+            .unwrap_or_default()
+    }
+
+    fn get_monster_pose(&self, uid: u32, group_id: u32, config_id: u32) -> u32 {
+        nod_krai_gi_data::scene::group_entity_state_cache::get_group_entity_state_cache()
+            .get_monster_state(uid, group_id, config_id)
+            .map(|s| s.pose_id)
+            .unwrap_or_default()
+    }
+
     fn active_challenge(
         &self,
         group_id: u32,
@@ -317,6 +628,13 @@ impl ScriptLib for BevyScriptLib {
         });
     }
 
+    fn pause_challenge(&self, group_id: u32, challenge_index: u32) {
+        self.queue.push(ScriptCommand::PauseChallenge {
+            group_id,
+            challenge_index,
+        });
+    }
+
     fn stop_challenge(&self, group_id: u32, challenge_index: u32, is_success: bool) {
         self.queue.push(ScriptCommand::StopChallenge {
             group_id,
@@ -341,17 +659,77 @@ impl ScriptLib for BevyScriptLib {
     }
 
     fn create_gadget(&self, group_id: u32, config_id: u32) {
-        self.queue.push(ScriptCommand::CreateGadget { group_id, config_id });
+        self.create_gadget_with_params(group_id, config_id, None, None, Vec::new());
     }
 
     fn create_monster(&self, group_id: u32, config_id: u32) {
-        self.queue.push(ScriptCommand::CreateMonster { group_id, config_id });
+        self.create_monster_with_params(group_id, config_id, None, None, Vec::new());
+    }
+
+    fn create_gadget_with_params(&self, group_id: u32, config_id: u32, pos: Option<(f32, f32, f32)>, rot: Option<(f32, f32, f32)>, server_global_values: Vec<(String, f32)>) {
+        self.queue.push(ScriptCommand::CreateGadget { group_id, config_id, pos, rot, server_global_values });
+    }
+
+    fn create_monster_with_params(&self, group_id: u32, config_id: u32, pos: Option<(f32, f32, f32)>, rot: Option<(f32, f32, f32)>, server_global_values: Vec<(String, f32)>) {
+        self.queue.push(ScriptCommand::CreateMonster { group_id, config_id, pos, rot, server_global_values });
     }
 
     fn kill_entity_by_config_id(&self, group_id: u32, config_id: u32) {
         self.queue.push(ScriptCommand::KillEntityByConfigId { group_id, config_id });
     }
 
+    fn remove_entity_by_config_id(&self, group_id: u32, entity_type: u32, config_id: u32) {
+        self.queue.push(ScriptCommand::RemoveEntityByConfigId { group_id, entity_type, config_id });
+    }
+
+    fn unlock_scene_point(&self, scene_id: u32, point_id: u32) {
+        self.queue.push(ScriptCommand::UnlockScenePoint { scene_id, point_id });
+    }
+
+    fn lock_scene_point(&self, scene_id: u32, point_id: u32) {
+        self.queue.push(ScriptCommand::LockScenePoint { scene_id, point_id });
+    }
+
+    fn hide_scene_point(&self, scene_id: u32, point_id: u32) {
+        self.queue.push(ScriptCommand::HideScenePoint { scene_id, point_id });
+    }
+
+    fn unhide_scene_point(&self, scene_id: u32, point_id: u32) {
+        self.queue.push(ScriptCommand::UnhideScenePoint { scene_id, point_id });
+    }
+
+    fn add_scene_tag(&self, scene_id: u32, scene_tag_id: u32) {
+        self.queue.push(ScriptCommand::AddSceneTag { scene_id, scene_tag_id });
+    }
+
+    fn del_scene_tag(&self, scene_id: u32, scene_tag_id: u32) {
+        self.queue.push(ScriptCommand::DelSceneTag { scene_id, scene_tag_id });
+    }
+
+    fn change_to_target_level_tag(&self, level_tag_id: u32) {
+        self.queue.push(ScriptCommand::ChangeToTargetLevelTag { level_tag_id });
+    }
+
+    fn set_player_group_vision_type(&self, uid_list: Vec<u32>, vision_type_list: Vec<u32>) {
+        self.queue.push(ScriptCommand::SetPlayerGroupVisionType { uid_list, vision_type_list });
+    }
+
+    fn add_player_group_vision_type(&self, uid_list: Vec<u32>, vision_type_list: Vec<u32>) {
+        self.queue.push(ScriptCommand::AddPlayerGroupVisionType { uid_list, vision_type_list });
+    }
+
+    fn del_player_group_vision_type(&self, uid_list: Vec<u32>, vision_type_list: Vec<u32>) {
+        self.queue.push(ScriptCommand::DelPlayerGroupVisionType { uid_list, vision_type_list });
+    }
+
+    fn forbid_player_region_vision(&self, uid: u32) {
+        self.queue.push(ScriptCommand::ForbidPlayerRegionVision { uid });
+    }
+
+    fn revert_player_region_vision(&self, uid: u32) {
+        self.queue.push(ScriptCommand::RevertPlayerRegionVision { uid });
+    }
+
     fn set_is_allow_use_skill(&self, allow: bool) {
         self.queue.push(ScriptCommand::SetIsAllowUseSkill { allow });
     }
@@ -428,11 +806,78 @@ impl ScriptLib for BevyScriptLib {
         self.queue.push(ScriptCommand::GoToGroupSuite { group_id, suite_id });
     }
 
+    fn go_back_group_suite(&self, group_id: u32) {
+        self.queue.push(ScriptCommand::GoBackGroupSuite { group_id });
+    }
+
+    fn kill_extra_group_suite(&self, group_id: u32, suite_id: u32) {
+        self.queue.push(ScriptCommand::KillExtraGroupSuite { group_id, suite_id });
+    }
+
+    fn get_group_temp_value(&self, group_id: u32, key: &str) -> i32 {
+        self.variable_store.get_temp_value(group_id, key)
+    }
+
+    fn set_group_temp_value(&self, group_id: u32, key: &str, value: i32) -> i32 {
+        self.variable_store.set_temp_value(group_id, key, value)
+    }
+
+    fn change_group_temp_value(&self, group_id: u32, key: &str, delta: i32) -> i32 {
+        self.variable_store.change_temp_value(group_id, key, delta)
+    }
+
+    fn get_group_logic_state_value(&self, group_id: u32, name: &str) -> i32 {
+        self.variable_store.get_logic_state_value(group_id, name)
+    }
+
+    fn set_group_logic_state_value(&self, group_id: u32, name: &str, value: i32) -> i32 {
+        self.queue.push(ScriptCommand::SetGroupLogicStateValue {
+            group_id,
+            name: name.to_string(),
+            value,
+        });
+        self.variable_store.set_logic_state_value(group_id, name, value)
+    }
+
+    fn is_group_dead(&self, group_id: u32) -> bool {
+        self.variable_store.is_group_dead(group_id)
+    }
+
+    fn set_group_dead(&self, group_id: u32) {
+        self.variable_store.set_group_dead(group_id, true);
+        self.queue.push(ScriptCommand::SetGroupDead { group_id });
+    }
+
+    fn recover_dead_group(&self, group_id: u32) {
+        self.variable_store.set_group_dead(group_id, false);
+        self.queue.push(ScriptCommand::RecoverDeadGroup { group_id });
+    }
+
     fn get_group_suite(&self, _group_id: u32) -> u32 {
         // TODO: read from group registry
         0
     }
 
+    fn set_flow_suite(&self, group_id: u32, suite_id: u32) {
+        self.queue.push(ScriptCommand::SetFlowSuite { group_id, suite_id });
+    }
+
+    fn go_to_flow_suite(&self, group_id: u32, suite_id: u32) {
+        self.queue.push(ScriptCommand::GoToFlowSuite { group_id, suite_id });
+    }
+
+    fn add_extra_flow_suite(&self, group_id: u32, suite_id: u32, policy: u32) {
+        self.queue.push(ScriptCommand::AddExtraFlowSuite { group_id, suite_id, policy });
+    }
+
+    fn remove_extra_flow_suite(&self, group_id: u32, suite_id: u32, policy: u32) {
+        self.queue.push(ScriptCommand::RemoveExtraFlowSuite { group_id, suite_id, policy });
+    }
+
+    fn kill_extra_flow_suite(&self, group_id: u32, suite_id: u32, policy: u32) {
+        self.queue.push(ScriptCommand::KillExtraFlowSuite { group_id, suite_id, policy });
+    }
+
     fn set_group_variable_value_by_group(&self, group_id: u32, name: &str, value: i32) -> i32 {
         self.variable_store.set_variable(group_id, name, value);
         self.queue.push(ScriptCommand::SetGroupVariableValueByGroup {
@@ -468,22 +913,45 @@ impl ScriptLib for BevyScriptLib {
         });
     }
 
-    fn init_time_axis(&self, identifier: &str, delays: Vec<f64>, should_loop: bool) {
+    fn init_time_axis(&self, group_id: u32, identifier: &str, delays: Vec<f64>, should_loop: bool) {
         self.queue.push(ScriptCommand::InitTimeAxis {
+            group_id,
             identifier: identifier.to_string(),
             delays,
             should_loop,
         });
     }
 
-    fn end_time_axis(&self, identifier: &str) {
+    fn end_time_axis(&self, group_id: u32, identifier: &str) {
         self.queue.push(ScriptCommand::EndTimeAxis {
+            group_id,
             identifier: identifier.to_string(),
         });
     }
 
-    fn end_all_time_axis(&self) {
-        self.queue.push(ScriptCommand::EndAllTimeAxis);
+    fn end_all_time_axis(&self, group_id: u32) {
+        self.queue.push(ScriptCommand::EndAllTimeAxis { group_id });
+    }
+
+    fn pause_time_axis(&self, group_id: u32, identifier: &str) {
+        self.queue.push(ScriptCommand::PauseTimeAxis {
+            group_id,
+            identifier: identifier.to_string(),
+        });
+    }
+
+    fn continue_time_axis(&self, group_id: u32, identifier: &str) {
+        self.queue.push(ScriptCommand::ContinueTimeAxis {
+            group_id,
+            identifier: identifier.to_string(),
+        });
+    }
+
+    fn create_group_trigger(&self, group_id: u32, trigger_name: &str) {
+        self.queue.push(ScriptCommand::CreateGroupTrigger {
+            group_id,
+            trigger_name: trigger_name.to_string(),
+        });
     }
 
     fn trans_player_to_pos(&self, uid_list: Vec<u32>, pos: (f32, f32, f32), rot: (f32, f32, f32), scene_id: u32, radius: f32) {
@@ -501,12 +969,45 @@ impl ScriptLib for BevyScriptLib {
     fn auto_monster_tide(&self, group_id: u32, source_id: u32, orders_config_id: Vec<u32>, tide_count: u32, scene_limit: u32) {
         self.queue.push(ScriptCommand::AutoMonsterTide { group_id, source_id, orders_config_id, tide_count, scene_limit });
     }
+
+    fn auto_pool_monster_tide(&self, group_id: u32, source_id: u32, pool_id_list: Vec<u32>, point_config_id_list: Vec<u32>, total_count: u32, min_count: u32, max_count: u32, tag: String) {
+        self.queue.push(ScriptCommand::AutoPoolMonsterTide { group_id, source_id, pool_id_list, point_config_id_list, total_count, min_count, max_count, tag });
+    }
+
+    fn end_monster_tide(&self, group_id: u32, source_id: u32, end_type: u32) {
+        self.queue.push(ScriptCommand::EndMonsterTide { group_id, source_id, end_type });
+    }
+
+    fn kill_monster_tide(&self, group_id: u32, source_id: u32) {
+        self.queue.push(ScriptCommand::KillMonsterTide { group_id, source_id });
+    }
+
+    fn pause_monster_tide(&self, group_id: u32, source_id: u32) {
+        self.queue.push(ScriptCommand::PauseMonsterTide { group_id, source_id });
+    }
+
+    fn continue_monster_tide(&self, group_id: u32, source_id: u32) {
+        self.queue.push(ScriptCommand::ContinueMonsterTide { group_id, source_id });
+    }
+
+    fn create_monsters_from_monster_pool(&self, group_id: u32, tag: String) {
+        self.queue.push(ScriptCommand::CreateMonstersFromMonsterPool { group_id, tag });
+    }
+}
+
+fn with_group_template<T>(
+    group_id: u32,
+    f: impl FnOnce(&nod_krai_gi_data::scene::scene_group_template::SceneGroupTemplate) -> Option<T>,
+) -> Option<T> {
+    let collection = nod_krai_gi_data::scene::script_cache::SCENE_GROUP_COLLECTION.get()?;
+    let template = collection.get(&group_id)?;
+    template.value().as_ref().and_then(f)
 }
 
 pub fn call_lua_trigger_condition(
     func: &Function,
-    context: LuaContext,
-    evt: LuaEvt,
+    context: &Table,
+    evt: &Table,
 ) -> mlua::Result<bool> {
     match func.call((context, evt)) {
         Ok(ret) => Ok(ret),
@@ -519,8 +1020,8 @@ pub fn call_lua_trigger_condition(
 
 pub fn call_lua_trigger_action(
     func: &Function,
-    context: LuaContext,
-    evt: LuaEvt,
+    context: &Table,
+    evt: &Table,
 ) -> mlua::Result<i32> {
     match func.call((context, evt)) {
         Ok(ret) => Ok(ret),
@@ -553,6 +1054,7 @@ pub fn call_lua_on_be_hurt(
     param1: u32,
     param2: u32,
     param3: bool,
+    param4: u32,
 ) -> mlua::Result<Option<bool>> {
-    func.call((context, param1, param2, param3))
+    func.call((context, param1, param2, param3, param4))
 }
@@ -174,9 +174,9 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "AddExtraFlowSuite",
-            |_, _this, (_ctx, _group_id, _suite_index, _policy): (Table, u32, u32, u32)| {
-                tracing::debug!("AddExtraFlowSuite called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, suite_index, policy): (Table, u32, u32, u32)| {
+                this.script_lib.add_extra_flow_suite(group_id, suite_index, policy);
+                Ok(0)
             },
         );
 
@@ -243,9 +243,12 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "AddPlayerGroupVisionType",
-            |_, _this, (_ctx, _uid_list, _type_list): (Table, Table, Table)| {
+            |_, this, (_ctx, uid_list, type_list): (Table, Table, Table)| {
                 tracing::debug!("AddPlayerGroupVisionType called");
-                Ok(-1)
+                let uid_list: Vec<u32> = uid_list.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                let vision_type_list: Vec<u32> = type_list.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                this.script_lib.add_player_group_vision_type(uid_list, vision_type_list);
+                Ok(0)
             },
         );
 
@@ -314,9 +317,10 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "AddSceneTag",
-            |_, _this, (_ctx, _scene_id, _scene_tag_id): (Table, u32, u32)| {
+            |_, this, (_ctx, scene_id, scene_tag_id): (Table, u32, u32)| {
                 tracing::debug!("AddSceneTag called");
-                Ok(-1)
+                this.script_lib.add_scene_tag(scene_id, scene_tag_id);
+                Ok(0)
             },
         );
 
@@ -392,8 +396,8 @@ impl UserData for LuaScriptLibHandle {
         methods.add_method(
             "AutoPoolMonsterTide",
             |_,
-             _this,
-             (_ctx, _param1, _param2, _param3, _param4, _param5, _param6, _param7): (
+             this,
+             (_ctx, source_id, group_id, pool_id_table, _param4, point_table, _affix_table, param_table): (
                 Table,
                 u32,
                 u32,
@@ -403,8 +407,14 @@ impl UserData for LuaScriptLibHandle {
                 Table,
                 Table,
             )| {
-                tracing::debug!("AutoPoolMonsterTide called");
-                Ok(-1)
+                let pool_id_list: Vec<u32> = pool_id_table.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                let point_config_id_list: Vec<u32> = point_table.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                let total_count: u32 = param_table.get("total_count").unwrap_or(0);
+                let min_count: u32 = param_table.get("min_count").unwrap_or(0);
+                let max_count: u32 = param_table.get("max_count").unwrap_or(0);
+                let tag: String = param_table.get::<Option<String>>("tag").ok().flatten().unwrap_or_default();
+                this.script_lib.auto_pool_monster_tide(group_id, source_id, pool_id_list, point_config_id_list, total_count, min_count, max_count, tag);
+                Ok(0)
             },
         );
 
@@ -465,9 +475,13 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "ChangeGroupTempValue",
-            |_, _this, (_ctx, _key, _delta, _param_table): (Table, String, i32, Table)| {
-                tracing::debug!("ChangeGroupTempValue called");
-                Ok(-1)
+            |_, this, (ctx, key, delta, param_table): (Table, String, i32, Table)| {
+                let group_id: u32 = param_table
+                    .get::<Option<u32>>("group_id")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(ctx.get("group_id").unwrap_or(0));
+                Ok(this.script_lib.change_group_temp_value(group_id, &key, delta))
             },
         );
 
@@ -508,17 +522,19 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "ChangeToTargetLevelTag",
-            |_, _this, (_ctx, _level_tag_id): (Table, u32)| {
+            |_, this, (_ctx, level_tag_id): (Table, u32)| {
                 tracing::debug!("ChangeToTargetLevelTag called");
-                Ok(-1)
+                this.script_lib.change_to_target_level_tag(level_tag_id);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "ChangeToTargetLevelTagWithParamTable",
-            |_, _this, (_ctx, _level_tag_id, _param_table): (Table, u32, Table)| {
+            |_, this, (_ctx, level_tag_id, _param_table): (Table, u32, Table)| {
                 tracing::debug!("ChangeToTargetLevelTagWithParamTable called");
-                Ok(-1)
+                this.script_lib.change_to_target_level_tag(level_tag_id);
+                Ok(0)
             },
         );
 
@@ -598,9 +614,9 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "ClearPoolMonsterTide",
-            |_, _this, (_ctx, _group_id, _tide_index): (Table, u32, u32)| {
-                tracing::debug!("ClearPoolMonsterTide called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index): (Table, u32, u32)| {
+                this.script_lib.kill_monster_tide(group_id, tide_index);
+                Ok(0)
             },
         );
 
@@ -614,17 +630,19 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "ContinueAutoMonster",
-            |_, _this, (_ctx, _group_id, _tide_index): (Table, u32, u32)| {
-                tracing::debug!("ContinueAutoMonster called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index): (Table, u32, u32)| {
+                this.script_lib.continue_monster_tide(group_id, tide_index);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "ContinueTimeAxis",
-            |_, _this, (_ctx, _key): (Table, String)| {
+            |_, this, (ctx, key): (Table, String)| {
                 tracing::debug!("ContinueTimeAxis called");
-                Ok(-1)
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                this.script_lib.continue_time_axis(group_id, &key);
+                Ok(0)
             },
         );
 
@@ -727,17 +745,27 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "CreateGadgetByConfigIdByPos",
-            |_, _this, (_ctx, _config_id, _pos_table, _rot_table): (Table, u32, Table, Table)| {
-                tracing::debug!("CreateGadgetByConfigIdByPos called");
-                Ok(-1)
+            |_, this, (ctx, config_id, pos_table, rot_table): (Table, u32, Table, Table)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                tracing::debug!("[ScriptLib] CreateGadgetByConfigIdByPos group={} config={}", group_id, config_id);
+                this.script_lib.create_gadget_with_params(group_id, config_id, Some(table_to_vector(&pos_table)), Some(table_to_vector(&rot_table)), Vec::new());
+                Ok(0)
             },
         );
 
         methods.add_method(
             "CreateGadgetByParamTable",
-            |_, _this, (_ctx, _param_table): (Table, Table)| {
-                tracing::debug!("CreateGadgetByParamTable called");
-                Ok(-1)
+            |_, this, (ctx, param_table): (Table, Table)| {
+                let group_id: u32 = param_table.get("group_id").unwrap_or(ctx.get("group_id").unwrap_or(0));
+                let config_id: u32 = param_table.get("config_id").unwrap_or(0);
+                tracing::debug!("[ScriptLib] CreateGadgetByParamTable group={} config={}", group_id, config_id);
+                if config_id == 0 {
+                    return Ok(-1);
+                }
+                let pos = param_table.get::<Table>("pos").ok().map(|t| table_to_vector(&t));
+                let rot = param_table.get::<Table>("rot").ok().map(|t| table_to_vector(&t));
+                this.script_lib.create_gadget_with_params(group_id, config_id, pos, rot, Vec::new());
+                Ok(0)
             },
         );
 
@@ -760,17 +788,26 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "CreateGadgetWithGlobalValue",
-            |_, _this, (_ctx, _config_id, _value_table): (Table, u32, Table)| {
-                tracing::debug!("CreateGadgetWithGlobalValue called");
-                Ok(-1)
+            |_, this, (ctx, config_id, value_table): (Table, u32, Table)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                tracing::debug!("[ScriptLib] CreateGadgetWithGlobalValue group={} config={}", group_id, config_id);
+                this.script_lib.create_gadget_with_params(group_id, config_id, None, None, table_to_global_values(&value_table));
+                Ok(0)
             },
         );
 
         methods.add_method(
             "CreateGather",
-            |_, _this, (_ctx, _p_config_table): (Table, Table)| {
-                tracing::debug!("CreateGather called");
-                Ok(-1)
+            |_, this, (ctx, p_config_table): (Table, Table)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                let config_id: u32 = p_config_table.get("config_id").unwrap_or(0);
+                tracing::debug!("[ScriptLib] CreateGather group={} config={}", group_id, config_id);
+                if config_id == 0 {
+                    return Ok(-1);
+                }
+                // gather points are group gadgets, CreateGadget resolves the gather config
+                this.script_lib.create_gadget(group_id, config_id);
+                Ok(0)
             },
         );
 
@@ -784,9 +821,11 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "CreateGroupTrigger",
-            |_, _this, (_ctx, _name): (Table, String)| {
+            |_, this, (ctx, name): (Table, String)| {
                 tracing::debug!("CreateGroupTrigger called");
-                Ok(-1)
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                this.script_lib.create_group_trigger(group_id, &name);
+                Ok(0)
             },
         );
 
@@ -809,9 +848,11 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "CreateMonsterByConfigIdByPos",
-            |_, _this, (_ctx, _config_id, _pos_table, _rot_table): (Table, u32, Table, Table)| {
-                tracing::debug!("CreateMonsterByConfigIdByPos called");
-                Ok(-1)
+            |_, this, (ctx, config_id, pos_table, rot_table): (Table, u32, Table, Table)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                tracing::debug!("[ScriptLib] CreateMonsterByConfigIdByPos group={} config={}", group_id, config_id);
+                this.script_lib.create_monster_with_params(group_id, config_id, Some(table_to_vector(&pos_table)), Some(table_to_vector(&rot_table)), Vec::new());
+                Ok(0)
             },
         );
 
@@ -841,17 +882,20 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "CreateMonsterWithGlobalValue",
-            |_, _this, (_ctx, _config_id, _value_table): (Table, u32, Table)| {
-                tracing::debug!("CreateMonsterWithGlobalValue called");
-                Ok(-1)
+            |_, this, (ctx, config_id, value_table): (Table, u32, Table)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                tracing::debug!("[ScriptLib] CreateMonsterWithGlobalValue group={} config={}", group_id, config_id);
+                this.script_lib.create_monster_with_params(group_id, config_id, None, None, table_to_global_values(&value_table));
+                Ok(0)
             },
         );
 
         methods.add_method(
             "CreateMonstersFromMonsterPool",
-            |_, _this, (_ctx, _drop_tag): (Table, String)| {
-                tracing::debug!("CreateMonstersFromMonsterPool called");
-                Ok(-1)
+            |_, this, (ctx, tag): (Table, String)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                this.script_lib.create_monsters_from_monster_pool(group_id, tag);
+                Ok(0)
             },
         );
 
@@ -920,17 +964,21 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "DelPlayerGroupVisionType",
-            |_, _this, (_ctx, _uid_list, _type_list): (Table, Table, Table)| {
+            |_, this, (_ctx, uid_list, type_list): (Table, Table, Table)| {
                 tracing::debug!("DelPlayerGroupVisionType called");
-                Ok(-1)
+                let uid_list: Vec<u32> = uid_list.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                let vision_type_list: Vec<u32> = type_list.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                this.script_lib.del_player_group_vision_type(uid_list, vision_type_list);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "DelSceneTag",
-            |_, _this, (_ctx, _scene_id, _scene_tag_id): (Table, u32, u32)| {
+            |_, this, (_ctx, scene_id, scene_tag_id): (Table, u32, u32)| {
                 tracing::debug!("DelSceneTag called");
-                Ok(-1)
+                this.script_lib.del_scene_tag(scene_id, scene_tag_id);
+                Ok(0)
             },
         );
 
@@ -1004,8 +1052,9 @@ impl UserData for LuaScriptLibHandle {
             },
         );
 
-        methods.add_method("EndAllTimeAxis", |_, this, _ctx: Table| {
-            this.script_lib.end_all_time_axis();
+        methods.add_method("EndAllTimeAxis", |_, this, ctx: Table| {
+            let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+            this.script_lib.end_all_time_axis(group_id);
             Ok(0)
         });
 
@@ -1019,17 +1068,17 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "EndMonsterTide",
-            |_, _this, (_ctx, _group_id, _tide_index, _end_type): (Table, u32, u32, u32)| {
-                tracing::debug!("EndMonsterTide called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index, end_type): (Table, u32, u32, u32)| {
+                this.script_lib.end_monster_tide(group_id, tide_index, end_type);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "EndPoolMonsterTide",
-            |_, _this, (_ctx, _group_id, _tide_index): (Table, u32, u32)| {
-                tracing::debug!("EndPoolMonsterTide called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index): (Table, u32, u32)| {
+                this.script_lib.end_monster_tide(group_id, tide_index, 0);
+                Ok(0)
             },
         );
 
@@ -1049,8 +1098,9 @@ impl UserData for LuaScriptLibHandle {
             },
         );
 
-        methods.add_method("EndTimeAxis", |_, this, (_ctx, key): (Table, String)| {
-            this.script_lib.end_time_axis(&key);
+        methods.add_method("EndTimeAxis", |_, this, (ctx, key): (Table, String)| {
+            let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+            this.script_lib.end_time_axis(group_id, &key);
             Ok(0)
         });
 
@@ -1208,9 +1258,10 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "ForbidPlayerRegionVision",
-            |_, _this, (_ctx, _uid): (Table, u32)| {
+            |_, this, (_ctx, uid): (Table, u32)| {
                 tracing::debug!("ForbidPlayerRegionVision called");
-                Ok(-1)
+                this.script_lib.forbid_player_region_vision(uid);
+                Ok(0)
             },
         );
 
@@ -1422,9 +1473,10 @@ impl UserData for LuaScriptLibHandle {
             },
         );
 
-        methods.add_method("GetCurTriggerCount", |_, _this, _ctx: Table| {
+        methods.add_method("GetCurTriggerCount", |_, _this, ctx: Table| {
             tracing::debug!("GetCurTriggerCount called");
-            Ok(-1)
+            let trigger_count: i32 = ctx.get("trigger_count").unwrap_or(0);
+            Ok(trigger_count)
         });
 
         methods.add_method(
@@ -1599,9 +1651,10 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "GetGadgetIdByEntityId",
-            |_, _this, (_ctx, _entity_id): (Table, u32)| {
+            |_, this, (ctx, entity_id): (Table, u32)| {
                 tracing::debug!("GetGadgetIdByEntityId called");
-                Ok(-1)
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                Ok(this.script_lib.get_gadget_id_by_entity_id(uid, entity_id))
             },
         );
 
@@ -1754,17 +1807,16 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "GetGroupLogicStateValue",
-            |_, _this, (_ctx, _name): (Table, String)| {
-                tracing::debug!("GetGroupLogicStateValue called");
-                Ok(-1)
+            |_, this, (ctx, name): (Table, String)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                Ok(this.script_lib.get_group_logic_state_value(group_id, &name))
             },
         );
 
         methods.add_method(
             "GetGroupLogicStateValueByGroup",
-            |_, _this, (_ctx, _name, _group_id): (Table, String, u32)| {
-                tracing::debug!("GetGroupLogicStateValueByGroup called");
-                Ok(-1)
+            |_, this, (_ctx, name, group_id): (Table, String, u32)| {
+                Ok(this.script_lib.get_group_logic_state_value(group_id, &name))
             },
         );
 
@@ -1799,9 +1851,13 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "GetGroupTempValue",
-            |_, _this, (_ctx, _key, _param_table): (Table, String, Table)| {
-                tracing::debug!("GetGroupTempValue called");
-                Ok(-1)
+            |_, this, (ctx, key, param_table): (Table, String, Table)| {
+                let group_id: u32 = param_table
+                    .get::<Option<u32>>("group_id")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(ctx.get("group_id").unwrap_or(0));
+                Ok(this.script_lib.get_group_temp_value(group_id, &key))
             },
         );
 
@@ -1972,6 +2028,72 @@ impl UserData for LuaScriptLibHandle {
             },
         );
 
+        methods.add_method(
+            "GetMonsterAggroList",
+            |_, this, (ctx, group_id, config_id): (Table, u32, u32)| {
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                let uid_list = this
+                    .script_lib
+                    .get_monster_aggro_list(uid, group_id, config_id);
+                tracing::debug!(
+                    "GetMonsterAggroList: group_id={}, config_id={}, uid_list={:?}",
+                    group_id,
+                    config_id,
+                    uid_list
+                );
+                Ok(uid_list)
+            },
+        );
+
+        methods.add_method(
+            "GetMonsterAggroTarget",
+            |_, this, (ctx, group_id, config_id): (Table, u32, u32)| {
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                let target_uid = this
+                    .script_lib
+                    .get_monster_aggro_target(uid, group_id, config_id);
+                tracing::debug!(
+                    "GetMonsterAggroTarget: group_id={}, config_id={}, target_uid={}",
+                    group_id,
+                    config_id,
+                    target_uid
+                );
+                Ok(target_uid)
+            },
+        );
+
+        methods.add_method(
+            "GetMonsterAiState",
+            |_, this, (ctx, group_id, config_id): (Table, u32, u32)| {
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                let state = this
+                    .script_lib
+                    .get_monster_ai_state(uid, group_id, config_id);
+                tracing::debug!(
+                    "GetMonsterAiState: group_id={}, config_id={}, state={}",
+                    group_id,
+                    config_id,
+                    state
+                );
+                Ok(state)
+            },
+        );
+
+        methods.add_method(
+            "GetMonsterPose",
+            |_, this, (ctx, group_id, config_id): (Table, u32, u32)| {
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                let pose_id = this.script_lib.get_monster_pose(uid, group_id, config_id);
+                tracing::debug!(
+                    "GetMonsterPose: group_id={}, config_id={}, pose_id={}",
+                    group_id,
+                    config_id,
+                    pose_id
+                );
+                Ok(pose_id)
+            },
+        );
+
         methods.add_method(
             "GetMonsterConfigId",
             |_, _this, (_ctx, _param_table): (Table, Table)| {
@@ -1982,25 +2104,28 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "GetMonsterHp",
-            |_, _this, (_ctx, _group_id, _config_id): (Table, u32, u32)| {
+            |_, this, (ctx, group_id, config_id): (Table, u32, u32)| {
                 tracing::debug!("GetMonsterHp called");
-                Ok(-1)
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                Ok(this.script_lib.get_monster_hp(uid, group_id, config_id))
             },
         );
 
         methods.add_method(
             "GetMonsterHpPercent",
-            |_, _this, (_ctx, _group_id, _config_id): (Table, u32, u32)| {
+            |_, this, (ctx, group_id, config_id): (Table, u32, u32)| {
                 tracing::debug!("GetMonsterHpPercent called");
-                Ok(-1)
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                Ok(this.script_lib.get_monster_hp_percent(uid, group_id, config_id))
             },
         );
 
         methods.add_method(
             "GetMonsterIdByEntityId",
-            |_, _this, (_ctx, _entity_id): (Table, u32)| {
+            |_, this, (ctx, entity_id): (Table, u32)| {
                 tracing::debug!("GetMonsterIdByEntityId called");
-                Ok(-1)
+                let uid: u32 = ctx.get("uid").unwrap_or(0);
+                Ok(this.script_lib.get_monster_id_by_entity_id(uid, entity_id))
             },
         );
 
@@ -2326,17 +2451,17 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "GoBackGroupSuite",
-            |_, _this, (_ctx, _group_id): (Table, u32)| {
-                tracing::debug!("GoBackGroupSuite called");
-                Ok(-1)
+            |_, this, (_ctx, group_id): (Table, u32)| {
+                this.script_lib.go_back_group_suite(group_id);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "GoToFlowSuite",
-            |_, _this, (_ctx, _group_id, _suite_index): (Table, u32, u32)| {
-                tracing::debug!("GoToFlowSuite called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, suite_index): (Table, u32, u32)| {
+                this.script_lib.go_to_flow_suite(group_id, suite_index);
+                Ok(0)
             },
         );
 
@@ -2350,9 +2475,11 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "HideScenePoint",
-            |_, _this, (_ctx, _point_id): (Table, u32)| {
+            |_, this, (ctx, point_id): (Table, u32)| {
                 tracing::debug!("HideScenePoint called");
-                Ok(-1)
+                let scene_id: u32 = ctx.get("scene_id").unwrap_or(0);
+                this.script_lib.hide_scene_point(scene_id, point_id);
+                Ok(0)
             },
         );
 
@@ -2396,9 +2523,10 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "InitTimeAxis",
-            |_, this, (_ctx, key, delays_table, should_loop): (Table, String, Table, bool)| {
+            |_, this, (ctx, key, delays_table, should_loop): (Table, String, Table, bool)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
                 let delays: Vec<f64> = delays_table.sequence_values::<f64>().filter_map(|v| v.ok()).collect();
-                this.script_lib.init_time_axis(&key, delays, should_loop);
+                this.script_lib.init_time_axis(group_id, &key, delays, should_loop);
                 Ok(0)
             },
         );
@@ -2477,9 +2605,8 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "IsGroupDead",
-            |_, _this, (_ctx, _group_id): (Table, u32)| {
-                tracing::debug!("IsGroupDead called");
-                Ok(false)
+            |_, this, (_ctx, group_id): (Table, u32)| {
+                Ok(this.script_lib.is_group_dead(group_id))
             },
         );
 
@@ -2561,17 +2688,17 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "KillExtraFlowSuite",
-            |_, _this, (_ctx, _group_id, _suite_index, _policy): (Table, u32, u32, u32)| {
-                tracing::debug!("KillExtraFlowSuite called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, suite_index, policy): (Table, u32, u32, u32)| {
+                this.script_lib.kill_extra_flow_suite(group_id, suite_index, policy);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "KillExtraGroupSuite",
-            |_, _this, (_ctx, _group_id, _suite_index): (Table, u32, u32)| {
-                tracing::debug!("KillExtraGroupSuite called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, suite_index): (Table, u32, u32)| {
+                this.script_lib.kill_extra_group_suite(group_id, suite_index);
+                Ok(0)
             },
         );
 
@@ -2587,17 +2714,19 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "KillMonsterTide",
-            |_, _this, (_ctx, _group_id, _tide_index): (Table, u32, u32)| {
-                tracing::debug!("KillMonsterTide called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index): (Table, u32, u32)| {
+                this.script_lib.kill_monster_tide(group_id, tide_index);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "LockCurScenePoint",
-            |_, _this, (_ctx, _point_id): (Table, u32)| {
+            |_, this, (ctx, point_id): (Table, u32)| {
                 tracing::debug!("LockCurScenePoint called");
-                Ok(-1)
+                let scene_id: u32 = ctx.get("scene_id").unwrap_or(0);
+                this.script_lib.lock_scene_point(scene_id, point_id);
+                Ok(0)
             },
         );
 
@@ -2609,9 +2738,11 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "LockMonsterHp",
-            |_, _this, (_ctx, _config_id): (Table, u32)| {
+            |_, this, (ctx, config_id): (Table, u32)| {
                 tracing::debug!("LockMonsterHp called");
-                Ok(-1)
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                this.script_lib.lock_monster_hp(group_id, config_id);
+                Ok(0)
             },
         );
 
@@ -2713,33 +2844,37 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "PauseAutoMonsterTide",
-            |_, _this, (_ctx, _group_id, _tide_index): (Table, u32, u32)| {
-                tracing::debug!("PauseAutoMonsterTide called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index): (Table, u32, u32)| {
+                this.script_lib.pause_monster_tide(group_id, tide_index);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "PauseAutoPoolMonsterTide",
-            |_, _this, (_ctx, _group_id, _tide_index): (Table, u32, u32)| {
-                tracing::debug!("PauseAutoPoolMonsterTide called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index): (Table, u32, u32)| {
+                this.script_lib.pause_monster_tide(group_id, tide_index);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "PauseChallenge",
-            |_, _this, (_ctx, _source_name): (Table, u32)| {
+            |_, this, (ctx, challenge_index): (Table, u32)| {
                 tracing::debug!("PauseChallenge called");
-                Ok(-1)
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                this.script_lib.pause_challenge(group_id, challenge_index);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "PauseTimeAxis",
-            |_, _this, (_ctx, _key): (Table, String)| {
+            |_, this, (ctx, key): (Table, String)| {
                 tracing::debug!("PauseTimeAxis called");
-                Ok(-1)
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                this.script_lib.pause_time_axis(group_id, &key);
+                Ok(0)
             },
         );
 
@@ -2799,9 +2934,9 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "RecoverDeadGroup",
-            |_, _this, (_ctx, _group_id): (Table, u32)| {
-                tracing::debug!("RecoverDeadGroup called");
-                Ok(-1)
+            |_, this, (_ctx, group_id): (Table, u32)| {
+                this.script_lib.recover_dead_group(group_id);
+                Ok(0)
             },
         );
 
@@ -2843,17 +2978,18 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "RemoveEntityByConfigId",
-            |_, _this, (_ctx, _group_id, _entity_type, _config_id): (Table, u32, u32, u32)| {
-                tracing::debug!("RemoveEntityByConfigId called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, entity_type, config_id): (Table, u32, u32, u32)| {
+                tracing::debug!("[ScriptLib] RemoveEntityByConfigId group={} type={} config={}", group_id, entity_type, config_id);
+                this.script_lib.remove_entity_by_config_id(group_id, entity_type, config_id);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "RemoveExtraFlowSuite",
-            |_, _this, (_ctx, _group_id, _suite_index, _policy): (Table, u32, u32, u32)| {
-                tracing::debug!("RemoveExtraFlowSuite called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, suite_index, policy): (Table, u32, u32, u32)| {
+                this.script_lib.remove_extra_flow_suite(group_id, suite_index, policy);
+                Ok(0)
             },
         );
 
@@ -2902,17 +3038,18 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "ResumeAutoPoolMonsterTide",
-            |_, _this, (_ctx, _group_id, _tide_index): (Table, u32, u32)| {
-                tracing::debug!("ResumeAutoPoolMonsterTide called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, tide_index): (Table, u32, u32)| {
+                this.script_lib.continue_monster_tide(group_id, tide_index);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "RevertPlayerRegionVision",
-            |_, _this, (_ctx, _uid): (Table, u32)| {
+            |_, this, (_ctx, uid): (Table, u32)| {
                 tracing::debug!("RevertPlayerRegionVision called");
-                Ok(-1)
+                this.script_lib.revert_player_region_vision(uid);
+                Ok(0)
             },
         );
 
@@ -3074,9 +3211,9 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "SetFlowSuite",
-            |_, _this, (_ctx, _group_id, _suite_index): (Table, u32, u32)| {
-                tracing::debug!("SetFlowSuite called");
-                Ok(-1)
+            |_, this, (_ctx, group_id, suite_index): (Table, u32, u32)| {
+                this.script_lib.set_flow_suite(group_id, suite_index);
+                Ok(0)
             },
         );
 
@@ -3090,9 +3227,10 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "SetGadgetHp",
-            |_, _this, (_ctx, _group_id, _config_id, _hp_percent): (Table, u32, u32, f32)| {
+            |_, this, (_ctx, group_id, config_id, hp_percent): (Table, u32, u32, f32)| {
                 tracing::debug!("SetGadgetHp called");
-                Ok(-1)
+                this.script_lib.set_gadget_hp(group_id, config_id, hp_percent);
+                Ok(0)
             },
         );
 
@@ -3187,9 +3325,9 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "SetGroupDead",
-            |_, _this, (_ctx, _group_id): (Table, u32)| {
-                tracing::debug!("SetGroupDead called");
-                Ok(-1)
+            |_, this, (_ctx, group_id): (Table, u32)| {
+                this.script_lib.set_group_dead(group_id);
+                Ok(0)
             },
         );
 
@@ -3203,17 +3341,16 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "SetGroupLogicStateValue",
-            |_, _this, (_ctx, _name, _value): (Table, String, i32)| {
-                tracing::debug!("SetGroupLogicStateValue called");
-                Ok(-1)
+            |_, this, (ctx, name, value): (Table, String, i32)| {
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                Ok(this.script_lib.set_group_logic_state_value(group_id, &name, value))
             },
         );
 
         methods.add_method(
             "SetGroupLogicStateValueByGroup",
-            |_, _this, (_ctx, _name, _value, _group_id): (Table, String, i32, u32)| {
-                tracing::debug!("SetGroupLogicStateValueByGroup called");
-                Ok(-1)
+            |_, this, (_ctx, name, value, group_id): (Table, String, i32, u32)| {
+                Ok(this.script_lib.set_group_logic_state_value(group_id, &name, value))
             },
         );
 
@@ -3227,9 +3364,13 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "SetGroupTempValue",
-            |_, _this, (_ctx, _key, _value, _param_table): (Table, String, i32, Table)| {
-                tracing::debug!("SetGroupTempValue called");
-                Ok(-1)
+            |_, this, (ctx, key, value, param_table): (Table, String, i32, Table)| {
+                let group_id: u32 = param_table
+                    .get::<Option<u32>>("group_id")
+                    .ok()
+                    .flatten()
+                    .unwrap_or(ctx.get("group_id").unwrap_or(0));
+                Ok(this.script_lib.set_group_temp_value(group_id, &key, value))
             },
         );
 
@@ -3344,9 +3485,10 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "SetMonsterHp",
-            |_, _this, (_ctx, _group_id, _config_id, _hp_percent): (Table, u32, u32, f32)| {
+            |_, this, (_ctx, group_id, config_id, hp_percent): (Table, u32, u32, f32)| {
                 tracing::debug!("SetMonsterHp called");
-                Ok(-1)
+                this.script_lib.set_monster_hp(group_id, config_id, hp_percent);
+                Ok(0)
             },
         );
 
@@ -3440,9 +3582,12 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "SetPlayerGroupVisionType",
-            |_, _this, (_ctx, _uid_list, _type_list): (Table, Table, Table)| {
+            |_, this, (_ctx, uid_list, type_list): (Table, Table, Table)| {
                 tracing::debug!("SetPlayerGroupVisionType called");
-                Ok(-1)
+                let uid_list: Vec<u32> = uid_list.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                let vision_type_list: Vec<u32> = type_list.sequence_values::<u32>().filter_map(|v| v.ok()).collect();
+                this.script_lib.set_player_group_vision_type(uid_list, vision_type_list);
+                Ok(0)
             },
         );
 
@@ -3893,9 +4038,11 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "UnhideScenePoint",
-            |_, _this, (_ctx, _point_id): (Table, u32)| {
+            |_, this, (ctx, point_id): (Table, u32)| {
                 tracing::debug!("UnhideScenePoint called");
-                Ok(-1)
+                let scene_id: u32 = ctx.get("scene_id").unwrap_or(0);
+                this.script_lib.unhide_scene_point(scene_id, point_id);
+                Ok(0)
             },
         );
 
@@ -3918,17 +4065,21 @@ impl UserData for LuaScriptLibHandle {
 
         methods.add_method(
             "UnlockMonsterHp",
-            |_, _this, (_ctx, _config_id): (Table, u32)| {
+            |_, this, (ctx, config_id): (Table, u32)| {
                 tracing::debug!("UnlockMonsterHp called");
-                Ok(-1)
+                let group_id: u32 = ctx.get("group_id").unwrap_or(0);
+                this.script_lib.unlock_monster_hp(group_id, config_id);
+                Ok(0)
             },
         );
 
         methods.add_method(
             "UnlockScenePoint",
-            |_, _this, (_ctx, _point_id): (Table, u32)| {
+            |_, this, (ctx, point_id): (Table, u32)| {
                 tracing::debug!("UnlockScenePoint called");
-                Ok(-1)
+                let scene_id: u32 = ctx.get("scene_id").unwrap_or(0);
+                this.script_lib.unlock_scene_point(scene_id, point_id);
+                Ok(0)
             },
         );
 
@@ -4115,3 +4266,16 @@ impl UserData for LuaScriptLibHandle {
         );
     }
 }
+
+fn table_to_vector(table: &Table) -> (f32, f32, f32) {
+    (
+        table.get("x").unwrap_or(0.0),
+        table.get("y").unwrap_or(0.0),
+        table.get("z").unwrap_or(0.0),
+    )
+}
+
+// lua {SGV_Name = value} tables
+fn table_to_global_values(table: &Table) -> Vec<(String, f32)> {
+    table.pairs::<String, f32>().flatten().collect()
+}
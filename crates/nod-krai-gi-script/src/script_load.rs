@@ -6,12 +6,23 @@ use nod_krai_gi_data::scene::script_cache::{load_scene_group_from_cache, scene_g
 use nod_krai_gi_data::scene::{EventType, LuaContext};
 use std::collections::HashMap;
 
+const SUITE_HISTORY_LIMIT: usize = 16;
+
 pub struct CompiledTrigger {
     pub name: String,
+    pub source: String,
+    // 0 means the trigger never runs out
+    pub trigger_count: u32,
     pub condition: Option<Function>,
     pub action: Option<Function>,
 }
 
+#[derive(Clone)]
+pub struct SuiteSnapshot {
+    pub active_suites: Vec<u32>,
+    pub flow_suites: Vec<u32>,
+}
+
 pub enum GroupLoadState {
     Unloaded,
     Loading,
@@ -24,7 +35,14 @@ pub struct SceneGroupRuntime {
     pub context: LuaContext,
     pub triggers_by_event: HashMap<EventType, Vec<CompiledTrigger>>,
     pub active_suites: Vec<u32>,
+    // flow suites are switched independently from the group suites above
+    pub flow_suites: Vec<u32>,
+    // snapshots taken by GoToGroupSuite/GoToFlowSuite, popped by GoBackGroupSuite
+    pub suite_history: Vec<SuiteSnapshot>,
     pub active_trigger_names: Vec<String>,
+    // triggers registered at runtime by CreateGroupTrigger, kept across suite switches
+    pub extra_trigger_names: Vec<String>,
+    pub trigger_fire_counts: HashMap<String, u32>,
     pub variables: HashMap<String, i32>,
 }
 
@@ -81,7 +99,11 @@ impl SceneGroupRuntime {
                 },
                 triggers_by_event: HashMap::new(),
                 active_suites: vec![1],
+                flow_suites: Vec::new(),
+                suite_history: Vec::new(),
                 active_trigger_names: Vec::new(),
+                extra_trigger_names: Vec::new(),
+                trigger_fire_counts: HashMap::new(),
                 variables,
             });
         }
@@ -89,9 +111,6 @@ impl SceneGroupRuntime {
         let mut triggers_by_event: HashMap<EventType, Vec<CompiledTrigger>> = HashMap::new();
 
         for trig in &data.triggers {
-            if trig.trigger_count == Some(0) {
-                continue;
-            }
             let cond = (!trig.condition.is_empty())
                 .then_some(trig.condition.as_str())
                 .and_then(|name| env.get::<Function>(name).ok());
@@ -106,6 +125,9 @@ impl SceneGroupRuntime {
                     .or_default()
                     .push(CompiledTrigger {
                         name: trig.name.clone(),
+                        source: trig.source.clone(),
+                        // triggers without a trigger_count only fire once
+                        trigger_count: trig.trigger_count.unwrap_or(1).max(0) as u32,
                         condition: cond,
                         action: Some(action),
                     });
@@ -135,7 +157,11 @@ impl SceneGroupRuntime {
             },
             triggers_by_event,
             active_suites: vec![1],
+            flow_suites: Vec::new(),
+            suite_history: Vec::new(),
             active_trigger_names: Vec::new(),
+            extra_trigger_names: Vec::new(),
+            trigger_fire_counts: HashMap::new(),
             variables,
         };
 
@@ -143,10 +169,59 @@ impl SceneGroupRuntime {
         Some(rt)
     }
 
+    pub fn is_suite_active(&self, suite_id: u32) -> bool {
+        self.active_suites.contains(&suite_id) || self.flow_suites.contains(&suite_id)
+    }
+
+    pub fn all_active_suites(&self) -> Vec<u32> {
+        let mut suites = self.active_suites.clone();
+        for suite_id in &self.flow_suites {
+            if !suites.contains(suite_id) {
+                suites.push(*suite_id);
+            }
+        }
+        suites
+    }
+
+    pub fn push_suite_history(&mut self) {
+        if self.suite_history.len() >= SUITE_HISTORY_LIMIT {
+            self.suite_history.remove(0);
+        }
+        self.suite_history.push(SuiteSnapshot {
+            active_suites: self.active_suites.clone(),
+            flow_suites: self.flow_suites.clone(),
+        });
+    }
+
+    // replaces both suite layers, returns the suites whose entities should be (despawned, spawned)
+    pub fn switch_suites(
+        &mut self,
+        active_suites: Vec<u32>,
+        flow_suites: Vec<u32>,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let old_suites = self.all_active_suites();
+        self.active_suites = active_suites;
+        self.flow_suites = flow_suites;
+        let new_suites = self.all_active_suites();
+        self.recompute_active_triggers();
+
+        let removed = old_suites
+            .iter()
+            .filter(|suite_id| !new_suites.contains(suite_id))
+            .copied()
+            .collect();
+        let added = new_suites
+            .iter()
+            .filter(|suite_id| !old_suites.contains(suite_id))
+            .copied()
+            .collect();
+        (removed, added)
+    }
+
     pub fn recompute_active_triggers(&mut self) {
-        let mut names = Vec::new();
+        let mut names = self.extra_trigger_names.clone();
 
-        for suite_id in &self.active_suites {
+        for suite_id in &self.all_active_suites() {
             let idx = (*suite_id as usize).saturating_sub(1);
             if let Some(suite) = self.data.suites.get(idx) {
                 for name in &suite.triggers {
@@ -157,6 +232,10 @@ impl SceneGroupRuntime {
             }
         }
 
-        self.active_trigger_names = names;
+        // triggers that get registered again start counting from zero
+        let old_names = std::mem::replace(&mut self.active_trigger_names, names);
+        let active_trigger_names = &self.active_trigger_names;
+        self.trigger_fire_counts
+            .retain(|name, _| old_names.contains(name) && active_trigger_names.contains(name));
     }
 }
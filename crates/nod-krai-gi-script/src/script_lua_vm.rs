@@ -1,25 +1,195 @@
 use crate::script_lib::BevyScriptLib;
 use crate::script_lib_handle::LuaScriptLibHandle;
 use bevy_ecs::prelude::*;
-use mlua::Lua;
-use nod_krai_gi_data::scene::script_cache::SCENE_LUA_VM;
-use std::sync::Arc;
+use mlua::{ChunkMode, HookTriggers, Lua, VmState};
+use nod_krai_gi_data::scene::script_cache::{compile_lua_chunk, new_scene_lua_state};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+const LUA_MEMORY_LIMIT: usize = 128 * 1024 * 1024;
+const LUA_INSTRUCTION_LIMIT_PER_TICK: u64 = 20_000_000;
+const LUA_HOOK_INSTRUCTION_INTERVAL: u32 = 1000;
+
+pub struct GadgetScript {
+    // bumped every time the script is recompiled by the hot reload watcher
+    pub generation: u64,
+    pub bytecode: Vec<u8>,
+}
+
+// precompiled assets/lua/gadget scripts, shared read-only by every world's lua state
+static GADGET_LUA_BYTECODE: OnceLock<RwLock<HashMap<String, Arc<GadgetScript>>>> = OnceLock::new();
+static GADGET_LUA_GENERATION: AtomicU64 = AtomicU64::new(0);
 
 #[derive(Resource, Clone)]
 pub struct LuaRuntime {
     pub lua: Lua,
+    pub instruction_budget: Arc<AtomicU64>,
+    pub gadget_generation: u64,
 }
 
 impl LuaRuntime {
     pub fn new(script_lib: Arc<BevyScriptLib>, protocol_version: String) -> Self {
-        let lua = get_lua(script_lib, protocol_version);
+        let instruction_budget = Arc::new(AtomicU64::new(LUA_INSTRUCTION_LIMIT_PER_TICK));
+        let gadget_generation = GADGET_LUA_GENERATION.load(Ordering::Acquire);
+        let lua = get_lua(script_lib, protocol_version, instruction_budget.clone());
+
+        Self {
+            lua,
+            instruction_budget,
+            gadget_generation,
+        }
+    }
+
+    pub fn reset_instruction_budget(&self) {
+        self.instruction_budget
+            .store(LUA_INSTRUCTION_LIMIT_PER_TICK, Ordering::Relaxed);
+    }
+}
+
+pub fn reset_lua_instruction_budget(lua_vm: Res<LuaRuntime>) {
+    lua_vm.reset_instruction_budget();
+}
+
+fn get_gadget_scripts() -> &'static RwLock<HashMap<String, Arc<GadgetScript>>> {
+    GADGET_LUA_BYTECODE.get_or_init(|| {
+        let lua = Lua::new();
+        let mut scripts = HashMap::new();
+
+        let gadget_dir = std::path::Path::new("./assets/lua/gadget");
+        let Ok(entries) = std::fs::read_dir(gadget_dir) else {
+            tracing::error!("Failed to read gadget lua directory");
+            return RwLock::new(scripts);
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("lua") {
+                continue;
+            }
+            let Ok(code) = common::string_util::read_utf8_no_bom(&path) else {
+                continue;
+            };
+            let script_name = path.file_name().unwrap().to_string_lossy().to_string();
+            match compile_lua_chunk(&lua, &script_name, &code) {
+                Ok(bytecode) => {
+                    scripts.insert(
+                        script_name,
+                        Arc::new(GadgetScript {
+                            generation: 0,
+                            bytecode,
+                        }),
+                    );
+                }
+                Err(err) => tracing::error!("compile gadget lua {} fail: {}", script_name, err),
+            }
+        }
+
+        RwLock::new(scripts)
+    })
+}
+
+fn get_gadget_scripts_since(generation: u64) -> Vec<(String, Arc<GadgetScript>)> {
+    match get_gadget_scripts().read() {
+        Ok(scripts) => scripts
+            .iter()
+            .filter(|(_, script)| script.generation >= generation)
+            .map(|(name, script)| (name.clone(), Arc::clone(script)))
+            .collect(),
+        Err(_) => {
+            tracing::error!("Failed to lock gadget lua scripts");
+            vec![]
+        }
+    }
+}
 
-        Self { lua }
+// recompiles a gadget script, live worlds pick it up in sync_gadget_lua_scripts
+pub fn reload_gadget_script(path: &std::path::Path) -> Result<(), String> {
+    let code = common::string_util::read_utf8_no_bom(path).map_err(|err| err.to_string())?;
+    let script_name = path
+        .file_name()
+        .ok_or("invalid gadget lua path")?
+        .to_string_lossy()
+        .to_string();
+
+    let bytecode =
+        compile_lua_chunk(&Lua::new(), &script_name, &code).map_err(|err| err.to_string())?;
+
+    let mut scripts = get_gadget_scripts()
+        .write()
+        .map_err(|_| "Failed to lock gadget lua scripts".to_string())?;
+    let generation = GADGET_LUA_GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
+    scripts.insert(
+        script_name,
+        Arc::new(GadgetScript {
+            generation,
+            bytecode,
+        }),
+    );
+    Ok(())
+}
+
+pub fn sync_gadget_lua_scripts(mut lua_vm: ResMut<LuaRuntime>) {
+    let generation = GADGET_LUA_GENERATION.load(Ordering::Acquire);
+    if generation == lua_vm.gadget_generation {
+        return;
+    }
+
+    for (script_name, script) in get_gadget_scripts_since(lua_vm.gadget_generation + 1) {
+        tracing::info!("Reloading gadget lua: {}", script_name);
+        exec_gadget_script(&lua_vm.lua, &script_name, &script.bytecode);
+    }
+    lua_vm.gadget_generation = generation;
+}
+
+fn exec_gadget_script(lua: &Lua, script_name: &str, bytecode: &[u8]) {
+    let globals = lua.globals();
+
+    let env = lua.create_table().unwrap();
+    let mt = lua.create_table().unwrap();
+    mt.set("__index", globals.clone()).unwrap();
+    env.set_metatable(Some(mt)).unwrap();
+
+    let chunk = lua
+        .load(bytecode)
+        .set_name(script_name)
+        .set_mode(ChunkMode::Binary);
+    if let Err(err) = chunk.set_environment(env.clone()).exec() {
+        tracing::debug!("gadget lua {} exec fail {}", script_name, err);
+        return;
     }
+
+    globals.set(script_name, env).unwrap();
 }
 
-pub fn get_lua(script_lib: Arc<BevyScriptLib>, protocol_version: String) -> Lua {
-    let lua = SCENE_LUA_VM.get().unwrap().clone();
+pub fn get_lua(
+    script_lib: Arc<BevyScriptLib>,
+    protocol_version: String,
+    instruction_budget: Arc<AtomicU64>,
+) -> Lua {
+    let lua = new_scene_lua_state();
+
+    if let Err(err) = lua.set_memory_limit(LUA_MEMORY_LIMIT) {
+        tracing::debug!("lua set_memory_limit fail {}", err);
+    }
+
+    let result = lua.set_hook(
+        HookTriggers::new().every_nth_instruction(LUA_HOOK_INSTRUCTION_INTERVAL),
+        move |_, _| {
+            let remaining = instruction_budget.load(Ordering::Relaxed);
+            let interval = LUA_HOOK_INSTRUCTION_INTERVAL as u64;
+            if remaining < interval {
+                return Err(mlua::Error::RuntimeError(
+                    "lua instruction limit exceeded".to_string(),
+                ));
+            }
+            instruction_budget.store(remaining - interval, Ordering::Relaxed);
+            Ok(VmState::Continue)
+        },
+    );
+    if let Err(err) = result {
+        tracing::debug!("lua set_hook fail {}", err);
+    }
 
     let globals = lua.globals();
 
@@ -37,31 +207,9 @@ pub fn get_lua(script_lib: Arc<BevyScriptLib>, protocol_version: String) -> Lua
         }
     }
 
-    let gadget_dir = std::path::Path::new("./assets/lua/gadget");
-
-    if let Ok(entries) = std::fs::read_dir(gadget_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("lua") {
-                if let Ok(code) = common::string_util::read_utf8_no_bom(&path) {
-                    let code = code.replace("ScriptLib.", "ScriptLib:");
-                    let script_name = path.file_name().unwrap().to_string_lossy().to_string();
-                    tracing::debug!("Loading gadget lua: {}", script_name.clone());
-
-                    let env = lua.create_table().unwrap();
-                    let mt = lua.create_table().unwrap();
-                    mt.set("__index", globals.clone()).unwrap();
-                    env.set_metatable(Some(mt)).unwrap();
-
-                    let chunk = lua.load(&code).set_name(script_name.clone());
-                    chunk.set_environment(env.clone()).exec().unwrap();
-
-                    lua.globals().set(script_name.clone(), env).unwrap();
-                }
-            }
-        }
-    } else {
-        tracing::error!("Failed to read gadget lua directory");
+    for (script_name, script) in get_gadget_scripts_since(0) {
+        tracing::debug!("Loading gadget lua: {}", script_name);
+        exec_gadget_script(&lua, &script_name, &script.bytecode);
     }
 
     lua
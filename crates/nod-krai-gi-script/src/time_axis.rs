@@ -0,0 +1,213 @@
+use crate::script_load::GroupLoadState;
+use crate::SceneGroupRegistry;
+use bevy_ecs::prelude::*;
+use nod_krai_gi_data::scene::{EventType, LuaEvt, ScriptCommand};
+use nod_krai_gi_event::lua::{LuaSourceTriggerEvent, ScriptCommandEvent};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub struct TimeAxis {
+    // stage times counted from the start of the current cycle
+    delays: Vec<Duration>,
+    should_loop: bool,
+    next_stage: usize,
+    started_at: Instant,
+    // elapsed time frozen by PauseTimeAxis, the start is shifted by it on ContinueTimeAxis
+    paused_elapsed: Option<Duration>,
+}
+
+impl TimeAxis {
+    fn new(delays: &[f64], should_loop: bool, now: Instant) -> Option<Self> {
+        let mut delays: Vec<Duration> = delays
+            .iter()
+            .map(|delay| Duration::from_secs_f64(delay.max(0.0)))
+            .collect();
+        delays.sort();
+        let last_delay = *delays.last()?;
+
+        Some(Self {
+            delays,
+            // a zero length cycle would pass forever within one tick
+            should_loop: should_loop && !last_delay.is_zero(),
+            next_stage: 0,
+            started_at: now,
+            paused_elapsed: None,
+        })
+    }
+
+    fn pause(&mut self, now: Instant) {
+        if self.paused_elapsed.is_none() {
+            self.paused_elapsed = Some(now.duration_since(self.started_at));
+        }
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if let Some(elapsed) = self.paused_elapsed.take() {
+            self.started_at = now - elapsed;
+        }
+    }
+
+    // returns the stages (1-based) passed since the last poll and whether the axis is over
+    fn poll(&mut self, now: Instant) -> (Vec<u32>, bool) {
+        let mut passed_stages = Vec::new();
+        if self.paused_elapsed.is_some() {
+            return (passed_stages, false);
+        }
+
+        while let Some(delay) = self.delays.get(self.next_stage).copied() {
+            if now.duration_since(self.started_at) < delay {
+                break;
+            }
+            self.next_stage += 1;
+            passed_stages.push(self.next_stage as u32);
+
+            if self.next_stage >= self.delays.len() {
+                if !self.should_loop {
+                    return (passed_stages, true);
+                }
+                self.started_at += delay;
+                self.next_stage = 0;
+            }
+        }
+
+        (passed_stages, false)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TimeAxisManager {
+    axes: HashMap<(u32, String), TimeAxis>,
+}
+
+pub fn handle_time_axis_command(
+    mut ev_reader: MessageReader<ScriptCommandEvent>,
+    mut time_axis_manager: ResMut<TimeAxisManager>,
+) {
+    let now = Instant::now();
+
+    for event in ev_reader.read() {
+        match &event.command {
+            ScriptCommand::InitTimeAxis {
+                group_id,
+                identifier,
+                delays,
+                should_loop,
+            } => {
+                tracing::debug!(
+                    "[Script] [InitTimeAxis] group={} id={} delays={:?} loop={}",
+                    group_id,
+                    identifier,
+                    delays,
+                    should_loop
+                );
+                let Some(time_axis) = TimeAxis::new(delays, *should_loop, now) else {
+                    continue;
+                };
+                time_axis_manager
+                    .axes
+                    .insert((*group_id, identifier.clone()), time_axis);
+            }
+
+            ScriptCommand::EndTimeAxis {
+                group_id,
+                identifier,
+            } => {
+                tracing::debug!(
+                    "[Script] [EndTimeAxis] group={} id={}",
+                    group_id,
+                    identifier
+                );
+                time_axis_manager
+                    .axes
+                    .remove(&(*group_id, identifier.clone()));
+            }
+
+            ScriptCommand::EndAllTimeAxis { group_id } => {
+                tracing::debug!("[Script] [EndAllTimeAxis] group={}", group_id);
+                time_axis_manager
+                    .axes
+                    .retain(|(axis_group_id, _), _| axis_group_id != group_id);
+            }
+
+            ScriptCommand::PauseTimeAxis {
+                group_id,
+                identifier,
+            } => {
+                tracing::debug!(
+                    "[Script] [PauseTimeAxis] group={} id={}",
+                    group_id,
+                    identifier
+                );
+                if let Some(time_axis) = time_axis_manager
+                    .axes
+                    .get_mut(&(*group_id, identifier.clone()))
+                {
+                    time_axis.pause(now);
+                }
+            }
+
+            ScriptCommand::ContinueTimeAxis {
+                group_id,
+                identifier,
+            } => {
+                tracing::debug!(
+                    "[Script] [ContinueTimeAxis] group={} id={}",
+                    group_id,
+                    identifier
+                );
+                if let Some(time_axis) = time_axis_manager
+                    .axes
+                    .get_mut(&(*group_id, identifier.clone()))
+                {
+                    time_axis.resume(now);
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+pub fn time_axis_tick_system(
+    mut time_axis_manager: ResMut<TimeAxisManager>,
+    registry: NonSend<SceneGroupRegistry>,
+    mut lua_source_trigger_events: MessageWriter<LuaSourceTriggerEvent>,
+) {
+    if time_axis_manager.axes.is_empty() {
+        return;
+    }
+
+    let now = Instant::now();
+
+    // axes die with their group
+    time_axis_manager.axes.retain(|(group_id, _), _| {
+        matches!(
+            registry.groups.get(group_id),
+            Some(GroupLoadState::Loaded(_)) | Some(GroupLoadState::Loading)
+        )
+    });
+
+    time_axis_manager
+        .axes
+        .retain(|(group_id, identifier), time_axis| {
+            let (passed_stages, finished) = time_axis.poll(now);
+            for stage in passed_stages {
+                tracing::debug!(
+                    "[TimeAxis] group={} id={} stage={} passed",
+                    group_id,
+                    identifier,
+                    stage
+                );
+                lua_source_trigger_events.write(LuaSourceTriggerEvent {
+                    group_id: *group_id,
+                    event_type: EventType::EventTimeAxisPass,
+                    source_name: identifier.clone(),
+                    evt: LuaEvt {
+                        param1: stage,
+                        ..Default::default()
+                    },
+                });
+            }
+            !finished
+        });
+}
@@ -1,6 +1,7 @@
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use nod_krai_gi_event::command::*;
+use nod_krai_gi_event::scene::WorldLevel;
 use nod_krai_gi_message::event::ClientMessageEvent;
 use nod_krai_gi_message::output::MessageOutput;
 use nod_krai_gi_proto::retcode::Retcode;
@@ -19,6 +20,7 @@ pub fn handle_chat(
     message_output: Res<MessageOutput>,
     mut console_chat_event: MessageWriter<ConsoleChatReqEvent>,
     mut gm_notify_events: MessageWriter<ConsoleChatNotifyEvent>,
+    world_level: Res<WorldLevel>,
 ) {
     for message in events.read() {
         match message.message_name() {
@@ -32,7 +34,7 @@ pub fn handle_chat(
                             uid: 123,
                             nickname: "Console".to_string(),
                             level: 60,
-                            world_level: 9,
+                            world_level: world_level.0,
                             signature: "这是签名 this's signature".to_string(),
                             name_card_id: 210248,
                             profile_picture: Some(ProfilePicture {